@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+/// A result type for DoE sampling methods
+pub type Result<T> = std::result::Result<T, DoeError>;
+
+/// An error when building a sampling method
+#[derive(Error, Debug)]
+pub enum DoeError {
+    /// When the design space `xlimits` is malformed
+    #[error("Invalid xlimits: {0}")]
+    InvalidXlimits(String),
+}
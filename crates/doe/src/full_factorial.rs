@@ -1,3 +1,5 @@
+use crate::errors::Result;
+use crate::utils::check_xlimits;
 use crate::SamplingMethod;
 use linfa::Float;
 use ndarray::{s, Array, Array1, Array2, ArrayBase, Data, Ix2};
@@ -10,7 +12,6 @@ use serde::{Deserialize, Serialize};
 /// of levels for all components within the design space.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
-
 pub struct FullFactorial<F: Float> {
     /// Design space definition as
     /// The ith row is the [lower_bound, upper_bound] of xi, the ith component of a sample x
@@ -20,6 +21,8 @@ pub struct FullFactorial<F: Float> {
 impl<F: Float> FullFactorial<F> {
     /// Constructor given a design space given a (nx, 2) matrix \[\[lower bound, upper bound\], ...\]
     ///
+    /// **Panics** if xlimits number of columns is different from 2 or if a lower bound is greater than its upper bound.
+    ///
     /// ```
     /// use egobox_doe::FullFactorial;
     /// use ndarray::arr2;
@@ -27,12 +30,16 @@ impl<F: Float> FullFactorial<F> {
     /// let doe = FullFactorial::new(&arr2(&[[0.0, 1.0], [5.0, 10.0]]));
     /// ```
     pub fn new(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Self {
-        if xlimits.ncols() != 2 {
-            panic!("xlimits must have 2 columns (lower, upper)");
-        }
-        FullFactorial {
+        Self::try_new(xlimits).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [`FullFactorial::new`] but returns an error if xlimits number of columns
+    /// is different from 2 or if a lower bound is greater than its upper bound.
+    pub fn try_new(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Result<Self> {
+        check_xlimits(xlimits)?;
+        Ok(FullFactorial {
             xlimits: xlimits.to_owned(),
-        }
+        })
     }
 }
 
@@ -1,4 +1,5 @@
-use crate::utils::{cdist, pdist};
+use crate::errors::Result;
+use crate::utils::{cdist, check_xlimits, pdist};
 use crate::SamplingMethod;
 use linfa::Float;
 use ndarray::{s, Array, Array2, ArrayBase, Axis, Data, Ix2, ShapeBuilder};
@@ -53,6 +54,8 @@ pub struct Lhs<F: Float, R: Rng + Clone> {
 impl<F: Float> Lhs<F, Xoshiro256Plus> {
     /// Constructor given a design space given a (nx, 2) matrix \[\[lower bound, upper bound\], ...\]
     ///
+    /// **Panics** if xlimits number of columns is different from 2 or if a lower bound is greater than its upper bound.
+    ///
     /// ```
     /// use egobox_doe::Lhs;
     /// use ndarray::arr2;
@@ -62,6 +65,11 @@ impl<F: Float> Lhs<F, Xoshiro256Plus> {
     pub fn new(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Self {
         Self::new_with_rng(xlimits, Xoshiro256Plus::from_entropy())
     }
+
+    /// Same as [`Lhs::new`] but returns an error if xlimits is malformed
+    pub fn try_new(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Result<Self> {
+        Self::try_new_with_rng(xlimits, Xoshiro256Plus::from_entropy())
+    }
 }
 
 impl<F: Float, R: Rng + Clone> SamplingMethod<F> for Lhs<F, R> {
@@ -91,15 +99,21 @@ impl<F: Float, R: Rng + Clone> Lhs<F, R> {
     /// * `xlimits`: (nx, 2) matrix where nx is the dimension of the samples and the ith row
     ///   is the definition interval of the ith component of x.
     /// * `rng`: random generator used for [LhsKind::Classic] and [LhsKind::Optimized] LHS
+    ///
+    /// **Panics** if xlimits number of columns is different from 2 or if a lower bound is greater than its upper bound.
     pub fn new_with_rng(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>, rng: R) -> Self {
-        if xlimits.ncols() != 2 {
-            panic!("xlimits must have 2 columns (lower, upper)");
-        }
-        Lhs {
+        Self::try_new_with_rng(xlimits, rng).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [`Lhs::new_with_rng`] but returns an error if xlimits number of columns
+    /// is different from 2 or if a lower bound is greater than its upper bound.
+    pub fn try_new_with_rng(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>, rng: R) -> Result<Self> {
+        check_xlimits(xlimits)?;
+        Ok(Lhs {
             xlimits: xlimits.to_owned(),
             kind: LhsKind::default(),
             rng: Arc::new(RwLock::new(rng)),
-        }
+        })
     }
 
     /// Sets the kind of LHS
@@ -408,4 +422,28 @@ mod tests {
         let s2 = lhs2.sample(10);
         assert_abs_diff_ne!(s1, s2);
     }
+
+    #[test]
+    fn test_lhs_reversed_bounds() {
+        let err = Lhs::try_new(&array![[0., 1.], [10., 5.]]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid xlimits: xlimits row 1 must satisfy lower <= upper, got [10, 5]"
+        );
+    }
+
+    #[test]
+    fn test_lhs_wrong_xlimits_shape() {
+        let err = Lhs::try_new(&array![[0., 1., 2.], [5., 10., 15.]]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid xlimits: xlimits must have 2 columns (lower, upper), got shape [2, 3]"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid xlimits: xlimits row 0 must satisfy lower <= upper")]
+    fn test_lhs_new_panics_on_reversed_bounds() {
+        let _ = Lhs::new(&array![[1., 0.]]);
+    }
 }
@@ -31,12 +31,14 @@ This library contains three kinds of sampling methods:
 * [Random Sampling](crate::random::Random)
 
 */
+mod errors;
 mod full_factorial;
 mod lhs;
 mod random;
 mod traits;
 mod utils;
 
+pub use errors::*;
 pub use full_factorial::*;
 pub use lhs::*;
 pub use random::*;
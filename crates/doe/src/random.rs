@@ -1,3 +1,5 @@
+use crate::errors::Result;
+use crate::utils::check_xlimits;
 use crate::SamplingMethod;
 use linfa::Float;
 use ndarray::{Array, Array2, ArrayBase, Data, Ix2};
@@ -30,21 +32,30 @@ impl<F: Float> Random<F, Xoshiro256Plus> {
     pub fn new(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Self {
         Self::new_with_rng(xlimits, Xoshiro256Plus::from_entropy())
     }
+
+    /// Same as [`Random::new`] but returns an error if xlimits is malformed
+    pub fn try_new(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Result<Self> {
+        Self::try_new_with_rng(xlimits, Xoshiro256Plus::from_entropy())
+    }
 }
 
 impl<F: Float, R: Rng + Clone> Random<F, R> {
     /// Constructor given a design space given a (nx, 2) matrix \[\[lower bound, upper bound\], ...\]
     /// and a random generator for reproducibility
     ///
-    /// **Panics** if xlimits number of columns is different from 2.
+    /// **Panics** if xlimits number of columns is different from 2 or if a lower bound is greater than its upper bound.
     pub fn new_with_rng(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>, rng: R) -> Self {
-        if xlimits.ncols() != 2 {
-            panic!("xlimits must have 2 columns (lower, upper)");
-        }
-        Random {
+        Self::try_new_with_rng(xlimits, rng).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [`Random::new_with_rng`] but returns an error if xlimits number of columns
+    /// is different from 2 or if a lower bound is greater than its upper bound.
+    pub fn try_new_with_rng(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>, rng: R) -> Result<Self> {
+        check_xlimits(xlimits)?;
+        Ok(Random {
             xlimits: xlimits.to_owned(),
             rng,
-        }
+        })
     }
 
     /// Set random generator
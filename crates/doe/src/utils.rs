@@ -1,3 +1,4 @@
+use crate::errors::{DoeError, Result};
 use linfa::Float;
 use ndarray::{Array1, Array2, ArrayBase, Data, Ix2};
 use ndarray_stats::DeviationExt;
@@ -44,6 +45,28 @@ pub fn cdist<F: Float>(
     res
 }
 
+/// Checks `xlimits` is a (nx, 2) matrix \[\[lower bound, upper bound\], ...\] with lower <= upper
+///
+/// Returns an error describing the first malformed part otherwise.
+pub(crate) fn check_xlimits<F: Float>(xlimits: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Result<()> {
+    if xlimits.ncols() != 2 {
+        return Err(DoeError::InvalidXlimits(format!(
+            "xlimits must have 2 columns (lower, upper), got shape {:?}",
+            xlimits.shape()
+        )));
+    }
+    for (i, row) in xlimits.rows().into_iter().enumerate() {
+        // NaN bounds are rejected as well
+        if row[0].partial_cmp(&row[1]).map_or(true, |o| o.is_gt()) {
+            return Err(DoeError::InvalidXlimits(format!(
+                "xlimits row {} must satisfy lower <= upper, got [{}, {}]",
+                i, row[0], row[1]
+            )));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +102,7 @@
 use crate::errors::Result;
 use crate::gpmix::mixint::*;
 use crate::types::*;
+use crate::utils::StateCheckpoint;
 use crate::EgorConfig;
 use crate::EgorState;
 use crate::HotStartMode;
@@ -112,12 +113,15 @@ use argmin::core::observers::ObserverMode;
 
 use egobox_moe::GpMixtureParams;
 use log::info;
-use ndarray::{concatenate, Array2, ArrayBase, Axis, Data, Ix2};
+use ndarray::{concatenate, Array1, Array2, ArrayBase, Axis, Data, Ix2};
 use ndarray_rand::rand::SeedableRng;
 use rand_xoshiro::Xoshiro256Plus;
 
 use argmin::core::{observers::Observe, Error, Executor, State, KV};
 use serde::de::DeserializeOwned;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 /// Json filename for configuration
 pub const CONFIG_FILE: &str = "egor_config.json";
@@ -170,6 +174,9 @@ impl<O: GroupFunc> EgorBuilder<O> {
         Egor {
             fobj: ObjFunc::new(self.fobj),
             solver: EgorSolver::new(config, rng),
+            observer: None,
+            save_state: None,
+            resume_from: None,
         }
     }
 
@@ -189,6 +196,9 @@ impl<O: GroupFunc> EgorBuilder<O> {
         Egor {
             fobj: ObjFunc::new(self.fobj),
             solver: EgorSolver::new(config, rng),
+            observer: None,
+            save_state: None,
+            resume_from: None,
         }
     }
 }
@@ -199,11 +209,49 @@ impl<O: GroupFunc> EgorBuilder<O> {
 pub struct Egor<O: GroupFunc, SB: SurrogateBuilder + DeserializeOwned> {
     fobj: ObjFunc<O>,
     solver: EgorSolver<SB>,
+    observer: Option<IterationCallback>,
+    save_state: Option<PathBuf>,
+    resume_from: Option<PathBuf>,
 }
 
 impl<O: GroupFunc, SB: SurrogateBuilder + DeserializeOwned> Egor<O, SB> {
+    /// Sets a callback called after each iteration with the current optimizer state
+    /// giving access to the iteration index (`iter`), the current best x/y
+    /// (`best_param`, `best_cost`) and the last evaluated infill point (`param`).
+    /// Objective values are given in the sign of the objective whatever the objective sense.
+    pub fn with_observer(mut self, observer: impl FnMut(&EgorState<f64>) + Send + 'static) -> Self {
+        self.observer = Some(IterationCallback(
+            Arc::new(Mutex::new(observer)),
+            ObjectiveSense::Minimize,
+        ));
+        self
+    }
+
+    /// Saves the optimization state (solver with its configuration, evaluated points,
+    /// iteration count) in the given file at each iteration, allowing to resume
+    /// an interrupted optimization with [`Egor::resume_from()`].
+    pub fn save_state(mut self, path: impl Into<PathBuf>) -> Self {
+        self.save_state = Some(path.into());
+        self
+    }
+
+    /// Resumes the optimization from the state saved in the given file (see [`Egor::save_state()`]).
+    /// The optimization continues with the saved configuration and evaluated points
+    /// until the iteration budget (`max_iters`) of this optimizer is reached.
+    ///
+    /// Note: takes precedence over the `hot_start` option.
+    pub fn resume_from(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resume_from = Some(path.into());
+        self
+    }
+
     /// Runs the (constrained) optimization of the objective function.
     pub fn run(&self) -> Result<OptimResult<f64>> {
+        self.run_with_progress(std::io::stderr())
+    }
+
+    /// Runs the optimization writing iteration progress to `progress` when verbose is enabled.
+    fn run_with_progress(&self, progress: impl Write + Send + 'static) -> Result<OptimResult<f64>> {
         let xtypes = self.solver.config.xtypes.clone();
         info!("{:?}", self.solver.config);
         if let Some(outdir) = self.solver.config.outdir.as_ref() {
@@ -213,9 +261,33 @@ impl<O: GroupFunc, SB: SurrogateBuilder + DeserializeOwned> Egor<O, SB> {
             std::fs::write(filepath, json).expect("Unable to write file");
         }
 
-        let exec = Executor::new(self.fobj.clone(), self.solver.clone());
-
-        let exec = if self.solver.config.hot_start != HotStartMode::Disabled {
+        // Maximization is handled as the minimization of the opposite objective
+        let sense = self.solver.config.objective_sense;
+        let sign = sense.sign();
+        let mut solver = self.solver.clone();
+        if sense == ObjectiveSense::Maximize {
+            let config = &mut solver.config;
+            config.target = if config.target.is_finite() {
+                -config.target
+            } else {
+                f64::NEG_INFINITY
+            };
+            let nx = solver.xlimits.nrows();
+            if let Some(doe) = config.doe.as_mut().filter(|doe| doe.ncols() > nx) {
+                doe.column_mut(nx).mapv_inplace(|v| -v);
+            }
+        }
+        let fobj = self.fobj.clone().objective_sense(sense);
+        let exec = Executor::new(fobj, solver);
+
+        let exec = if self.save_state.is_some() || self.resume_from.is_some() {
+            let checkpoint = StateCheckpoint {
+                save: self.save_state.clone(),
+                resume: self.resume_from.clone(),
+                max_iters: self.solver.config.max_iters as u64,
+            };
+            exec.checkpointing(checkpoint)
+        } else if self.solver.config.hot_start != HotStartMode::Disabled {
             let checkpoint = HotStartCheckpoint::new(
                 ".checkpoints",
                 "egor",
@@ -227,21 +299,37 @@ impl<O: GroupFunc, SB: SurrogateBuilder + DeserializeOwned> Egor<O, SB> {
             exec
         };
 
+        let exec = if let Some(observer) = self.observer.as_ref() {
+            let observer = IterationCallback(observer.0.clone(), sense);
+            exec.add_observer(observer, ObserverMode::Always)
+        } else {
+            exec
+        };
+
+        let exec = if self.solver.config.verbose {
+            exec.add_observer(ProgressObserver(progress, sign), ObserverMode::Always)
+        } else {
+            exec
+        };
+
         let result = if let Some(outdir) = self.solver.config.outdir.as_ref() {
-            let hist = OptimizationObserver::new(outdir.clone());
+            let hist = OptimizationObserver::new(outdir.clone(), sign);
             exec.add_observer(hist, ObserverMode::Always).run()?
         } else {
             exec.run()?
         };
 
         info!("{}", result);
-        let (x_data, y_data) = result.state().clone().take_data().unwrap();
+        let (x_data, mut y_data) = result.state().clone().take_data().unwrap();
+        let mut y_opt = result.state.get_full_best_cost().unwrap().to_owned();
+        y_data.column_mut(0).mapv_inplace(|v| sign * v);
+        y_opt[0] *= sign;
 
         let res = if !self.solver.config.discrete() {
             info!("Data: \n{}", concatenate![Axis(1), x_data, y_data]);
             OptimResult {
                 x_opt: result.state.get_best_param().unwrap().to_owned(),
-                y_opt: result.state.get_full_best_cost().unwrap().to_owned(),
+                y_opt,
                 x_doe: x_data,
                 y_doe: y_data,
                 state: result.state,
@@ -259,33 +347,73 @@ impl<O: GroupFunc, SB: SurrogateBuilder + DeserializeOwned> Egor<O, SB> {
             let x_opt = to_discrete_space(&xtypes, &x_opt.view());
             OptimResult {
                 x_opt: x_opt.row(0).to_owned(),
-                y_opt: result.state.get_full_best_cost().unwrap().to_owned(),
+                y_opt,
                 x_doe: x_data,
                 y_doe: y_data,
                 state: result.state,
             }
         };
-        info!("Optim Result: min f(x)={} at x={}", res.y_opt, res.x_opt);
+        info!("Optim Result: best f(x)={} at x={}", res.y_opt, res.x_opt);
 
         Ok(res)
     }
 }
 
+// User callback called after each iteration (see `Egor::with_observer`)
+// given the state with objective values in the user sign wrt the objective sense
+type IterationFn = dyn FnMut(&EgorState<f64>) + Send;
+
+#[derive(Clone)]
+struct IterationCallback(Arc<Mutex<IterationFn>>, ObjectiveSense);
+
+impl Observe<EgorState<f64>> for IterationCallback {
+    fn observe_iter(&mut self, state: &EgorState<f64>, _kv: &KV) -> std::result::Result<(), Error> {
+        if self.1 == ObjectiveSense::Maximize {
+            (self.0.lock().unwrap())(&state.clone().negate_objective());
+        } else {
+            (self.0.lock().unwrap())(state);
+        }
+        Ok(())
+    }
+}
+
+// The progress observer writes one line per iteration (see `EgorConfig::verbose`)
+// with the best objective value multiplied by the given sign (-1 when maximizing)
+struct ProgressObserver<W: Write>(W, f64);
+
+impl<W: Write> Observe<EgorState<f64>> for ProgressObserver<W> {
+    fn observe_iter(&mut self, state: &EgorState<f64>, _kv: &KV) -> std::result::Result<(), Error> {
+        let to_string = |x: Option<&Array1<f64>>| x.map_or("[]".to_string(), |x| x.to_string());
+        writeln!(
+            self.0,
+            "egor iter={} best_f={} best_x={} next_x={}",
+            state.get_iter(),
+            self.1 * state.get_best_cost(),
+            to_string(state.get_best_param()),
+            to_string(state.get_param()),
+        )?;
+        Ok(())
+    }
+}
+
 // The optimization observer collects best costs ans params
 // during the optimization execution allowing to get optimization history
 // saved as a numpy array for further analysis
-// Note: the observer is activated only when outdir is specified
+// Note: the observer is activated only when outdir is specified,
+// objective values are saved multiplied by the given sign (-1 when maximizing)
 #[derive(Default)]
 struct OptimizationObserver {
     pub dir: String,
+    pub sign: f64,
     pub best_params: Option<Array2<f64>>,
     pub best_costs: Option<Array2<f64>>,
 }
 
 impl OptimizationObserver {
-    fn new(dir: String) -> Self {
+    fn new(dir: String, sign: f64) -> Self {
         Self {
             dir,
+            sign,
             best_params: None,
             best_costs: None,
         }
@@ -324,11 +452,9 @@ impl Observe<EgorState<f64>> for OptimizationObserver {
     }
 
     fn observe_final(&mut self, _state: &EgorState<f64>) -> std::result::Result<(), Error> {
-        let hist = concatenate![
-            Axis(1),
-            self.best_costs.take().unwrap(),
-            self.best_params.take().unwrap(),
-        ];
+        let mut best_costs = self.best_costs.take().unwrap();
+        best_costs.column_mut(0).mapv_inplace(|v| self.sign * v);
+        let hist = concatenate![Axis(1), best_costs, self.best_params.take().unwrap()];
         std::fs::create_dir_all(&self.dir)?;
         let filepath = std::path::Path::new(&self.dir).join(HISTORY_FILE);
         info!("Save history {:?} in {:?}", hist.shape(), filepath);
@@ -342,16 +468,17 @@ impl Observe<EgorState<f64>> for OptimizationObserver {
 mod tests {
     use super::*;
     use approx::assert_abs_diff_eq;
+    use argmin::core::{TerminationReason, TerminationStatus};
     use argmin_testfunctions::rosenbrock;
     use egobox_doe::{Lhs, SamplingMethod};
-    use ndarray::{array, s, Array2, ArrayView2, Ix1, Zip};
+    use ndarray::{array, s, Array1, Array2, ArrayView2, Ix1, Zip};
 
     use ndarray_npy::read_npy;
 
     use serial_test::serial;
     use std::time::Instant;
 
-    use crate::{gpmix::spec::*, DOE_FILE, DOE_INITIAL_FILE};
+    use crate::{gpmix::spec::*, TregoConfig, DOE_FILE, DOE_INITIAL_FILE};
 
     #[cfg(not(feature = "blas"))]
     use linfa_linalg::norm::*;
@@ -407,6 +534,179 @@ mod tests {
         assert_abs_diff_eq!(expected, res.x_opt, epsilon = 1e-1);
     }
 
+    #[test]
+    #[serial]
+    fn test_xsinx_scaled_wb2s_egor_builder() {
+        // large objective scale with negative predicted values at the optimum
+        let scaled_xsinx = |x: &ArrayView2<f64>| xsinx(x) * 1e6;
+        let res = EgorBuilder::optimize(scaled_xsinx)
+            .configure(|config| {
+                config
+                    .infill_strategy(InfillStrategy::WB2S)
+                    .doe(&array![[0.], [7.], [25.]])
+                    .max_iters(10)
+                    .seed(42)
+            })
+            .min_within(&array![[0.0, 25.0]])
+            .run()
+            .expect("Egor should minimize");
+        assert_abs_diff_eq!(array![18.9], res.x_opt, epsilon = 1e-1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_flat_bottom_early_stopping_egor() {
+        // flat bottom on [3, 7]
+        let flat_bottom =
+            |x: &ArrayView2<f64>| x.mapv(|v| f64::max((v - 5.).abs() - 2., 0.).powi(2));
+        let max_iters = 30;
+        let res = EgorBuilder::optimize(flat_bottom)
+            .configure(|config| {
+                config
+                    .infill_strategy(InfillStrategy::EI)
+                    .doe(&array![[0.], [4.], [10.]])
+                    .max_iters(max_iters)
+                    .max_infill(1e-4)
+                    .seed(42)
+            })
+            .min_within(&array![[0.0, 10.0]])
+            .run()
+            .expect("Egor should minimize");
+        assert_abs_diff_eq!(0., res.y_opt[0], epsilon = 1e-6);
+        assert!(res.state.get_iter() < max_iters as u64);
+        assert_eq!(
+            res.state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverExit(
+                "Infill criterion max value below threshold".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_flat_bottom_patience_egor() {
+        let flat_bottom =
+            |x: &ArrayView2<f64>| x.mapv(|v| f64::max((v - 5.).abs() - 2., 0.).powi(2));
+        let res = EgorBuilder::optimize(flat_bottom)
+            .configure(|config| {
+                config
+                    .doe(&array![[0.], [4.], [10.]])
+                    .max_iters(30)
+                    .tolerance(1e-6)
+                    .patience(3)
+                    .seed(42)
+            })
+            .min_within(&array![[0.0, 10.0]])
+            .run()
+            .expect("Egor should minimize");
+        // best point already in doe: stop after patience iterations
+        assert_eq!(res.state.get_iter(), 3);
+        assert_eq!(
+            res.state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverExit(
+                "No improvement over patience iterations".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_xsinx_observer_egor() {
+        let iters = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let observed = iters.clone();
+        let max_iters = 5;
+        let res = EgorBuilder::optimize(xsinx)
+            .configure(|config| config.max_iters(max_iters).seed(42))
+            .min_within(&array![[0.0, 25.0]])
+            .with_observer(move |state| {
+                assert!(state.best_param.is_some() && state.best_cost.is_some());
+                assert!(state.param.is_some());
+                observed.lock().unwrap().push(state.iter)
+            })
+            .run()
+            .expect("Egor should minimize");
+        let iters = iters.lock().unwrap();
+        assert_eq!(iters.len(), max_iters);
+        assert!(iters.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(iters.len() as u64, res.state.get_iter());
+    }
+
+    // Shared buffer standing for stderr in verbose tests
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_xsinx_verbose_egor() {
+        let max_iters = 3;
+        for verbose in [true, false] {
+            let buffer = Buffer::default();
+            EgorBuilder::optimize(xsinx)
+                .configure(|config| config.max_iters(max_iters).seed(42).verbose(verbose))
+                .min_within(&array![[0.0, 25.0]])
+                .run_with_progress(buffer.clone())
+                .expect("Egor should minimize");
+            let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+            if verbose {
+                let lines: Vec<&str> = output.lines().collect();
+                assert_eq!(lines.len(), max_iters);
+                for (i, line) in lines.iter().enumerate() {
+                    assert!(line.starts_with(&format!("egor iter={i} best_f=")));
+                    assert!(line.contains(" best_x=[") && line.contains(" next_x=["));
+                }
+            } else {
+                assert!(output.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_concave_maximize_egor() {
+        let concave = |x: &ArrayView2<f64>| x.mapv(|v| 5. - (v - 3.) * (v - 3.));
+        let outdir = "target/test_concave_maximize";
+        let _ = std::fs::remove_file(format!("{outdir}/{DOE_FILE}"));
+        let best_costs = Arc::new(Mutex::new(vec![]));
+        let observed = best_costs.clone();
+        let res = EgorBuilder::optimize(concave)
+            .configure(|config| {
+                config
+                    .max_iters(10)
+                    .doe(&array![[0.], [5.], [10.]])
+                    .objective_sense(ObjectiveSense::Maximize)
+                    .outdir(outdir)
+                    .seed(42)
+            })
+            .min_within(&array![[0.0, 10.0]])
+            .with_observer(move |state| observed.lock().unwrap().push(state.get_best_cost()))
+            .run()
+            .expect("Egor should maximize");
+        assert_abs_diff_eq!(array![3.], res.x_opt, epsilon = 1e-2);
+        assert_abs_diff_eq!(array![5.], res.y_opt, epsilon = 1e-3);
+        // history is reported with the objective sign
+        assert_abs_diff_eq!(res.y_doe[[0, 0]], -4.);
+        assert!(res.y_doe.column(0).iter().all(|y| *y <= res.y_opt[0]));
+        // observer and outdir files get values with the objective sign as well
+        let best_costs = best_costs.lock().unwrap();
+        assert!(best_costs.windows(2).all(|w| w[0] <= w[1]));
+        assert_abs_diff_eq!(*best_costs.last().unwrap(), res.y_opt[0]);
+        let filepath = std::path::Path::new(&outdir).join(DOE_FILE);
+        let doe: Array2<f64> = read_npy(filepath).expect("file read");
+        assert_abs_diff_eq!(doe.column(1), res.y_doe.column(0));
+        let filepath = std::path::Path::new(&outdir).join(HISTORY_FILE);
+        let hist: Array2<f64> = read_npy(filepath).expect("file read");
+        assert_abs_diff_eq!(hist[[hist.nrows() - 1, 0]], res.y_opt[0]);
+    }
+
     #[test]
     #[serial]
     fn test_xsinx_optmod_egor() {
@@ -468,6 +768,45 @@ mod tests {
         let _ = std::fs::remove_file(".checkpoints/egor.arg");
     }
 
+    #[test]
+    #[serial]
+    fn test_xsinx_save_resume_egor() {
+        let path = "target/test_save_resume/egor_state.bin";
+        let _ = std::fs::remove_file(path);
+        let res = EgorBuilder::optimize(xsinx)
+            .configure(|config| config.max_iters(10).seed(42))
+            .min_within(&array![[0.0, 25.0]])
+            .run()
+            .expect("Egor should minimize");
+
+        // interrupted after 5 iterations
+        let res5 = EgorBuilder::optimize(xsinx)
+            .configure(|config| config.max_iters(5).seed(42))
+            .min_within(&array![[0.0, 25.0]])
+            .save_state(path)
+            .run()
+            .expect("Egor should minimize");
+        assert_eq!(5, res5.state.get_iter());
+
+        let resumed = EgorBuilder::optimize(xsinx)
+            .configure(|config| config.max_iters(10))
+            .min_within(&array![[0.0, 25.0]])
+            .resume_from(path)
+            .run()
+            .expect("Egor should minimize");
+        assert_eq!(10, resumed.state.get_iter());
+        assert_eq!(res.x_doe.nrows(), resumed.x_doe.nrows());
+        assert_abs_diff_eq!(res.x_opt, resumed.x_opt, epsilon = 1e-6);
+        assert_abs_diff_eq!(res.y_opt, resumed.y_opt, epsilon = 1e-6);
+
+        // resuming from a missing state fails
+        assert!(EgorBuilder::optimize(xsinx)
+            .min_within(&array![[0.0, 25.0]])
+            .resume_from("target/test_save_resume/missing.bin")
+            .run()
+            .is_err());
+    }
+
     #[test]
     #[serial]
     fn test_xsinx_auto_clustering_egor_builder() {
@@ -512,6 +851,74 @@ mod tests {
         assert_abs_diff_eq!(expected, res.x_opt, epsilon = 1e-1);
     }
 
+    #[test]
+    #[serial]
+    fn test_xsinx_history_egor_builder() {
+        let n_doe = 4;
+        let max_iters = 5;
+        let res = EgorBuilder::optimize(xsinx)
+            .configure(|config| config.n_doe(n_doe).max_iters(max_iters).seed(42))
+            .min_within(&array![[0.0, 25.0]])
+            .run()
+            .expect("Minimize failure");
+
+        assert_eq!(res.x_doe.nrows(), n_doe + max_iters);
+        assert_eq!(res.y_doe.nrows(), n_doe + max_iters);
+        let running_min = res
+            .y_doe
+            .column(0)
+            .iter()
+            .scan(f64::INFINITY, |min, &y| {
+                *min = y.min(*min);
+                Some(*min)
+            })
+            .collect::<Vec<_>>();
+        assert!(running_min.windows(2).all(|w| w[1] <= w[0]));
+        assert_abs_diff_eq!(running_min[n_doe + max_iters - 1], res.y_opt[0]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_xsinx_warm_start_data_egor_builder() {
+        let xlimits = array![[0.0, 25.0]];
+        let doe = array![[0.], [7.], [25.]];
+        let cold = EgorBuilder::optimize(xsinx)
+            .configure(|config| config.max_iters(20).doe(&doe).target(-15.1).seed(42))
+            .min_within(&xlimits)
+            .run()
+            .expect("Minimize failure");
+
+        // previous evaluations near the optimum are reused as is
+        let x = array![[0.], [7.], [18.], [20.], [25.]];
+        let y = xsinx(&x.view());
+        let warm = EgorBuilder::optimize(xsinx)
+            .configure(|config| {
+                config
+                    .max_iters(20)
+                    .warm_start_data(&x, &y)
+                    .n_doe(2)
+                    .target(-15.1)
+                    .seed(42)
+            })
+            .min_within(&xlimits)
+            .run()
+            .expect("Minimize failure");
+
+        assert_eq!(x, warm.x_doe.slice(s![..5, ..]));
+        assert!(warm.x_doe.nrows() >= 5 + 2);
+        assert!(warm.state.get_iter() < cold.state.get_iter());
+        assert_abs_diff_eq!(array![18.9], warm.x_opt, epsilon = 1e-1);
+
+        // n_doe is not used when an initial doe is specified
+        let doe = concatenate![Axis(1), x, y];
+        let res = EgorBuilder::optimize(xsinx)
+            .configure(|config| config.max_iters(0).doe(&doe).n_doe(2).seed(42))
+            .min_within(&xlimits)
+            .run()
+            .expect("Minimize failure");
+        assert_eq!(x, res.x_doe);
+    }
+
     fn rosenb(x: &ArrayView2<f64>) -> Array2<f64> {
         let mut y: Array2<f64> = Array2::zeros((x.nrows(), 1));
         Zip::from(y.rows_mut())
@@ -583,6 +990,47 @@ mod tests {
         assert_abs_diff_eq!(expected, res.x_opt, epsilon = 5e-1);
     }
 
+    // Ill-conditioned ellipsoid: min f = 0 at x = (0.2, ..., 0.2)
+    fn ellipsoid(x: &ArrayView2<f64>) -> Array2<f64> {
+        let nx = x.ncols();
+        let coefs = Array1::from_shape_fn(nx, |i| 10f64.powf(4. * i as f64 / (nx - 1) as f64));
+        let y = (x - 0.2).mapv(|v| v * v).dot(&coefs);
+        y.insert_axis(Axis(1))
+    }
+
+    #[test]
+    #[serial]
+    fn test_ellipsoid_4d_trego_egor_builder() {
+        let xlimits = array![[-1., 1.], [-1., 1.], [-1., 1.], [-1., 1.]];
+        let init_doe = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(10);
+        let max_iters = 20;
+        let optimize = |trego: bool| {
+            EgorBuilder::optimize(ellipsoid)
+                .configure(|config| {
+                    config
+                        .doe(&init_doe)
+                        .max_iters(max_iters)
+                        .seed(42)
+                        .trego(trego)
+                })
+                .min_within(&xlimits)
+                .run()
+                .expect("Minimize failure")
+        };
+        let res = optimize(true);
+        // trust region size was updated from its initial value
+        assert_ne!(TregoConfig::default().sigma0, res.state.sigma);
+        // local steps speed up convergence wrt plain EGO within the same budget
+        let ego = optimize(false);
+        println!(
+            "TREGO y_opt = {}, EGO y_opt = {}",
+            res.y_opt[0], ego.y_opt[0]
+        );
+        assert!(res.y_opt[0] < ego.y_opt[0]);
+    }
+
     // Objective
     fn g24(x: &ArrayBase<impl Data<Elem = f64>, Ix1>) -> f64 {
         // Function G24: 1 global optimum y_opt = -5.5080 at x_opt =(2.3295, 3.1785)
@@ -736,6 +1184,41 @@ mod tests {
         assert_abs_diff_eq!(&array![18.], &res.x_opt, epsilon = 3.);
     }
 
+    // Mixed continuous/integer quadratic: min f = 0 at x = (1.3, 3)
+    fn mixquad(x: &ArrayView2<f64>) -> Array2<f64> {
+        assert!(
+            x.column(1).iter().all(|v| v.fract() == 0.),
+            "Error: mixquad expects integer values for x[1], got {:?}",
+            x
+        );
+        let y = (x.column(0).mapv(|v| v - 1.3)).mapv(|v| v * v)
+            + (x.column(1).mapv(|v| v - 3.)).mapv(|v| v * v);
+        y.insert_axis(Axis(1))
+    }
+
+    #[test]
+    #[serial]
+    fn test_mixquad_mixint_egor_builder() {
+        let max_iters = 20;
+        let xtypes = vec![XType::Cont(-5., 5.), XType::Int(-5, 5)];
+
+        let res = EgorBuilder::optimize(mixquad)
+            .configure(|config| config.max_iters(max_iters).seed(42))
+            .min_within_mixint_space(&xtypes)
+            .run()
+            .unwrap();
+
+        assert_eq!(3., res.x_opt[1]);
+        assert_abs_diff_eq!(1.3, res.x_opt[0], epsilon = 1e-1);
+        // no integer point evaluated twice
+        let xdoe = res.x_doe;
+        for i in 0..xdoe.nrows() {
+            for j in (i + 1)..xdoe.nrows() {
+                assert_ne!(xdoe.row(i), xdoe.row(j));
+            }
+        }
+    }
+
     fn mixobj(x: &ArrayView2<f64>) -> Array2<f64> {
         // XType.Float
         let x1 = x.column(0);
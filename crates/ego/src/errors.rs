@@ -18,6 +18,9 @@ pub enum EgoError {
     /// When Moe error occurs
     #[error("MOE error")]
     MoeError(#[from] egobox_moe::MoeError),
+    /// When a design of experiments can not be built
+    #[error("DoE error")]
+    DoeError(#[from] egobox_doe::DoeError),
     /// When IO fails
     #[error("IO error")]
     IoError(#[from] std::io::Error),
@@ -10,12 +10,13 @@ use egobox_gp::metrics::CrossValScore;
 use egobox_gp::ThetaTuning;
 use egobox_moe::{
     Clustered, Clustering, CorrelationSpec, FullGpSurrogate, GpFileFormat, GpMixture,
-    GpMixtureParams, GpSurrogate, GpSurrogateExt, MixtureGpSurrogate, RegressionSpec,
+    GpMixtureParams, GpSurrogate, GpSurrogateExt, GpType, MixtureGpSurrogate, RegressionSpec,
 };
 use linfa::traits::{Fit, PredictInplace};
 use linfa::{DatasetBase, Float, ParamGuard};
 use ndarray::{
-    s, Array, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, DataMut, Ix1, Ix2, Zip,
+    s, Array, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, CowArray, Data, DataMut,
+    Ix1, Ix2, Zip,
 };
 use ndarray_rand::rand::SeedableRng;
 use ndarray_stats::QuantileExt;
@@ -107,6 +108,23 @@ fn compute_continuous_dim(xtypes: &[XType]) -> usize {
         .unwrap()
 }
 
+/// Locate enumerate inputs in the unfolded space as (first column, number of levels)
+/// of their one-hot encoded columns.
+fn enum_categories(xtypes: &[XType]) -> Vec<(usize, usize)> {
+    let mut categories = vec![];
+    let mut unfold_index = 0;
+    xtypes.iter().for_each(|xtype| match xtype {
+        XType::Enum(v) => {
+            if *v > 1 {
+                categories.push((unfold_index, *v));
+            }
+            unfold_index += v;
+        }
+        _ => unfold_index += 1,
+    });
+    categories
+}
+
 /// Expand categorical inputs from initial x dimension space where categorical x dimensions
 /// are valued by the index in the corresponding enumerate list to the discrete unfolded space.
 ///
@@ -342,6 +360,20 @@ impl MixintGpMixtureParams {
 }
 
 impl MixintGpMixtureValidParams {
+    /// The surrogate builder where enumerate inputs are modelled as categorical inputs
+    /// of the experts (not available with PLS reduction or sparse GPs)
+    fn expert_builder(&self) -> GpMixtureParams<f64> {
+        let builder = self.surrogate_builder.clone();
+        let full_gp = builder.check_ref().is_ok_and(|params| {
+            params.kpls_dim().is_none() && matches!(params.gp_type(), GpType::FullGp)
+        });
+        if full_gp {
+            builder.categorical_inputs(enum_categories(&self.xtypes))
+        } else {
+            builder
+        }
+    }
+
     fn _train(
         &self,
         xt: &ArrayBase<impl Data<Elem = f64>, Ix2>,
@@ -355,8 +387,7 @@ impl MixintGpMixtureValidParams {
         cast_to_discrete_values_mut(&self.xtypes, &mut xcast);
         let mixmoe = MixintGpMixture {
             moe: self
-                .surrogate_builder
-                .clone()
+                .expert_builder()
                 .check()?
                 .train(&xcast, &yt.to_owned())
                 .unwrap(),
@@ -382,8 +413,7 @@ impl MixintGpMixtureValidParams {
         cast_to_discrete_values_mut(&self.xtypes, &mut xcast);
         let mixmoe = MixintGpMixture {
             moe: self
-                .surrogate_builder
-                .clone()
+                .expert_builder()
                 .check_ref()?
                 .train_on_clusters(&xcast, &yt.to_owned(), clustering)
                 .unwrap(),
@@ -458,6 +488,20 @@ impl SurrogateBuilder for MixintGpMixtureParams {
         }
     }
 
+    /// Sets the number of hyperparameters optimization restarts
+    fn set_n_start(&mut self, n_start: usize) {
+        self.0 = MixintGpMixtureValidParams {
+            surrogate_builder: self.0.surrogate_builder.clone().n_start(n_start),
+            xtypes: self.0.xtypes.clone(),
+            work_in_folded_space: self.0.work_in_folded_space,
+        }
+    }
+
+    /// Number of hyperparameters optimization restarts
+    fn n_start(&self) -> usize {
+        SurrogateBuilder::n_start(&self.0.surrogate_builder)
+    }
+
     fn train(
         &self,
         xt: ArrayView2<f64>,
@@ -561,6 +605,18 @@ impl GpSurrogate for MixintGpMixture {
         self.moe.dims()
     }
 
+    fn kind(&self) -> String {
+        self.moe.kind()
+    }
+
+    fn relevance(&self) -> Array1<f64> {
+        self.moe.relevance()
+    }
+
+    fn condition_number(&self) -> f64 {
+        self.moe.condition_number()
+    }
+
     fn predict(&self, x: &ArrayView2<f64>) -> egobox_moe::Result<Array1<f64>> {
         let mut xcast = if self.work_in_folded_space {
             unfold_with_enum_mask(&self.xtypes, x)
@@ -581,14 +637,29 @@ impl GpSurrogate for MixintGpMixture {
         self.moe.predict_var(&xcast)
     }
 
+    fn is_extrapolating(&self, x: &ArrayView2<f64>) -> egobox_moe::Result<Array1<bool>> {
+        let mut xcast = if self.work_in_folded_space {
+            unfold_with_enum_mask(&self.xtypes, x)
+        } else {
+            x.to_owned()
+        };
+        cast_to_discrete_values_mut(&self.xtypes, &mut xcast);
+        self.moe.is_extrapolating(&xcast.view())
+    }
+
+    /// Training data of the underlying continuous mixture
+    fn training_data(&self) -> (CowArray<'_, f64, Ix2>, CowArray<'_, f64, Ix2>) {
+        GpSurrogate::training_data(&self.moe)
+    }
+
     /// Save Moe model in given file.
     #[cfg(feature = "persistent")]
     fn save(&self, path: &str, format: GpFileFormat) -> egobox_moe::Result<()> {
         use egobox_moe::GpFileFormat;
 
-        let mut file = fs::File::create(path).unwrap();
+        let mut file = fs::File::create(path)?;
         let bytes = match format {
-            GpFileFormat::Json => serde_json::to_vec(self).map_err(MoeError::SaveJsonError)?,
+            GpFileFormat::Json => egobox_moe::to_json_with_dtype(self, self.dtype())?,
             GpFileFormat::Binary => bincode::serialize(self).map_err(MoeError::SaveBinaryError)?,
         };
         file.write_all(&bytes)?;
@@ -899,6 +970,38 @@ mod tests {
         //println!("LOOCV = {}", mixi_moe.loocv_score());
     }
 
+    #[test]
+    fn test_mixint_categorical_switch() {
+        // output behavior switched by an unordered categorical input with 3 levels
+        let xtypes = vec![XType::Cont(0., 1.), XType::Enum(3)];
+        let f = |x: &Array2<f64>| {
+            Zip::from(x.rows()).map_collect(|xi| match xi[1] as usize {
+                0 => (6. * xi[0]).sin(),
+                1 => xi[0] * xi[0],
+                _ => 1. - xi[0],
+            })
+        };
+        let grid = |n| {
+            let x0 = Array::linspace(0., 1., n);
+            let mut x = Array2::zeros((3 * n, 2));
+            for (i, mut row) in x.rows_mut().into_iter().enumerate() {
+                row[0] = x0[i % n];
+                row[1] = (i / n) as f64;
+            }
+            x
+        };
+        let xt = grid(10);
+        let yt = f(&xt);
+        let mixi = MixintContext::new(&xtypes);
+        let mixi_moe = mixi
+            .create_surrogate(&MoeBuilder::new(), &Dataset::new(xt, yt))
+            .expect("Mixint surrogate creation");
+
+        let xtest = grid(7);
+        let ytest = mixi_moe.predict(&xtest.view()).expect("Predict val fail");
+        assert_abs_diff_eq!(ytest, f(&xtest), epsilon = 5e-2);
+    }
+
     fn ftest(x: &Array2<f64>) -> Array1<f64> {
         let mut y = x.column(0).to_owned() * x.column(0);
         y = &y + (x.column(1).to_owned() * x.column(1));
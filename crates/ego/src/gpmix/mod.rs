@@ -5,7 +5,8 @@ pub mod spec;
 
 use egobox_gp::ThetaTuning;
 use egobox_moe::{
-    Clustering, CorrelationSpec, GpMixtureParams, MixtureGpSurrogate, RegressionSpec,
+    Clustering, CorrelationSpec, GpMixtureParams, GpMixtureValidParams, MixtureGpSurrogate,
+    RegressionSpec,
 };
 use ndarray::{ArrayView1, ArrayView2};
 
@@ -49,6 +50,18 @@ impl SurrogateBuilder for GpMixtureParams<f64> {
         *self = self.clone().theta_tunings(theta_tunings);
     }
 
+    /// Sets the number of hyperparameters optimization restarts
+    fn set_n_start(&mut self, n_start: usize) {
+        *self = self.clone().n_start(n_start);
+    }
+
+    /// Number of hyperparameters optimization restarts (default one if parameters are invalid)
+    fn n_start(&self) -> usize {
+        self.check_ref()
+            .map(|params| params.n_start())
+            .unwrap_or_else(|_| GpMixtureValidParams::<f64>::default().n_start())
+    }
+
     fn train(
         &self,
         xt: ArrayView2<f64>,
@@ -186,6 +186,9 @@
 //!     egor_config.hot_start(HotStartMode::Enabled);
 //! ```
 //!
+//! * Alternatively the optimizer state can be saved in a given file with `Egor::save_state()`
+//!   and the optimization resumed from it with `Egor::resume_from()` for a new iteration budget.
+//!
 //! # Implementation notes
 //!
 //! * Mixture of experts and PLS dimension reduction is explained in \[[Bartoli2019](#Bartoli2019)\]
@@ -17,6 +17,7 @@ use rand_xoshiro::Xoshiro256Plus;
 pub enum Algorithm {
     Cobyla,
     Slsqp,
+    Lbfgs,
     Lhs,
 }
 
@@ -134,6 +135,90 @@ impl<'a> Optimizer<'a> {
         }
     }
 
+    /// L-BFGS minimization within bounds using the change of variables
+    /// `x = lower + (upper - lower) * (1 + sin(z)) / 2` which makes the problem unconstrained in `z`.
+    /// Constraint functions are not handled.
+    #[cfg(not(feature = "nlopt"))]
+    fn lbfgs_minimize(&self) -> (f64, Array1<f64>) {
+        use argmin::core::{CostFunction, Executor, Gradient, State};
+        use argmin::solver::linesearch::MoreThuenteLineSearch;
+        use argmin::solver::quasinewton::LBFGS;
+
+        struct BoundedProblem<'a> {
+            fun: &'a (dyn ObjFn<InfillObjData<f64>> + Sync),
+            bounds: &'a Array2<f64>,
+            user_data: &'a InfillObjData<f64>,
+        }
+
+        impl BoundedProblem<'_> {
+            fn to_x(&self, z: &[f64]) -> Vec<f64> {
+                z.iter()
+                    .zip(self.bounds.outer_iter())
+                    .map(|(z, b)| b[0] + (b[1] - b[0]) * (1. + z.sin()) / 2.)
+                    .collect()
+            }
+
+            fn to_z(&self, x: &[f64]) -> Vec<f64> {
+                x.iter()
+                    .zip(self.bounds.outer_iter())
+                    .map(|(x, b)| (2. * (x - b[0]) / (b[1] - b[0]) - 1.).clamp(-1., 1.).asin())
+                    .collect()
+            }
+        }
+
+        impl CostFunction for BoundedProblem<'_> {
+            type Param = Vec<f64>;
+            type Output = f64;
+
+            fn cost(&self, z: &Self::Param) -> std::result::Result<f64, argmin::core::Error> {
+                Ok((self.fun)(&self.to_x(z), None, &mut self.user_data.clone()))
+            }
+        }
+
+        impl Gradient for BoundedProblem<'_> {
+            type Param = Vec<f64>;
+            type Gradient = Vec<f64>;
+
+            fn gradient(
+                &self,
+                z: &Self::Param,
+            ) -> std::result::Result<Self::Gradient, argmin::core::Error> {
+                let mut grad = vec![0.; z.len()];
+                (self.fun)(&self.to_x(z), Some(&mut grad), &mut self.user_data.clone());
+                Ok(grad
+                    .iter()
+                    .zip(z.iter().zip(self.bounds.outer_iter()))
+                    .map(|(g, (z, b))| g * (b[1] - b[0]) * z.cos() / 2.)
+                    .collect())
+            }
+        }
+
+        let problem = BoundedProblem {
+            fun: self.fun,
+            bounds: &self.bounds,
+            user_data: self.user_data,
+        };
+        let xinit = self.xinit.clone().unwrap().to_vec();
+        let zinit = problem.to_z(&xinit);
+        let mut solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+        if let Some(ftol_abs) = self.ftol_abs {
+            solver = solver.with_tolerance_cost(ftol_abs).unwrap();
+        }
+        let res = Executor::new(problem, solver)
+            .configure(|state| state.param(zinit).max_iters(self.max_eval as u64))
+            .run();
+        match res {
+            Ok(res) => match res.state().get_best_param() {
+                Some(z_opt) => {
+                    let x_opt = res.problem().problem.as_ref().unwrap().to_x(z_opt);
+                    (res.state().get_best_cost(), arr1(&x_opt))
+                }
+                None => (f64::INFINITY, arr1(&xinit)),
+            },
+            Err(_) => (f64::INFINITY, arr1(&xinit)),
+        }
+    }
+
     pub fn minimize(&self) -> (f64, Array1<f64>) {
         let cstr_tol = self
             .cstr_tol
@@ -232,6 +317,16 @@ impl<'a> Optimizer<'a> {
                     }
                 }
             }
+            Algorithm::Lbfgs => {
+                #[cfg(feature = "nlopt")]
+                {
+                    self.nlopt_minimize(nlopt::Algorithm::Lbfgs, cstr_tol)
+                }
+                #[cfg(not(feature = "nlopt"))]
+                {
+                    self.lbfgs_minimize()
+                }
+            }
             Algorithm::Lhs => {
                 let res = LhsOptimizer::new(&self.bounds, self.fun, &self.cons, self.user_data);
                 let res = if let Some(seed) = self.seed {
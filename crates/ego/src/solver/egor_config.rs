@@ -5,6 +5,7 @@ use crate::HotStartMode;
 use egobox_moe::{CorrelationSpec, RegressionSpec};
 use ndarray::Array1;
 use ndarray::Array2;
+use ndarray::{concatenate, Axis};
 
 use serde::{Deserialize, Serialize};
 
@@ -45,11 +46,17 @@ pub struct EgorConfig {
     /// Interval between two hyperparameters optimizations (as iteration number modulo)
     /// hyperparameters are optimized or re-used from an iteration to another
     pub(crate) n_optmod: usize,
+    /// If true, hyperparameters optimization of a surrogate starts from the theta
+    /// values found at the previous iteration instead of the default initial guess
+    /// with fewer restarts
+    #[serde(default = "default_theta_warm_start")]
+    pub(crate) theta_warm_start: bool,
     /// Number of points returned by EGO iteration (aka qEI Multipoint strategy)
     /// Actually as some point determination may fail (at most q_points are returned)
     pub(crate) q_points: usize,
     /// Number of initial doe drawn using Latin hypercube sampling
     /// Note: n_doe > 0; otherwise n_doe = max(xdim + 1, 5)
+    /// When warm start data are specified, n_doe LHS points are added to them
     pub(crate) n_doe: usize,
     /// Number of Constraints
     /// Note: dim function ouput = 1 objective + n_cstr constraints
@@ -59,6 +66,9 @@ pub struct EgorConfig {
     /// Initial doe can be either \[x\] with x inputs only or an evaluated doe \[x, y\]
     /// Note: x dimension is determined using `xlimits.nrows()`
     pub(crate) doe: Option<Array2<f64>>,
+    /// Optional previous evaluations \[x, y\] completed with `n_doe` LHS points as initial doe
+    #[serde(default)]
+    pub(crate) warm_start_data: Option<Array2<f64>>,
     /// Multipoint strategy used to get several points to be evaluated at each iteration
     pub(crate) q_ei: QEiStrategy,
     /// Criterion to select next point to evaluate
@@ -77,6 +87,16 @@ pub struct EgorConfig {
     pub(crate) n_clusters: usize,
     /// Specification of a target objective value which is used to stop the algorithm once reached
     pub(crate) target: f64,
+    /// Minimal decrease of the best objective value considered as an improvement
+    /// (used together with `patience`)
+    #[serde(default)]
+    pub(crate) tolerance: f64,
+    /// Optional number of iterations without improvement (greater than `tolerance`)
+    /// after which the algorithm is stopped
+    pub(crate) patience: Option<usize>,
+    /// Optional infill criterion threshold: the algorithm is stopped once the max value
+    /// of the infill criterion found at an iteration falls below it
+    pub(crate) max_infill: Option<f64>,
     /// Directory to save intermediate results: inital doe + evalutions at each iteration
     pub(crate) outdir: Option<String>,
     /// If true use `outdir` to retrieve and start from previous results
@@ -89,6 +109,16 @@ pub struct EgorConfig {
     pub(crate) seed: Option<u64>,
     /// Trego parameterization
     pub(crate) trego: TregoConfig,
+    /// If true print iteration progress on stderr
+    #[serde(default)]
+    pub(crate) verbose: bool,
+    /// Whether the objective is minimized or maximized
+    #[serde(default)]
+    pub(crate) objective_sense: ObjectiveSense,
+}
+
+fn default_theta_warm_start() -> bool {
+    true
 }
 
 impl Default for EgorConfig {
@@ -97,11 +127,13 @@ impl Default for EgorConfig {
             max_iters: 20,
             n_start: 20,
             n_optmod: 1,
+            theta_warm_start: true,
             q_points: 1,
             n_doe: 0,
             n_cstr: 0,
             cstr_tol: None,
             doe: None,
+            warm_start_data: None,
             q_ei: QEiStrategy::KrigingBeliever,
             infill_criterion: Box::new(WB2),
             infill_optimizer: InfillOptimizer::Slsqp,
@@ -110,12 +142,17 @@ impl Default for EgorConfig {
             kpls_dim: None,
             n_clusters: 1,
             target: f64::NEG_INFINITY,
+            tolerance: 0.,
+            patience: None,
+            max_infill: None,
             outdir: None,
             warm_start: false,
             hot_start: HotStartMode::Disabled,
             xtypes: vec![],
             seed: None,
             trego: TregoConfig::default(),
+            verbose: false,
+            objective_sense: ObjectiveSense::Minimize,
         }
     }
 }
@@ -133,17 +170,36 @@ impl EgorConfig {
     }
 
     /// Sets the number of runs of infill strategy optimizations (best result taken)
+    ///
+    /// Each run of the infill optimizer (see [`EgorConfig::infill_optimizer`]) starts
+    /// from a different point of a LHS sampling of the design space.
     pub fn n_start(mut self, n_start: usize) -> Self {
         self.n_start = n_start;
         self
     }
 
+    /// Sets the number of starting points of the infill optimizer multistart,
+    /// same as [`EgorConfig::n_start`]
+    pub fn infill_n_start(self, n_start: usize) -> Self {
+        self.n_start(n_start)
+    }
+
     /// Sets the number of iteration interval between two hyperparameter optimization
     pub fn n_optmod(mut self, n_optmod: usize) -> Self {
         self.n_optmod = n_optmod;
         self
     }
 
+    /// Whether hyperparameters optimization is started from the theta values
+    /// of the previous iteration (default) or from the default initial guess.
+    /// Warm start speeds up refits as previous theta are close to the new optimum
+    /// hence fewer optimization restarts are used: the number of restarts of the surrogate
+    /// builder is capped to 5 (see [`SurrogateBuilder::n_start`](crate::SurrogateBuilder::n_start)).
+    pub fn theta_warm_start(mut self, theta_warm_start: bool) -> Self {
+        self.theta_warm_start = theta_warm_start;
+        self
+    }
+
     /// Sets Number of parallel evaluations of the function under optimization
     pub fn q_points(mut self, q_points: usize) -> Self {
         self.q_points = q_points;
@@ -154,6 +210,8 @@ impl EgorConfig {
     ///
     /// When 0 a number of points is computed automatically regarding the number of input variables
     /// of the function under optimization.
+    /// When warm start data are specified (see [`EgorConfig::warm_start_data`]), `n_doe` LHS points
+    /// are drawn and appended to them, the default 0 meaning the given data are used as is.
     pub fn n_doe(mut self, n_doe: usize) -> Self {
         self.n_doe = n_doe;
         self
@@ -180,6 +238,20 @@ impl EgorConfig {
         self
     }
 
+    /// Sets previous evaluations `x` \[ns, nx\] and `y` \[ns, ny\] to warm start the optimization:
+    /// initial surrogates are trained on these data completed with `n_doe` LHS points
+    /// (none by default, see [`EgorConfig::n_doe`]).
+    ///
+    /// Ignored when an initial DOE is specified (see [`EgorConfig::doe`]).
+    ///
+    /// # Panics
+    ///
+    /// When `x` and `y` have not the same number of rows.
+    pub fn warm_start_data(mut self, x: &Array2<f64>, y: &Array2<f64>) -> Self {
+        self.warm_start_data = Some(concatenate![Axis(1), x.to_owned(), y.to_owned()]);
+        self
+    }
+
     /// Removes any previously specified initial doe to get the default doe usage
     pub fn default_doe(mut self) -> Self {
         self.doe = None;
@@ -252,6 +324,31 @@ impl EgorConfig {
         self
     }
 
+    /// Sets the minimal decrease of the best objective value to be considered
+    /// as an improvement when checking the `patience` stopping criterion.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets a number of iterations without improvement of the best objective value
+    /// (by more than `tolerance`) used as a stopping criterion.
+    pub fn patience(mut self, patience: usize) -> Self {
+        self.patience = Some(patience);
+        self
+    }
+
+    /// Sets an infill criterion threshold used as a stopping criterion:
+    /// the algorithm stops once the max infill criterion value found at an iteration
+    /// falls below it (e.g. negligible expected improvement).
+    ///
+    /// Note: as WB2-like criteria may take negative values, this threshold is mainly
+    /// relevant with [`InfillStrategy::EI`].
+    pub fn max_infill(mut self, max_infill: f64) -> Self {
+        self.max_infill = Some(max_infill);
+        self
+    }
+
     /// Sets a directory to write optimization history and used as search path for warm start doe
     pub fn outdir(mut self, outdir: impl Into<String>) -> Self {
         self.outdir = Some(outdir.into());
@@ -294,6 +391,23 @@ impl EgorConfig {
         self
     }
 
+    /// Whether iteration progress is printed on stderr, one line per iteration as
+    /// `egor iter=<iteration> best_f=<best objective> best_x=<best x> next_x=<last evaluated x>`
+    /// which does not require to set up a logger.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Whether the objective function is minimized (default) or maximized.
+    /// When maximizing, objective values of the initial doe, the `target` and the optimization
+    /// results are expressed with the sign of the objective function while the solver state
+    /// holds the minimized opposite values.
+    pub fn objective_sense(mut self, sense: ObjectiveSense) -> Self {
+        self.objective_sense = sense;
+        self
+    }
+
     /// Check whether we are in a discrete optimization context
     pub fn discrete(&self) -> bool {
         crate::utils::discrete(&self.xtypes)
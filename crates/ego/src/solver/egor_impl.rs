@@ -1,5 +1,5 @@
 use crate::errors::{EgoError, Result};
-use crate::gpmix::mixint::{as_continuous_limits, to_discrete_space};
+use crate::gpmix::mixint::{as_continuous_limits, cast_to_discrete_values, to_discrete_space};
 use crate::utils::{compute_cstr_scales, find_best_result_index_from, update_data};
 use crate::{find_best_result_index, optimizers::*, EgorConfig};
 use crate::{types::*, EgorState};
@@ -24,6 +24,10 @@ use rand_xoshiro::Xoshiro256Plus;
 use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 
+/// Maximum number of hyperparameters optimization restarts of surrogates warm started
+/// from the previous theta values, the surrogate builder one being used if lower
+const WARM_START_N_START: usize = 5;
+
 impl<SB: SurrogateBuilder + DeserializeOwned> EgorSolver<SB> {
     /// Constructor of the optimization of the function `f` with specified random generator
     /// to get reproducibility.
@@ -77,6 +81,54 @@ impl<SB: SurrogateBuilder + DeserializeOwned> EgorSolver<SB> {
         );
         x_dat
     }
+
+    /// Given an evaluated doe (x, y) data, return the values at `x` points of the
+    /// configured infill criterion (aka acquisition function) computed with the
+    /// objective surrogate trained on that data.
+    ///
+    /// Values are the raw criterion values (to be maximized), not scaled
+    /// with regard to the infill criterion scaling used during the optimization.
+    pub fn acquisition(
+        &self,
+        x_data: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        y_data: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        x: &ArrayView2<f64>,
+    ) -> Array1<f64> {
+        let cstr_tol = self
+            .config
+            .cstr_tol
+            .clone()
+            .unwrap_or(Array1::from_elem(self.config.n_cstr, DEFAULT_CSTR_TOL));
+        let obj_model = self.make_clustered_surrogate(
+            "Objective",
+            x_data,
+            &y_data.column(0).to_owned(),
+            true,
+            true,
+            None,
+            None,
+        );
+        let fmin = y_data[[find_best_result_index(y_data, &cstr_tol), 0]];
+        let scale_ic = if self.config.infill_criterion.name() == "WB2S" {
+            let sampling = Lhs::new(&self.xlimits)
+                .with_rng(self.rng.clone())
+                .kind(LhsKind::Maximin);
+            let (_, _, scale_ic) = self.compute_scaling(&sampling, obj_model.as_ref(), &[], fmin);
+            scale_ic
+        } else {
+            1.
+        };
+        x.outer_iter()
+            .map(|xi| {
+                self.config.infill_criterion.value(
+                    &xi.to_vec(),
+                    obj_model.as_ref(),
+                    fmin,
+                    Some(scale_ic),
+                )
+            })
+            .collect()
+    }
 }
 
 impl<SB> EgorSolver<SB>
@@ -91,7 +143,8 @@ where
     /// Reclustering is triggered when recluster boolean is true otherwise
     /// previous clu=stering is used. theta_init allows to reuse
     /// previous theta without fully retraining the surrogates
-    /// (faster execution at the cost of surrogate quality)
+    /// (faster execution at the cost of surrogate quality) or to warm start
+    /// hyperparameters optimization (see [`EgorConfig::theta_warm_start`])
     #[allow(clippy::too_many_arguments)]
     fn make_clustered_surrogate(
         &self,
@@ -127,15 +180,26 @@ where
             let clustering = clustering.unwrap();
 
             let theta_tunings = if optimize_theta {
-                // set hyperparameters optimization
+                // set hyperparameters optimization, warm started from previous theta if enabled
                 let inits = theta_inits
                     .unwrap()
                     .outer_iter()
-                    .map(|init| ThetaTuning::Optimized {
-                        init: init.to_vec(),
-                        bounds: ThetaTuning::default().bounds().unwrap().to_vec(),
+                    .map(|init| {
+                        if self.config.theta_warm_start {
+                            ThetaTuning::Optimized {
+                                init: init.to_vec(),
+                                bounds: ThetaTuning::default().bounds().unwrap().to_vec(),
+                            }
+                        } else {
+                            ThetaTuning::default()
+                        }
                     })
                     .collect::<Vec<_>>();
+                if self.config.theta_warm_start {
+                    // previous theta are expected to be close to the optimum
+                    let n_start = builder.n_start().min(WARM_START_N_START);
+                    builder.set_n_start(n_start);
+                }
                 if model_name == "Objective" {
                     info!("Objective model hyperparameters optim init >>> {inits:?}");
                 }
@@ -288,7 +352,7 @@ where
             info!(
                 "Infill criterion {} max found = {}",
                 self.config.infill_criterion.name(),
-                state.get_infill_value()
+                new_state.get_infill_value()
             );
 
             let rejected_count = x_dat.nrows() - added_indices.len();
@@ -448,6 +512,11 @@ where
                 &infill_data,
             ) {
                 Ok((infill_obj, xk)) => {
+                    // Discrete components are snapped before evaluation so that
+                    // duplicated discrete points get rejected when updating the doe
+                    let xk = self
+                        .snap_to_discrete(xk.insert_axis(Axis(0)))
+                        .remove_axis(Axis(0));
                     match self.get_virtual_point(&xk, y_data, obj_model.as_ref(), cstr_models) {
                         Ok(yk) => {
                             y_dat = concatenate![
@@ -458,7 +527,8 @@ where
                             x_dat = concatenate![Axis(0), x_dat, xk.insert_axis(Axis(0))];
                             // infill objective was minimized while infill criterion itself
                             // is expected to be maximized hence the negative sign here
-                            infill_val = -infill_obj;
+                            // (scaling is removed to get the actual criterion value)
+                            infill_val = -infill_obj * infill_data.scale_infill_obj;
                         }
                         Err(err) => {
                             // Error while predict at best point: ignore
@@ -539,7 +609,11 @@ where
         let algorithm = match self.config.infill_optimizer {
             InfillOptimizer::Slsqp => crate::optimizers::Algorithm::Slsqp,
             InfillOptimizer::Cobyla => crate::optimizers::Algorithm::Cobyla,
+            // L-BFGS does not handle constraint functions
+            InfillOptimizer::Lbfgs if self.config.n_cstr > 0 => crate::optimizers::Algorithm::Slsqp,
+            InfillOptimizer::Lbfgs => crate::optimizers::Algorithm::Lbfgs,
         };
+        let analytic_grad = matches!(algorithm, crate::optimizers::Algorithm::Lbfgs);
 
         let obj =
             |x: &[f64], gradient: Option<&mut [f64]>, params: &mut InfillObjData<f64>| -> f64 {
@@ -553,10 +627,20 @@ where
                     ..
                 } = params;
                 if let Some(grad) = gradient {
-                    let f = |x: &Vec<f64>| -> f64 {
-                        self.eval_infill_obj(x, obj_model, fmin, *scale_infill_obj, *scale_wb2)
-                    };
-                    grad[..].copy_from_slice(&x.to_vec().central_diff(&f));
+                    if analytic_grad {
+                        grad[..].copy_from_slice(&self.eval_grad_infill_obj(
+                            x,
+                            obj_model,
+                            fmin,
+                            *scale_infill_obj,
+                            *scale_wb2,
+                        ));
+                    } else {
+                        let f = |x: &Vec<f64>| -> f64 {
+                            self.eval_infill_obj(x, obj_model, fmin, *scale_infill_obj, *scale_wb2)
+                        };
+                        grad[..].copy_from_slice(&x.to_vec().central_diff(&f));
+                    }
                 }
                 self.eval_infill_obj(x, obj_model, fmin, *scale_infill_obj, *scale_wb2)
             };
@@ -766,6 +850,26 @@ where
         (grad / scale).to_vec()
     }
 
+    /// Project discrete components of `x` (expressed in the continuous relaxed space)
+    /// to their closest admissible values. `x` is returned unchanged when
+    /// all input variables are continuous.
+    pub(crate) fn snap_to_discrete(&self, x: Array2<f64>) -> Array2<f64> {
+        if self.config.discrete() {
+            cast_to_discrete_values(&self.config.xtypes, &x)
+        } else {
+            x
+        }
+    }
+
+    /// DOE (x_data, y_data) concatenated with objective values in the user sign
+    /// (i.e. negated back when maximizing), as saved in `outdir`.
+    pub(crate) fn user_sign_doe(&self, x_data: &Array2<f64>, y_data: &Array2<f64>) -> Array2<f64> {
+        let mut doe = concatenate![Axis(1), x_data.view(), y_data.view()];
+        let sign = self.config.objective_sense.sign();
+        doe.column_mut(x_data.ncols()).mapv_inplace(|v| sign * v);
+        doe
+    }
+
     pub fn eval_obj<O: CostFunction<Param = Array2<f64>, Output = Array2<f64>>>(
         &self,
         pb: &mut Problem<O>,
@@ -783,3 +887,61 @@ where
             .expect("Objective evaluation")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use egobox_moe::GpMixtureParams;
+    use ndarray::array;
+    use rand_xoshiro::rand_core::SeedableRng;
+
+    fn xsinx(x: &ArrayView2<f64>) -> Array2<f64> {
+        (x - 3.5) * ((x - 3.5) / std::f64::consts::PI).mapv(|v| v.sin())
+    }
+
+    #[test]
+    fn test_theta_warm_start() {
+        let xt = array![[0.], [7.], [10.], [15.], [20.], [25.]];
+        let yt = xsinx(&xt.view()).column(0).to_owned();
+        let xnew = array![[0.], [3.], [7.], [10.], [15.], [18.], [20.], [25.]];
+        let ynew = xsinx(&xnew.view()).column(0).to_owned();
+
+        let refit = |theta_warm_start: bool, n_start: Option<usize>| {
+            let config = EgorConfig::default()
+                .xtypes(&[XType::Cont(0., 25.)])
+                .theta_warm_start(theta_warm_start);
+            let mut solver: EgorSolver<GpMixtureParams<f64>> =
+                EgorSolver::new(config, Xoshiro256Plus::seed_from_u64(42));
+            if let Some(n_start) = n_start {
+                solver.surrogate_builder.set_n_start(n_start);
+            }
+            let model =
+                solver.make_clustered_surrogate("Objective", &xt, &yt, true, true, None, None);
+            let thetas = model.experts()[0].theta().to_owned().insert_axis(Axis(0));
+            let clustering = model.to_clustering();
+            let model = solver.make_clustered_surrogate(
+                "Objective",
+                &xnew,
+                &ynew,
+                false,
+                true,
+                Some(&clustering),
+                Some(&thetas),
+            );
+            let expert = &model.experts()[0];
+            (expert.theta().to_owned(), expert.likelihood_evals())
+        };
+
+        let (warm, warm_evals) = refit(true, None);
+        let (cold, cold_evals) = refit(false, None);
+        assert_abs_diff_eq!(warm, cold, epsilon = 1e-2 * cold[0]);
+        println!("likelihood evaluations: warm {warm_evals}, cold {cold_evals}");
+        assert!(0 < warm_evals && warm_evals < cold_evals);
+
+        // a lower number of restarts of the surrogate builder is kept
+        let (_, single_evals) = refit(true, Some(1));
+        println!("likelihood evaluations: warm with a single start {single_evals}");
+        assert!(0 < single_evals && single_evals < warm_evals);
+    }
+}
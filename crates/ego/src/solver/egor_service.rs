@@ -43,7 +43,7 @@ use crate::gpmix::mixint::*;
 use crate::{to_xtypes, types::*, EgorConfig, EgorSolver};
 
 use egobox_moe::GpMixtureParams;
-use ndarray::{Array2, ArrayBase, Data, Ix2};
+use ndarray::{Array1, Array2, ArrayBase, ArrayView2, Data, Ix2};
 use ndarray_rand::rand::SeedableRng;
 use rand_xoshiro::Xoshiro256Plus;
 use serde::de::DeserializeOwned;
@@ -134,14 +134,31 @@ impl<SB: SurrogateBuilder + DeserializeOwned> EgorService<SB> {
         let x = self.solver.suggest(&x_data, y_data);
         to_discrete_space(xtypes, &x).to_owned()
     }
+
+    /// Given an evaluated doe (x, y) data, return the values of the configured
+    /// infill criterion at given `x` points without suggesting a new point.
+    /// Greater values denote more promising locations.
+    pub fn acquisition(
+        &self,
+        x_data: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        y_data: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        x: &ArrayView2<f64>,
+    ) -> Array1<f64> {
+        let xtypes = &self.solver.config.xtypes;
+        let x_data = to_continuous_space(xtypes, x_data);
+        let x = to_continuous_space(xtypes, x);
+        self.solver.acquisition(&x_data, y_data, &x.view())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::criteria::InfillCriterion;
     use crate::gpmix::spec::*;
     use approx::assert_abs_diff_eq;
-    use ndarray::{array, concatenate, ArrayView2, Axis};
+    use egobox_moe::MixtureGpSurrogate;
+    use ndarray::{array, concatenate, Axis};
 
     use ndarray_stats::QuantileExt;
 
@@ -173,4 +190,110 @@ mod tests {
         let y_opt = y_doe.min().unwrap();
         assert_abs_diff_eq!(expected, *y_opt, epsilon = 1e-1);
     }
+
+    #[test]
+    fn test_xsinx_infill_optimizers_egor_builder() {
+        let doe = array![[0.], [7.], [10.], [20.], [25.]];
+        let y_doe = xsinx(&doe.view());
+        let suggest_with = |optimizer| {
+            EgorServiceBuilder::optimize()
+                .configure(|conf| {
+                    conf.infill_strategy(InfillStrategy::EI)
+                        .infill_optimizer(optimizer)
+                        .infill_n_start(10)
+                        .seed(42)
+                })
+                .min_within(&array![[0., 25.]])
+                .suggest(&doe, &y_doe)
+        };
+        let x_slsqp = suggest_with(InfillOptimizer::Slsqp);
+        let x_cobyla = suggest_with(InfillOptimizer::Cobyla);
+        let x_lbfgs = suggest_with(InfillOptimizer::Lbfgs);
+        assert_abs_diff_eq!(x_slsqp, x_cobyla, epsilon = 1e-2);
+        assert_abs_diff_eq!(x_slsqp, x_lbfgs, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_xsinx_acquisition_egor_builder() {
+        let ego = EgorServiceBuilder::optimize()
+            .configure(|conf| conf.infill_strategy(InfillStrategy::EI).seed(42))
+            .min_within(&array![[0., 25.]]);
+
+        let doe = array![[0.], [7.], [20.], [25.]];
+        let y_doe = xsinx(&doe.view());
+        let x = Array1::linspace(0., 25., 251).insert_axis(Axis(1));
+        let ei = ego.acquisition(&doe, &y_doe, &x.view());
+
+        assert_eq!(ei.len(), x.nrows());
+        assert!(ei.iter().all(|v| *v >= 0.));
+        // No improvement expected at already evaluated points
+        let ei_doe = ego.acquisition(&doe, &y_doe, &doe.view());
+        let ei_max = *ei.max().unwrap();
+        assert!(ei_doe.iter().all(|v| *v < 1e-3 * ei_max));
+        // Peak lies within the largest unexplored region
+        let x_max = x[[ei.argmax().unwrap(), 0]];
+        assert!(x_max > 7. && x_max < 20., "EI peak at {x_max}");
+    }
+
+    /// Pure exploration criterion maximizing the predicted variance
+    #[derive(Clone, serde::Serialize, serde::Deserialize)]
+    struct MaxVariance;
+
+    #[typetag::serde]
+    impl InfillCriterion for MaxVariance {
+        fn name(&self) -> &'static str {
+            "MaxVariance"
+        }
+
+        fn value(
+            &self,
+            x: &[f64],
+            obj_model: &dyn MixtureGpSurrogate,
+            _fmin: f64,
+            _scale: Option<f64>,
+        ) -> f64 {
+            let pt = ArrayView2::from_shape((1, x.len()), x).unwrap();
+            obj_model
+                .predict_var(&pt)
+                .map(|v| v[[0, 0]])
+                .unwrap_or(-f64::INFINITY)
+        }
+
+        fn grad(
+            &self,
+            x: &[f64],
+            obj_model: &dyn MixtureGpSurrogate,
+            _fmin: f64,
+            _scale: Option<f64>,
+        ) -> Array1<f64> {
+            let pt = ArrayView2::from_shape((1, x.len()), x).unwrap();
+            obj_model
+                .predict_var_gradients(&pt)
+                .map(|g| g.row(0).to_owned())
+                .unwrap_or(Array1::zeros(x.len()))
+        }
+
+        fn scaling(
+            &self,
+            _x: &ArrayView2<f64>,
+            _obj_model: &dyn MixtureGpSurrogate,
+            _fmin: f64,
+        ) -> f64 {
+            1.
+        }
+    }
+
+    #[test]
+    fn test_xsinx_custom_infill_criterion_egor_builder() {
+        let ego = EgorServiceBuilder::optimize()
+            .configure(|conf| conf.infill_criterion(Box::new(MaxVariance)).seed(42))
+            .min_within(&array![[0., 25.]]);
+
+        // Points gathered on the left part of the design space
+        let doe = array![[0.], [2.], [5.], [7.], [10.]];
+        let y_doe = xsinx(&doe.view());
+        let x_suggested = ego.suggest(&doe, &y_doe);
+        // Exploration goes to the unexplored right part
+        assert!(x_suggested[[0, 0]] > 20., "suggested {x_suggested}");
+    }
 }
@@ -173,9 +173,8 @@ where
         let rng = self.rng.clone();
         let sampling = Lhs::new(&self.xlimits).with_rng(rng).kind(LhsKind::Maximin);
 
-        let hstart_doe: Option<Array2<f64>> =
-            if self.config.warm_start && self.config.outdir.is_some() {
-                let path: &String = self.config.outdir.as_ref().unwrap();
+        let hstart_doe: Option<Array2<f64>> = match self.config.outdir.as_ref() {
+            Some(path) if self.config.warm_start => {
                 let filepath = std::path::Path::new(&path).join(DOE_FILE);
                 if filepath.is_file() {
                     info!("Reading DOE from {:?}", filepath);
@@ -187,14 +186,31 @@ where
                 } else {
                     None
                 }
-            } else {
-                None
-            };
+            }
+            _ => None,
+        };
 
-        let doe = hstart_doe.as_ref().or(self.config.doe.as_ref());
+        // Saved DOE objective values are in the user sign
+        let sign = self.config.objective_sense.sign();
+        let nx = self.xlimits.nrows();
+        let hstart_doe = hstart_doe.map(|mut doe| {
+            if doe.ncols() > nx {
+                doe.column_mut(nx).mapv_inplace(|v| sign * v);
+            }
+            doe
+        });
+        let warm_start_data = if hstart_doe.is_none() && self.config.doe.is_none() {
+            self.config.warm_start_data.as_ref()
+        } else {
+            None
+        };
+        let doe = hstart_doe
+            .as_ref()
+            .or(self.config.doe.as_ref())
+            .or(warm_start_data);
 
         let (y_data, x_data) = if let Some(doe) = doe {
-            if doe.ncols() == self.xlimits.nrows() {
+            let (y_doe, x_doe) = if doe.ncols() == self.xlimits.nrows() {
                 // only x are specified
                 info!("Compute initial DOE on specified {} points", doe.nrows());
                 (self.eval_obj(problem, doe), doe.to_owned())
@@ -205,6 +221,21 @@ where
                     doe.slice(s![.., self.xlimits.nrows()..]).to_owned(),
                     doe.slice(s![.., ..self.xlimits.nrows()]).to_owned(),
                 )
+            };
+            if warm_start_data.is_some() && self.config.n_doe > 0 {
+                // warm start data are completed with the requested LHS points
+                info!(
+                    "Complete warm start data with {} LHS points",
+                    self.config.n_doe
+                );
+                let x = self.snap_to_discrete(sampling.sample(self.config.n_doe));
+                let y = self.eval_obj(problem, &x);
+                (
+                    concatenate![Axis(0), y_doe, y],
+                    concatenate![Axis(0), x_doe, x],
+                )
+            } else {
+                (y_doe, x_doe)
             }
         } else {
             let n_doe = if self.config.n_doe == 0 {
@@ -213,12 +244,11 @@ where
                 self.config.n_doe
             };
             info!("Compute initial LHS with {} points", n_doe);
-            let x = sampling.sample(n_doe);
+            let x = self.snap_to_discrete(sampling.sample(n_doe));
             (self.eval_obj(problem, &x), x)
         };
-        let doe = concatenate![Axis(1), x_data, y_data];
-        if self.config.outdir.is_some() {
-            let path = self.config.outdir.as_ref().unwrap();
+        if let Some(path) = self.config.outdir.as_ref() {
+            let doe = self.user_sign_doe(&x_data, &y_data);
             std::fs::create_dir_all(path)?;
             let filepath = std::path::Path::new(path).join(DOE_INITIAL_FILE);
             info!("Save initial doe shape {:?} in {:?}", doe.shape(), filepath);
@@ -234,9 +264,9 @@ where
             .clusterings(clusterings)
             .theta_inits(theta_inits)
             .sampling(sampling);
-        initial_state.doe_size = doe.nrows();
+        initial_state.doe_size = y_data.nrows();
         initial_state.max_iters = self.config.max_iters as u64;
-        initial_state.added = doe.nrows();
+        initial_state.added = y_data.nrows();
         initial_state.no_point_added_retries = no_point_added_retries;
         initial_state.cstr_tol = self
             .config
@@ -244,11 +274,14 @@ where
             .clone()
             .unwrap_or(Array1::from_elem(self.config.n_cstr, DEFAULT_CSTR_TOL));
         initial_state.target_cost = self.config.target;
+        initial_state.sigma = self.config.trego.sigma0;
 
         let best_index = find_best_result_index(&y_data, &initial_state.cstr_tol);
         initial_state.best_index = Some(best_index);
         initial_state.prev_best_index = Some(best_index);
         initial_state.last_best_iter = 0;
+        initial_state.last_improvement_cost = y_data[[best_index, 0]];
+        initial_state.last_improvement_iter = 0;
         debug!("Initial State = {:?}", initial_state);
         Ok((initial_state, None))
     }
@@ -264,28 +297,36 @@ where
             state.get_max_iters()
         );
         let now = Instant::now();
-        let res = if self.config.trego.activated {
+        let iter = state.get_iter();
+        let mut res = if self.config.trego.activated {
             self.trego_iteration(fobj, state)?
         } else {
             self.ego_iteration(fobj, state)?
         };
         let (x_data, y_data) = res.0.data.clone().unwrap();
 
-        if self.config.outdir.is_some() {
-            let doe = concatenate![Axis(1), x_data, y_data];
-            let path = self.config.outdir.as_ref().unwrap();
+        let best_cost = y_data[[res.0.best_index.unwrap(), 0]];
+        if res.0.last_improvement_cost - best_cost > self.config.tolerance {
+            res.0.last_improvement_cost = best_cost;
+            res.0.last_improvement_iter = iter + 1;
+        }
+
+        if let Some(path) = self.config.outdir.as_ref() {
+            let doe = self.user_sign_doe(&x_data, &y_data);
             std::fs::create_dir_all(path)?;
             let filepath = std::path::Path::new(path).join(DOE_FILE);
             info!("Save doe shape {:?} in {:?}", doe.shape(), filepath);
             write_npy(filepath, &doe).expect("Write current doe");
         }
 
+        let mut best_y = y_data.row(res.0.best_index.unwrap()).to_owned();
+        best_y[0] *= self.config.objective_sense.sign();
         info!(
             "********* End iteration {}/{} in {:.3}s: Best fun(x)={} at x={}",
             res.0.get_iter() + 1,
             res.0.get_max_iters(),
             now.elapsed().as_secs_f64(),
-            y_data.row(res.0.best_index.unwrap()),
+            best_y,
             x_data.row(res.0.best_index.unwrap())
         );
         Ok(res)
@@ -298,6 +339,28 @@ where
         debug!("Best index {:?}", state.best_index);
         debug!("Data {:?}", state.data.as_ref().unwrap());
 
+        if let Some(max_infill) = self.config.max_infill {
+            if state.get_iter() > 0 && state.infill_value < max_infill {
+                info!(
+                    "Infill criterion max value {} below threshold {}",
+                    state.infill_value, max_infill
+                );
+                return TerminationStatus::Terminated(TerminationReason::SolverExit(
+                    "Infill criterion max value below threshold".to_string(),
+                ));
+            }
+        }
+        if let Some(patience) = self.config.patience {
+            if state.get_iter() - state.last_improvement_iter >= patience as u64 {
+                info!(
+                    "No improvement greater than {} over the last {} iterations",
+                    self.config.tolerance, patience
+                );
+                return TerminationStatus::Terminated(TerminationReason::SolverExit(
+                    "No improvement over patience iterations".to_string(),
+                ));
+            }
+        }
         TerminationStatus::NotTerminated
     }
 }
@@ -338,12 +401,20 @@ where
         // Check prev step success
         let diff = y_data[[prev_best, 0]] - rho(state.sigma);
         let last_iter_success = y_data[[best, 0]] < diff;
+        // logged in the user sign wrt the objective sense
+        let sign = self.config.objective_sense.sign();
+        let (better, worse, margin) = if sign < 0. {
+            (">", "<=", "+")
+        } else {
+            ("<", ">=", "-")
+        };
         info!(
-            "success = {} as {} {} {} - {}",
+            "success = {} as {} {} {} {} {}",
             last_iter_success,
-            y_data[[best, 0]],
-            if last_iter_success { "<" } else { ">=" },
-            y_data[[prev_best, 0]],
+            sign * y_data[[best, 0]],
+            if last_iter_success { better } else { worse },
+            sign * y_data[[prev_best, 0]],
+            margin,
             rho(state.sigma)
         );
         let mut new_state = state.clone();
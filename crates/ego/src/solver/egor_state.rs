@@ -46,6 +46,10 @@ pub struct EgorState<F: Float> {
     pub iter: u64,
     /// Iteration number of last best cost
     pub last_best_iter: u64,
+    /// Best objective value at the last improvement greater than the configured tolerance
+    pub last_improvement_cost: F,
+    /// Iteration number of the last improvement greater than the configured tolerance
+    pub last_improvement_iter: u64,
     /// Maximum number of iterations
     pub max_iters: u64,
     /// Evaluation counts
@@ -68,7 +72,7 @@ pub struct EgorState<F: Float> {
     /// Constraint tolerance cstr < cstr_tol.
     /// It used to assess the validity of the param point and hence the corresponding cost
     pub cstr_tol: Array1<F>,
-    /// Infill criterion value
+    /// Max infill criterion value found at the last iteration
     pub infill_value: F,
 
     /// Current clusterings for objective and constraints GP mixture surrogate models
@@ -192,6 +196,28 @@ where
         self
     }
 
+    /// Negate objective values, i.e. the first component of costs and the first column
+    /// of output data, used to convert values of a maximized objective to the user sign.
+    pub(crate) fn negate_objective(mut self) -> Self {
+        for cost in [
+            &mut self.cost,
+            &mut self.prev_cost,
+            &mut self.best_cost,
+            &mut self.prev_best_cost,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            cost[0] = -cost[0];
+        }
+        if let Some((_, y_data)) = self.data.as_mut() {
+            y_data.column_mut(0).mapv_inplace(|v| -v);
+        }
+        self.target_cost = -self.target_cost;
+        self.last_improvement_cost = -self.last_improvement_cost;
+        self
+    }
+
     /// Set the current clusterings used by surrogate models
     pub fn clusterings(mut self, clustering: Vec<Option<Clustering>>) -> Self {
         self.clusterings = Some(clustering);
@@ -351,6 +377,8 @@ where
 
             iter: 0,
             last_best_iter: 0,
+            last_improvement_cost: F::infinity(),
+            last_improvement_iter: 0,
             max_iters: u64::MAX,
             counts: HashMap::new(),
             time: Some(web_time::Duration::new(0, 0)),
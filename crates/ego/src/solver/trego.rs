@@ -1,6 +1,6 @@
 use crate::optimizers::*;
 use crate::utils::find_best_result_index_from;
-use crate::utils::update_data;
+use crate::utils::{is_update_ok, update_data};
 use crate::EgorSolver;
 use crate::EgorState;
 use crate::InfillObjData;
@@ -52,28 +52,34 @@ impl<SB: SurrogateBuilder + DeserializeOwned> EgorSolver<SB> {
             ),
             infill_data,
         );
-        let mut new_state = new_state.infill_value(-infill_obj);
+        let mut new_state = new_state.infill_value(-infill_obj * infill_data.scale_infill_obj);
         info!(
             "Infill criterion {} max found = {}",
             self.config.infill_criterion.name(),
-            state.get_infill_value()
+            new_state.get_infill_value()
         );
 
-        let x_new = x_opt.insert_axis(Axis(0));
+        let x_new = self.snap_to_discrete(x_opt.insert_axis(Axis(0)));
         debug!(
             "x_old={} x_new={}",
             x_data.row(new_state.best_index.unwrap()),
             x_data.row(best_index)
         );
-        let y_new = self.eval_obj(fobj, &x_new);
-        debug!(
-            "y_old-y_new={}, rho={}",
-            y_old - y_new[[0, 0]],
-            rho(new_state.sigma)
-        );
-
         // Update DOE and best point
-        let added = update_data(&mut x_data, &mut y_data, &x_new, &y_new);
+        // Note: a point too close to a previous one (typically a discrete point
+        // already evaluated) is not evaluated again
+        let added = if is_update_ok(&x_data, &x_new.row(0)) {
+            let y_new = self.eval_obj(fobj, &x_new);
+            debug!(
+                "y_old-y_new={}, rho={}",
+                y_old - y_new[[0, 0]],
+                rho(new_state.sigma)
+            );
+            update_data(&mut x_data, &mut y_data, &x_new, &y_new)
+        } else {
+            info!("Reject point {} too close to previous ones", x_new.row(0));
+            vec![]
+        };
         new_state.prev_added = new_state.added;
         new_state.added += added.len();
         info!(
@@ -14,10 +14,13 @@ pub struct OptimResult<F: Float> {
     /// Optimum y value (e.g. f(x_opt))
     pub y_opt: Array1<F>,
     /// History of successive x values
+    /// in evaluation order starting with the initial doe
     pub x_doe: Array2<F>,
     /// History of successive y values (e.g f(x_doe))
+    /// in evaluation order starting with the initial doe
     pub y_doe: Array2<F>,
     /// EgorSolver final state
+    /// (the reason for stopping is given by its `termination_status`)
     pub state: EgorState<F>,
 }
 
@@ -39,6 +42,9 @@ pub enum InfillOptimizer {
     Slsqp,
     /// Cobyla optimizer (gradient free)
     Cobyla,
+    /// L-BFGS optimizer using the infill criterion analytic gradient.
+    /// Only bound constraints are handled, SLSQP is used when constraint functions are specified.
+    Lbfgs,
 }
 
 /// Strategy to choose several points at each iteration
@@ -56,6 +62,27 @@ pub enum QEiStrategy {
     ConstantLiarMinimum,
 }
 
+/// Whether the objective function is minimized or maximized
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectiveSense {
+    /// Objective function is minimized
+    #[default]
+    Minimize,
+    /// Objective function is maximized (actually its opposite is minimized)
+    Maximize,
+}
+
+impl ObjectiveSense {
+    /// Factor converting objective values between the user sign and the minimized one
+    /// (-1 when maximizing, 1 otherwise)
+    pub fn sign(&self) -> f64 {
+        match self {
+            ObjectiveSense::Minimize => 1.,
+            ObjectiveSense::Maximize => -1.,
+        }
+    }
+}
+
 /// An interface for objective function to be optimized
 ///
 /// The function is expected to return a matrix allowing nrows evaluations at once.
@@ -68,11 +95,22 @@ impl<T> GroupFunc for T where T: Clone + Fn(&ArrayView2<f64>) -> Array2<f64> {}
 #[derive(Clone)]
 pub struct ObjFunc<O: GroupFunc> {
     fobj: O,
+    sense: ObjectiveSense,
 }
 
 impl<O: GroupFunc> ObjFunc<O> {
     pub fn new(fobj: O) -> Self {
-        ObjFunc { fobj }
+        ObjFunc {
+            fobj,
+            sense: ObjectiveSense::Minimize,
+        }
+    }
+
+    /// Set the objective sense, when maximizing the objective values (first column)
+    /// are negated to be minimized.
+    pub fn objective_sense(mut self, sense: ObjectiveSense) -> Self {
+        self.sense = sense;
+        self
     }
 }
 
@@ -84,8 +122,11 @@ impl<O: GroupFunc> CostFunction for ObjFunc<O> {
 
     /// Apply the cost function to a parameter `p`
     fn cost(&self, p: &Self::Param) -> std::result::Result<Self::Output, argmin::core::Error> {
-        // Evaluate 2D Rosenbrock function
-        Ok((self.fobj)(&p.view()))
+        let mut res = (self.fobj)(&p.view());
+        if self.sense == ObjectiveSense::Maximize {
+            res.column_mut(0).mapv_inplace(|v| -v);
+        }
+        Ok(res)
     }
 }
 
@@ -126,6 +167,12 @@ pub trait SurrogateBuilder: Clone + Serialize + Sync {
     /// Sets the hyperparameters tuning strategy
     fn set_theta_tunings(&mut self, theta_tunings: &[ThetaTuning<f64>]);
 
+    /// Sets the number of hyperparameters optimization restarts
+    fn set_n_start(&mut self, n_start: usize);
+
+    /// Number of hyperparameters optimization restarts
+    fn n_start(&self) -> usize;
+
     /// Train the surrogate with given training dataset (x, y)
     fn train(
         &self,
@@ -121,3 +121,46 @@ where
         self.frequency
     }
 }
+
+/// Handles saving the optimization state in a given file at each iteration
+/// and resuming the optimization from a state previously saved in a given file.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) struct StateCheckpoint {
+    /// File where the state is saved to
+    pub save: Option<PathBuf>,
+    /// File where the state is resumed from
+    pub resume: Option<PathBuf>,
+    /// Iteration budget of the resumed optimization
+    pub max_iters: u64,
+}
+
+impl<S> Checkpoint<S, EgorState<f64>> for StateCheckpoint
+where
+    S: Serialize + DeserializeOwned,
+{
+    fn save(&self, solver: &S, state: &EgorState<f64>) -> Result<(), Error> {
+        if let Some(path) = self.save.as_ref() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?
+            }
+            let f = BufWriter::new(File::create(path)?);
+            bincode::serialize_into(f, &(solver, state))?;
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<(S, EgorState<f64>)>, Error> {
+        if let Some(path) = self.resume.as_ref() {
+            let reader = BufReader::new(File::open(path)?);
+            let (solver, mut state): (_, EgorState<_>) = bincode::deserialize_from(reader)?;
+            state.max_iters = self.max_iters;
+            Ok(Some((solver, state)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn frequency(&self) -> CheckpointingFrequency {
+        CheckpointingFrequency::Always
+    }
+}
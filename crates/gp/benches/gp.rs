@@ -4,7 +4,7 @@ use egobox_gp::correlation_models::SquaredExponentialCorr;
 use egobox_gp::mean_models::ConstantMean;
 use egobox_gp::GaussianProcess;
 use linfa::prelude::{Dataset, Fit};
-use ndarray::{array, Array1, Zip};
+use ndarray::{array, Array1, Axis, Zip};
 use ndarray_npy::{read_npy, write_npy};
 use ndarray_rand::rand::SeedableRng;
 use rand_xoshiro::Xoshiro256Plus;
@@ -67,5 +67,40 @@ fn criterion_gp(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, criterion_gp);
+fn criterion_gp_predict(c: &mut Criterion) {
+    let dim = 5;
+    let lim = array![[-600., 600.]];
+    let xlimits = lim.broadcast((dim, 2)).unwrap();
+    let rng = Xoshiro256Plus::seed_from_u64(42);
+    let xt = Lhs::new(&xlimits).with_rng(rng.clone()).sample(200);
+    let yt = xt.map_axis(Axis(1), |x| x.mapv(|v| v * v).sum() / 4000.);
+    let gp = GaussianProcess::<f64, ConstantMean, SquaredExponentialCorr>::params(
+        ConstantMean::default(),
+        SquaredExponentialCorr::default(),
+    )
+    .fit(&Dataset::new(xt, yt))
+    .expect("GP fit error");
+    let x = Lhs::new(&xlimits).with_rng(rng).sample(1000);
+
+    let mut group = c.benchmark_group("gp_predict");
+    group.bench_function("predict then predict_var", |b| {
+        b.iter(|| black_box((gp.predict(&x).unwrap(), gp.predict_var(&x).unwrap())))
+    });
+    group.bench_function("predict_valvar", |b| {
+        b.iter(|| black_box(gp.predict_valvar(&x).unwrap()))
+    });
+    group.bench_function("predict_var", |b| {
+        b.iter(|| black_box(gp.predict_var(&x).unwrap()))
+    });
+    let x1 = x.row(0).to_owned();
+    group.bench_function("predict one point", |b| {
+        b.iter(|| black_box(gp.predict(&x1.view().insert_axis(Axis(0))).unwrap()))
+    });
+    group.bench_function("predict_single", |b| {
+        b.iter(|| black_box(gp.predict_single(&x1)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_gp, criterion_gp_predict);
 criterion_main!(benches);
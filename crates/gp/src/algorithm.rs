@@ -1,8 +1,11 @@
 use crate::errors::{GpError, Result};
 use crate::mean_models::*;
 use crate::optimization::{optimize_params, prepare_multistart, CobylaParams};
-use crate::parameters::{GpParams, GpValidParams};
-use crate::utils::{pairwise_differences, DistanceMatrix, NormalizedData};
+use crate::parameters::{GpParams, GpValidParams, Scaler};
+use crate::utils::{
+    check_finite, differences, latent_inputs, normalized_relevance, outside_bounding_box,
+    pairwise_differences, theta_init_from_distances, DistanceMatrix, NormalizedData,
+};
 use crate::{correlation_models::*, ThetaTuning};
 
 use linfa::dataset::{WithLapack, WithoutLapack};
@@ -11,22 +14,24 @@ use linfa::prelude::{Dataset, DatasetBase, Fit, Float, PredictInplace};
 #[cfg(not(feature = "blas"))]
 use linfa_linalg::{cholesky::*, eigh::*, qr::*, svd::*, triangular::*};
 #[cfg(feature = "blas")]
-use log::warn;
-#[cfg(feature = "blas")]
 use ndarray_linalg::{cholesky::*, eigh::*, qr::*, svd::*, triangular::*};
 
 use linfa_pls::PlsRegression;
-use ndarray::{Array, Array1, Array2, ArrayBase, Axis, Data, Ix1, Ix2, Zip};
+use ndarray::{
+    concatenate, s, Array, Array1, Array2, ArrayBase, ArrayView1, Axis, CowArray, Data, DataMut,
+    Ix1, Ix2, Zip,
+};
 
 use ndarray_rand::rand_distr::Normal;
 use ndarray_rand::RandomExt;
 use ndarray_stats::QuantileExt;
 
-use log::debug;
+use log::{debug, warn};
 use rayon::prelude::*;
 #[cfg(feature = "serializable")]
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 
 // const LOG10_20: f64 = 1.301_029_995_663_981_3; //f64::log10(20.);
@@ -103,6 +108,10 @@ impl<F: Float> Clone for GpInnerParams<F> {
 ///   it depends on the inversion of a correlation (n, n) matrix which is an O(n3) operation.
 ///   To work around this problem the library implements dimension reduction using
 ///   Partial Least Squares method upon Kriging method also known as KPLS algorithm (see Reference)
+/// * Training inputs and outputs are always standardized (per-dimension mean and standard deviation)
+///   before fitting: scaling factors are stored within the model and applied transparently
+///   on predictions, so inputs spanning different orders of magnitude do not require
+///   prior normalization.
 /// * GP models can be saved and loaded using [serde](https://serde.rs/).
 ///   See `serializable` feature section below.
 ///
@@ -173,6 +182,9 @@ pub struct GaussianProcess<F: Float, Mean: RegressionModel<F>, Corr: Correlation
     /// Reduced likelihood value (result from internal optimization)
     /// Maybe used to compare different trained models
     likelihood: F,
+    /// Number of likelihood evaluations of the hyperparameters optimization
+    #[cfg_attr(feature = "serializable", serde(default))]
+    likelihood_evals: usize,
     /// Gaussian process internal fitted params
     inner_params: GpInnerParams<F>,
     /// Weights in case of KPLS dimension reduction coming from PLS regression (orig_dim, kpls_dim)
@@ -192,6 +204,18 @@ pub(crate) enum GpSamplingMethod {
     EigenValues,
 }
 
+/// Prediction mode of the GP mean when a nugget (or observation noise) is used
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PredictMode {
+    /// Smoothing predictor: the nugget is considered as noise, hence training outputs
+    /// are not exactly reproduced at training points
+    #[default]
+    Filter,
+    /// Interpolating predictor: the nugget is accounted for in the correlation
+    /// with training points, hence training outputs are reproduced at training points
+    Interpolate,
+}
+
 /// Kriging as GP special case when using constant mean and squared exponential correlation
 pub type Kriging<F> = GpParams<F, ConstantMean, SquaredExponentialCorr>;
 
@@ -208,6 +232,7 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> Clone
         Self {
             theta: self.theta.to_owned(),
             likelihood: self.likelihood,
+            likelihood_evals: self.likelihood_evals,
             inner_params: self.inner_params.clone(),
             w_star: self.w_star.to_owned(),
             xt_norm: self.xt_norm.clone(),
@@ -245,12 +270,92 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
 
     /// Predict output values at n given `x` points of nx components specified as a (n, nx) matrix.
     /// Returns n scalar output values as a vector (n,).
+    /// Equivalent to [`GaussianProcess::predict_with_mode`] with [`PredictMode::Filter`].
     pub fn predict(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Result<Array1<F>> {
+        self.predict_with_mode(x, PredictMode::Filter)
+    }
+
+    /// Predict output value at a single `x` point of nx components specified as a (nx,) vector.
+    ///
+    /// Fast path of [`GaussianProcess::predict`] for one point: the dual weights `R^-1.(y - F.beta)`
+    /// being computed at fit time, the prediction only requires the correlations with training points
+    /// and dot products, without allocating intermediate (1, nx) matrices.
+    ///
+    /// *Panics* if `x` does not have nx components.
+    pub fn predict_single(&self, x: &ArrayBase<impl Data<Elem = F>, Ix1>) -> F {
+        let xnorm = (x - &self.xt_norm.mean) / &self.xt_norm.std;
+        let f = self.params.mean.value(&xnorm.view().insert_axis(Axis(0)));
+        let dx = if self.params.categorical_inputs.is_empty() {
+            differences(&xnorm, &self.xt_norm.data)
+        } else {
+            let xnorm = xnorm.view().insert_axis(Axis(0));
+            let z = self.latent_inputs(&xnorm);
+            differences(&z.row(0), &self.latent_inputs(&self.xt_norm.data))
+        };
+        let mut corr = self.corr_value(&dx);
+        if self.params.fit_jitter > F::zero() {
+            Zip::from(corr.column_mut(0))
+                .and(dx.rows())
+                .for_each(|c, d| {
+                    if d.iter().all(|v| v.is_zero()) {
+                        *c += self.params.fit_jitter;
+                    }
+                });
+        }
+        let y_ = f.row(0).dot(&self.inner_params.beta.column(0))
+            + corr.column(0).dot(&self.inner_params.gamma.column(0));
+        y_ * self.yt_norm.std[0] + self.yt_norm.mean[0]
+    }
+
+    /// Predict output values at n given `x` points of nx components specified as a (n, nx) matrix
+    /// into the given (n,) `out` vector which is written in place, point by point using
+    /// [`GaussianProcess::predict_single`], hence without allocating (n,) intermediate arrays.
+    /// Returns an error if `out` length is not n.
+    pub fn predict_into(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        out: &mut ArrayBase<impl DataMut<Elem = F>, Ix1>,
+    ) -> Result<()> {
+        if out.len() != x.nrows() {
+            return Err(GpError::InvalidValueError(format!(
+                "Output length should be {}, got {}",
+                x.nrows(),
+                out.len()
+            )));
+        }
+        Zip::from(out)
+            .and(x.rows())
+            .for_each(|y, xi| *y = self.predict_single(&xi));
+        Ok(())
+    }
+
+    /// Predict output values at n given `x` points of nx components specified as a (n, nx) matrix
+    /// using the given prediction `mode` which only matters at training points.
+    /// Returns n scalar output values as a vector (n,).
+    pub fn predict_with_mode(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        mode: PredictMode,
+    ) -> Result<Array1<F>> {
         let xnorm = (x - &self.xt_norm.mean) / &self.xt_norm.std;
         // Compute the mean term at x
         let f = self.params.mean.value(&xnorm);
         // Compute the correlation term at x
-        let corr = self._compute_correlation(&xnorm);
+        let mut corr = self._compute_correlation(&xnorm);
+        if mode == PredictMode::Interpolate {
+            let noise = self.params.noise_diag();
+            // Add the diagonal terms of the training correlation matrix at training points
+            Zip::from(corr.rows_mut())
+                .and(xnorm.rows())
+                .for_each(|mut c, xi| {
+                    for (j, xj) in self.xt_norm.data.rows().into_iter().enumerate() {
+                        if xi == xj {
+                            c[j] += self.params.nugget
+                                + noise.as_ref().map_or(F::zero(), |noise| noise[j]);
+                        }
+                    }
+                });
+        }
         // Scaled predictor
         let y_ = &f.dot(&self.inner_params.beta) + &corr.dot(&self.inner_params.gamma);
         // Predictor
@@ -261,23 +366,102 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
     /// Returns n variance values as (n, 1) column vector.
     pub fn predict_var(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Result<Array2<F>> {
         let (rt, u, _) = self._compute_rt_u(x);
+        Ok(self._compute_mse(&rt, &u))
+    }
+
+    /// Predict observation noise variances at n given `x` points of nx components specified
+    /// as a (n, nx) matrix: the nugget times the process variance, plus the observation noise
+    /// (see [GpParams::observation_noise]) at training points.
+    /// Returns n variance values as (n, 1) column vector to be added to [`GaussianProcess::predict_var`]
+    /// to get variances of noisy observations.
+    pub fn predict_noise_var(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array2<F> {
+        let noise = self.params.noise_diag();
+        let xt = &self.training_data.0;
+        let noise_var = x.rows().into_iter().map(|xi| {
+            let obs_noise = noise
+                .as_ref()
+                .and_then(|noise| {
+                    xt.rows()
+                        .into_iter()
+                        .position(|xj| xj == xi)
+                        .map(|j| noise[j])
+                })
+                .unwrap_or(F::zero());
+            self.inner_params.sigma2 * (self.params.nugget + obs_noise)
+        });
+        Array1::from_iter(noise_var).insert_axis(Axis(1))
+    }
 
+    /// Predict both output values and variances at n given `x` points of nx components
+    /// specified as a (n, nx) matrix.
+    /// Correlations with training points are computed only once, which is cheaper than
+    /// calling [`GaussianProcess::predict`] then [`GaussianProcess::predict_var`].
+    /// Returns n scalar output values as a vector (n,) and n variance values as (n, 1) column vector.
+    pub fn predict_valvar(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    ) -> Result<(Array1<F>, Array2<F>)> {
+        let xnorm = (x - &self.xt_norm.mean) / &self.xt_norm.std;
+        let f = self.params.mean.value(&xnorm);
+        let corr = self._compute_correlation(&xnorm);
+        let y_ = &f.dot(&self.inner_params.beta) + &corr.dot(&self.inner_params.gamma);
+        let y = (&y_ * &self.yt_norm.std + &self.yt_norm.mean).remove_axis(Axis(1));
+
+        let (rt, u) = self._compute_rt_u_from(&f, &corr);
+        Ok((y, self._compute_mse(&rt, &u)))
+    }
+
+    /// Predict the prior mean (i.e. the trend given by the regression model) at n given `x` points
+    /// of nx components specified as a (n, nx) matrix, the prediction reverts to it far from training points.
+    /// Returns n scalar values as a vector (n,).
+    pub fn prior_mean(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array1<F> {
+        let xnorm = (x - &self.xt_norm.mean) / &self.xt_norm.std;
+        let f = self.params.mean.value(&xnorm);
+        let y_ = f.dot(&self.inner_params.beta);
+        (&y_ * &self.yt_norm.std + &self.yt_norm.mean).remove_axis(Axis(1))
+    }
+
+    /// Predict the contributions of the training points to the output value at a given `x` point.
+    /// Returns a vector (nt,) of the terms `r(x, xt_i) * gamma_i` scaled to the output space
+    /// which sum to the predicted value minus the mean (trend) term.
+    pub fn predict_contributions(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix1>,
+    ) -> Result<Array1<F>> {
+        let x = x.to_owned().insert_axis(Axis(0));
+        let xnorm = (&x - &self.xt_norm.mean) / &self.xt_norm.std;
+        let corr = self._compute_correlation(&xnorm).remove_axis(Axis(0));
+        let gamma = self.inner_params.gamma.column(0);
+        Ok(&corr * &gamma * self.yt_norm.std[0])
+    }
+
+    /// Compute variances (n, 1) from `rt` and `u` matrices
+    fn _compute_mse(&self, rt: &Array2<F>, u: &Array2<F>) -> Array2<F> {
         let mut b = Array::ones(rt.ncols()) - rt.mapv(|v| v * v).sum_axis(Axis(0))
             + u.mapv(|v: F| v * v).sum_axis(Axis(0));
         b.mapv_inplace(|v| self.inner_params.sigma2 * v);
-        let mse = b.into_shape((x.nrows(), 1)).unwrap();
+        let mse = b.into_shape((rt.ncols(), 1)).unwrap();
 
         // Mean Squared Error might be slightly negative depending on
         // machine precision: set to zero in that case
-        Ok(mse.mapv(|v| if v < F::zero() { F::zero() } else { F::cast(v) }))
+        let n_neg = mse.iter().filter(|&&v| v < F::zero()).count();
+        if n_neg > 0 {
+            debug!(
+                "Negative variance clamped to zero at {} point(s) (min={})",
+                n_neg,
+                mse.min().unwrap()
+            );
+        }
+        mse.mapv(|v| if v < F::zero() { F::zero() } else { F::cast(v) })
     }
 
     /// Compute covariance matrix given x points specified as a (n, nx) matrix
     fn _compute_covariance(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array2<F> {
         let (rt, u, xnorm) = self._compute_rt_u(x);
 
-        let cross_dx = pairwise_differences(&xnorm, &xnorm);
-        let k = self.params.corr.value(&cross_dx, &self.theta, &self.w_star);
+        let z = self.latent_inputs(&xnorm);
+        let cross_dx = pairwise_differences(&z, &z);
+        let k = self.corr_value(&cross_dx);
         let k = k.into_shape((xnorm.nrows(), xnorm.nrows())).unwrap();
 
         // let cov_matrix =
@@ -294,25 +478,20 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
         x: &ArrayBase<impl Data<Elem = F>, Ix2>,
     ) -> (Array2<F>, Array2<F>, Array2<F>) {
         let xnorm = (x - &self.xt_norm.mean) / &self.xt_norm.std;
+        let f = self.params.mean.value(&xnorm);
         let corr = self._compute_correlation(&xnorm);
+        let (rt, u) = self._compute_rt_u_from(&f, &corr);
+        (rt, u, xnorm)
+    }
+
+    /// Compute `rt` and `u` matrices given mean term values and correlations
+    /// with training points at normalized x
+    fn _compute_rt_u_from(&self, f: &Array2<F>, corr: &Array2<F>) -> (Array2<F>, Array2<F>) {
         let inners = &self.inner_params;
 
-        let corr_t = corr.t().to_owned();
-        #[cfg(feature = "blas")]
-        let rt = inners
-            .r_chol
-            .to_owned()
-            .with_lapack()
-            .solve_triangular(UPLO::Lower, Diag::NonUnit, &corr_t.with_lapack())
-            .unwrap()
-            .without_lapack();
-        #[cfg(not(feature = "blas"))]
-        let rt = inners
-            .r_chol
-            .solve_triangular(&corr_t, UPLO::Lower)
-            .unwrap();
+        let rt = self._solve_r_chol(&corr.t().to_owned());
 
-        let rhs = inners.ft.t().dot(&rt) - self.params.mean.value(&xnorm).t();
+        let rhs = inners.ft.t().dot(&rt) - f.t();
         #[cfg(feature = "blas")]
         let u = inners
             .ft_qr_r
@@ -328,18 +507,217 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
             .t()
             .solve_triangular(&rhs, UPLO::Lower)
             .unwrap();
-        (rt, u, xnorm)
+        (rt, u)
+    }
+
+    /// Solve `r_chol * z = rhs` where `r_chol` is the lower triangular
+    /// Cholesky factor of the correlation matrix of training points
+    fn _solve_r_chol(&self, rhs: &Array2<F>) -> Array2<F> {
+        #[cfg(feature = "blas")]
+        let z = self
+            .inner_params
+            .r_chol
+            .to_owned()
+            .with_lapack()
+            .solve_triangular(UPLO::Lower, Diag::NonUnit, &rhs.to_owned().with_lapack())
+            .unwrap()
+            .without_lapack();
+        #[cfg(not(feature = "blas"))]
+        let z = self
+            .inner_params
+            .r_chol
+            .solve_triangular(rhs, UPLO::Lower)
+            .unwrap();
+        z
+    }
+
+    /// Add one observation `(x, y)` to the fitted gaussian process without retraining it.
+    ///
+    /// Hyperparameters (theta) as well as input/output normalization parameters are kept unchanged:
+    /// the Cholesky factor of the correlation matrix is extended with the new point
+    /// (O(n^2) instead of O(n^3) for a full refactorization), then regression and
+    /// gaussian process weights are updated accordingly.
+    /// Use [`GpParams`] fit to get a new model with reoptimized hyperparameters.
+    ///
+    /// Returns an error if `x` dimension does not match training inputs dimension,
+    /// if observation noise or sample weights were specified at training or if the extended correlation matrix is not positive definite
+    /// (typically when `x` is already a training point and nugget is too small).
+    pub fn add_observation(&mut self, x: &ArrayBase<impl Data<Elem = F>, Ix1>, y: F) -> Result<()> {
+        if x.len() != self.xt_norm.ncols() {
+            return Err(GpError::InvalidValueError(format!(
+                "Observation dimension {} should match training input dimension {}",
+                x.len(),
+                self.xt_norm.ncols()
+            )));
+        }
+        if self.params.observation_noise.is_some() || self.params.sample_weights.is_some() {
+            return Err(GpError::InvalidValueError(
+                "Observation noise or weight of added point is unknown, refit the model instead"
+                    .to_string(),
+            ));
+        }
+        let xnorm = ((x - &self.xt_norm.mean) / &self.xt_norm.std).insert_axis(Axis(0));
+        let ynorm = (y - self.yt_norm.mean[0]) / self.yt_norm.std[0];
+
+        // Extend lower triangular factor L with a new row [l^T, d]
+        // where L.l = r(x, xt) and d^2 = r(x, x) - l^T.l
+        let corr = self._compute_correlation(&xnorm);
+        let l = self._solve_r_chol(&corr.t().to_owned());
+        let d2 = F::one() + self.params.nugget + self.params.fit_jitter - l.mapv(|v| v * v).sum();
+        if d2 <= F::zero() {
+            return Err(GpError::LikelihoodComputationError(
+                "Correlation matrix is not positive definite, \
+                observation may be too close to an existing training point"
+                    .to_string(),
+            ));
+        }
+        let n = self.inner_params.r_chol.nrows();
+        let mut r_chol = Array2::zeros((n + 1, n + 1));
+        r_chol
+            .slice_mut(s![..n, ..n])
+            .assign(&self.inner_params.r_chol);
+        r_chol.slice_mut(s![n, ..n]).assign(&l.column(0));
+        r_chol[[n, n]] = d2.sqrt();
+
+        self.xt_norm.data.push_row(xnorm.row(0)).unwrap();
+        self.yt_norm
+            .data
+            .push_row(Array1::from_elem(1, ynorm).view())
+            .unwrap();
+        self.training_data.0.push_row(x.view()).unwrap();
+        self.training_data
+            .1
+            .push(Axis(0), Array::from_elem((), y).view())
+            .unwrap();
+
+        let fx = self.params.mean.value(&self.xt_norm.data);
+        let (likelihood, inner_params) = reduced_likelihood_from_chol(&fx, r_chol, &self.yt_norm)?;
+        self.likelihood = likelihood;
+        self.inner_params = inner_params;
+        Ok(())
+    }
+
+    /// Fit the gaussian process to other output values `y` given at the same training inputs
+    /// without retraining it, typically to handle several outputs of a same function.
+    ///
+    /// Hyperparameters (theta) and the Cholesky factor of the correlation matrix are shared
+    /// with the current model (no refactorization), only the output normalization, regression
+    /// and gaussian process weights and the variance are computed from `y`.
+    /// Note that theta is not reoptimized, hence it remains the one fitted on the current model outputs.
+    ///
+    /// Returns an error if `y` length does not match the number of training points.
+    pub fn fit_output(&self, y: &ArrayBase<impl Data<Elem = F>, Ix1>) -> Result<Self> {
+        if y.len() != self.training_data.0.nrows() {
+            return Err(GpError::InvalidValueError(format!(
+                "Output values length {} should match the number of training points {}",
+                y.len(),
+                self.training_data.0.nrows()
+            )));
+        }
+        let yt_norm = NormalizedData::new(&y.to_owned().insert_axis(Axis(1)));
+        let fx = self.params.mean.value(&self.xt_norm.data);
+        let (likelihood, inner_params) =
+            reduced_likelihood_from_chol(&fx, self.inner_params.r_chol.to_owned(), &yt_norm)?;
+        Ok(GaussianProcess {
+            likelihood,
+            inner_params,
+            yt_norm,
+            training_data: (self.training_data.0.to_owned(), y.to_owned()),
+            ..self.clone()
+        })
     }
 
     /// Compute correlation matrix given x points specified as a (n, nx) matrix
     fn _compute_correlation(&self, xnorm: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array2<F> {
         // Get pairwise componentwise L1-distances to the input training set
-        let dx = pairwise_differences(xnorm, &self.xt_norm.data);
+        let dx = pairwise_differences(
+            &self.latent_inputs(xnorm),
+            &self.latent_inputs(&self.xt_norm.data),
+        );
         // Compute the correlation function
-        let r = self.params.corr.value(&dx, &self.theta, &self.w_star);
+        let r = self.corr_value(&dx);
         let n_obs = xnorm.nrows();
         let nt = self.xt_norm.data.nrows();
-        r.into_shape((n_obs, nt)).unwrap().to_owned()
+        let mut r = r.into_shape((n_obs, nt)).unwrap().to_owned();
+        if self.params.fit_jitter > F::zero() {
+            // Fit jitter is part of the correlation of training points with themselves
+            Zip::from(r.rows_mut())
+                .and(xnorm.rows())
+                .for_each(|mut c, xi| {
+                    for (j, xj) in self.xt_norm.data.rows().into_iter().enumerate() {
+                        if xi == xj {
+                            c[j] += self.params.fit_jitter;
+                        }
+                    }
+                });
+        }
+        r
+    }
+
+    /// Theta of the correlation model, i.e. theta without latent coordinates of categorical inputs levels
+    fn corr_theta(&self) -> ArrayView1<'_, F> {
+        self.theta
+            .slice(s![..self.theta.len() - self.params.n_latent()])
+    }
+
+    /// Map categorical inputs of scaled points `xnorm` (n, nx) to their latent coordinates,
+    /// points are returned unchanged when there is no categorical input
+    fn latent_inputs<'a>(
+        &self,
+        xnorm: &'a ArrayBase<impl Data<Elem = F>, Ix2>,
+    ) -> CowArray<'a, F, Ix2> {
+        if self.params.categorical_inputs.is_empty() {
+            xnorm.view().into()
+        } else {
+            let latent = self
+                .theta
+                .slice(s![self.theta.len() - self.params.n_latent()..]);
+            latent_inputs(
+                xnorm,
+                &self.params.categorical_inputs,
+                &latent,
+                &self.xt_norm.std,
+            )
+            .into()
+        }
+    }
+
+    /// Compute correlations given differences `d` (n, nx) between (latent) inputs
+    fn corr_value(&self, d: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array2<F> {
+        self.params.corr.value(d, &self.corr_theta(), &self.w_star)
+    }
+
+    /// Compute jacobian (nt, nx) of correlations with training points at scaled point `xnorm` (nx,)
+    fn corr_jacobian(&self, xnorm: &ArrayBase<impl Data<Elem = F>, Ix1>) -> Array2<F> {
+        if self.params.categorical_inputs.is_empty() {
+            return self
+                .params
+                .corr
+                .jacobian(xnorm, &self.xt_norm.data, &self.theta, &self.w_star);
+        }
+        let xnorm = xnorm.view().insert_axis(Axis(0));
+        let z = self.latent_inputs(&xnorm);
+        let mut jac = self.params.corr.jacobian(
+            &z.row(0),
+            &self.latent_inputs(&self.xt_norm.data),
+            &self.corr_theta(),
+            &self.w_star,
+        );
+        // chain rule: the latent coordinate of a categorical input is linear in its one-hot columns
+        let latent = self
+            .theta
+            .slice(s![self.theta.len() - self.params.n_latent()..]);
+        let mut offset = 0;
+        for &(first, k) in self.params.categorical_inputs.iter() {
+            let dz = jac.column(first).to_owned();
+            jac.column_mut(first).fill(F::zero());
+            for l in 1..k {
+                let factor = latent[offset + l - 1] * self.xt_norm.std[first + l];
+                jac.column_mut(first + l).assign(&(&dz * factor));
+            }
+            offset += k - 1;
+        }
+        jac
     }
 
     /// Sample the gaussian process for `n_traj` trajectories using cholesky decomposition
@@ -372,11 +750,19 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
         sample(x, mean.insert_axis(Axis(1)), cov, n_traj, method)
     }
 
-    /// Retrieve optimized hyperparameters theta
+    /// Retrieve optimized hyperparameters theta, followed by latent coordinates
+    /// of categorical inputs levels if any (see [GpParams::categorical_inputs])
     pub fn theta(&self) -> &Array1<F> {
         &self.theta
     }
 
+    /// Relevance of each input dimension derived from the optimized hyperparameters theta
+    /// (projected back onto the original input space when KPLS is used),
+    /// normalized to sum to one: the larger the value the more influential the input
+    pub fn relevance(&self) -> Array1<F> {
+        normalized_relevance(&self.corr_theta(), &self.w_star)
+    }
+
     /// Estimated variance
     pub fn variance(&self) -> F {
         self.inner_params.sigma2
@@ -387,6 +773,99 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
         self.likelihood
     }
 
+    /// Retrieve the number of likelihood evaluations used to optimize hyperparameters
+    /// (zero when theta is fixed)
+    pub fn likelihood_evals(&self) -> usize {
+        self.likelihood_evals
+    }
+
+    /// Reduced likelihood evaluated at each theta given as the rows of `theta_grid` (n, n_theta)
+    /// on the training data of this model which is left unchanged.
+    /// The value is `-inf` where the correlation matrix can not be factorized.
+    ///
+    /// Returns an error if the number of columns of `theta_grid` does not match theta length.
+    pub fn likelihood_profile(
+        &self,
+        theta_grid: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    ) -> Result<Array1<F>> {
+        if theta_grid.ncols() != self.theta.len() {
+            return Err(GpError::InvalidValueError(format!(
+                "Theta grid should have {} columns, got {}",
+                self.theta.len(),
+                theta_grid.ncols()
+            )));
+        }
+        let x_distances = DistanceMatrix::new(&self.xt_norm.data);
+        let fx = self.params.mean.value(&self.xt_norm.data);
+        let noise = self.params.noise_diag();
+        let profile = theta_grid
+            .rows()
+            .into_iter()
+            .map(|theta| {
+                let rxx = training_correlations(
+                    &self.params,
+                    &self.xt_norm,
+                    &x_distances,
+                    &theta,
+                    &self.w_star,
+                );
+                reduced_likelihood(
+                    &fx,
+                    rxx,
+                    &x_distances,
+                    &self.yt_norm,
+                    self.params.nugget + self.params.fit_jitter,
+                    noise.as_ref(),
+                )
+                .map_or(F::neg_infinity(), |(lkh, _)| lkh)
+            })
+            .collect();
+        Ok(profile)
+    }
+
+    /// Flags the points of `x` (n, nx) lying outside the bounding box of the training inputs
+    /// where the prediction extrapolates, reverting to the prior as points get far away
+    pub fn is_extrapolating(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array1<bool> {
+        outside_bounding_box(&self.training_data.0, x)
+    }
+
+    /// Retrieve training data (inputs (n, nx), outputs (n,)) the model was fitted on
+    pub fn training_data(&self) -> &(Array2<F>, Array1<F>) {
+        &self.training_data
+    }
+
+    /// Retrieve the scaling applied to training inputs
+    pub fn scaler(&self) -> Scaler {
+        self.params.scaler
+    }
+
+    /// Retrieve parameters used to fit the model (e.g. to fit a model of the same kind on other data)
+    pub fn fit_params(&self) -> &GpValidParams<F, Mean, Corr> {
+        &self.params
+    }
+
+    /// Retrieve nugget value used to fit the model
+    pub fn nugget(&self) -> F {
+        self.params.nugget
+    }
+
+    /// Retrieve jitter value added to the correlation matrix diagonal to fit the model
+    pub fn fit_jitter(&self) -> F {
+        self.params.fit_jitter
+    }
+
+    /// Estimate the condition number of the training correlation matrix \[R\]
+    /// as the squared ratio of the largest to the smallest diagonal element of its
+    /// Cholesky factor (a lower bound of the actual 2-norm condition number).
+    /// A huge value denotes a nearly singular matrix, consider raising the nugget.
+    pub fn condition_number(&self) -> F {
+        let diag = self.inner_params.r_chol.diag();
+        let max = diag.fold(F::zero(), |m, &v| m.max(v.abs()));
+        let min = diag.fold(F::infinity(), |m, &v| m.min(v.abs()));
+        let ratio = max / min;
+        ratio * ratio
+    }
+
     /// Retrieve number of PLS components 1 <= n <= x dimension
     pub fn kpls_dim(&self) -> Option<usize> {
         if self.w_star.ncols() < self.xt_norm.ncols() {
@@ -396,6 +875,12 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
         }
     }
 
+    /// Retrieve the PLS rotation matrix (x dimension, number of PLS components)
+    /// projecting inputs onto the reduced space if any
+    pub fn pls_rotation(&self) -> Option<&Array2<F>> {
+        self.kpls_dim().map(|_| &self.w_star)
+    }
+
     /// Retrieve input and output dimensions
     pub fn dims(&self) -> (usize, usize) {
         (self.xt_norm.ncols(), self.yt_norm.ncols())
@@ -495,10 +980,7 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
         let df = self.params.mean.jacobian(&xnorm.row(0));
         let df_dx = df.t().dot(beta);
 
-        let dr =
-            self.params
-                .corr
-                .jacobian(&xnorm.row(0), &self.xt_norm.data, &self.theta, &self.w_star);
+        let dr = self.corr_jacobian(&xnorm.row(0));
 
         let dr_dx = df_dx + dr.t().dot(gamma);
         Zip::from(jac.rows_mut())
@@ -521,15 +1003,15 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
     ) -> Array1<F> {
         let x = &(x.to_owned().insert_axis(Axis(0)));
         let xnorm = (x - &self.xt_norm.mean) / &self.xt_norm.std;
-        let dx = pairwise_differences(&xnorm, &self.xt_norm.data);
+        let dx = pairwise_differences(
+            &self.latent_inputs(&xnorm),
+            &self.latent_inputs(&self.xt_norm.data),
+        );
         let sigma2 = self.inner_params.sigma2;
         let r_chol = &self.inner_params.r_chol;
 
-        let r = self.params.corr.value(&dx, &self.theta, &self.w_star);
-        let dr =
-            self.params
-                .corr
-                .jacobian(&xnorm.row(0), &self.xt_norm.data, &self.theta, &self.w_star);
+        let r = self.corr_value(&dx);
+        let dr = self.corr_jacobian(&xnorm.row(0));
 
         // rho1 = Rc^-1 . r(x, X)
         let rho1 = r_chol.solve_triangular(&r, UPLO::Lower).unwrap();
@@ -590,21 +1072,16 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GaussianProc
         let x = &(x.to_owned().insert_axis(Axis(0)));
         let xnorm = (x - &self.xt_norm.mean) / &self.xt_norm.std;
 
-        let dx = pairwise_differences(&xnorm, &self.xt_norm.data);
+        let dx = pairwise_differences(
+            &self.latent_inputs(&xnorm),
+            &self.latent_inputs(&self.xt_norm.data),
+        );
 
         let sigma2 = self.inner_params.sigma2;
         let r_chol = &self.inner_params.r_chol.to_owned().with_lapack();
 
-        let r = self
-            .params
-            .corr
-            .value(&dx, &self.theta, &self.w_star)
-            .with_lapack();
-        let dr = self
-            .params
-            .corr
-            .jacobian(&xnorm.row(0), &self.xt_norm.data, &self.theta, &self.w_star)
-            .with_lapack();
+        let r = self.corr_value(&dx).with_lapack();
+        let dr = self.corr_jacobian(&xnorm.row(0)).with_lapack();
 
         let rho1 = r_chol
             .solve_triangular(UPLO::Lower, Diag::NonUnit, &r)
@@ -739,30 +1216,132 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>, D: Data<Elem
         &self,
         dataset: &DatasetBase<ArrayBase<D, Ix2>, ArrayBase<D, Ix1>>,
     ) -> Result<Self::Object> {
-        let x = dataset.records();
-        let y = dataset.targets().to_owned().insert_axis(Axis(1));
+        let y = dataset.targets().view().insert_axis(Axis(1));
+        let (gp, _) = self.fit_weighted(dataset.records(), &y, &Array1::ones(1))?;
+        Ok(gp)
+    }
+}
 
-        if let Some(d) = self.kpls_dim() {
-            if *d > x.ncols() {
-                return Err(GpError::InvalidValueError(format!(
-                    "Dimension reduction {} should be smaller than actual \
-                    training input dimensions {}",
+impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GpValidParams<F, Mean, Corr> {
+    /// Fit a GP for each output (column) of `y` given as a (n, n_outputs) matrix on `x` (n, nx)
+    /// training inputs. Hyperparameters are shared: they maximize the sum of the reduced likelihoods
+    /// of the outputs weighted by `weights` (n_outputs,), and so is the correlation matrix factorization.
+    ///
+    /// Returns an error if weights length is not n_outputs or if weights are negative or all zero.
+    pub fn fit_outputs(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        y: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        weights: &ArrayBase<impl Data<Elem = F>, Ix1>,
+    ) -> Result<Vec<GaussianProcess<F, Mean, Corr>>> {
+        if weights.len() != y.ncols() {
+            return Err(GpError::InvalidValueError(format!(
+                "Output weights length should be {}, got {}",
+                y.ncols(),
+                weights.len()
+            )));
+        }
+        if weights.iter().any(|w| !w.is_finite() || *w < F::zero()) || weights.sum() <= F::zero() {
+            return Err(GpError::InvalidValueError(format!(
+                "Output weights should be non negative and not all zero, got {weights}"
+            )));
+        }
+        let (gp, y) = self.fit_weighted(x, y, &weights.to_owned())?;
+        let mut gps = vec![gp];
+        for yk in y.columns().into_iter().skip(1) {
+            gps.push(gps[0].fit_output(&yk)?);
+        }
+        Ok(gps)
+    }
+
+    /// Fit a GP on the first output (column) of `y` (n, n_outputs), hyperparameters maximizing
+    /// the sum of the reduced likelihoods of the outputs weighted by `weights` (n_outputs,).
+    /// Returns the GP and the training outputs after duplicates merging if any.
+    fn fit_weighted(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        y: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        weights: &Array1<F>,
+    ) -> Result<(GaussianProcess<F, Mean, Corr>, Array2<F>)> {
+        for yk in y.columns() {
+            check_finite(x, &yk)?;
+        }
+        let (x, y) = match self.merge_duplicates() {
+            Some(tol) => merge_duplicates(x, y, tol),
+            None => (x.to_owned(), y.to_owned()),
+        };
+        let x = &x;
+
+        if x.nrows() == 0 {
+            return Err(GpError::InvalidValueError(
+                "At least one training point is required".to_string(),
+            ));
+        }
+
+        let kpls_dim = match self.kpls_dim() {
+            Some(d) if *d > x.ncols() => {
+                if self.strict_kpls() {
+                    return Err(GpError::InvalidValueError(format!(
+                        "Dimension reduction {} should be smaller than actual \
+                        training input dimensions {}",
+                        d,
+                        x.ncols()
+                    )));
+                }
+                warn!(
+                    "Dimension reduction {} greater than training input dimensions {}, clamped to {}",
                     d,
+                    x.ncols(),
                     x.ncols()
+                );
+                Some(x.ncols())
+            }
+            d => d.copied(),
+        };
+
+        if let Some(noise) = self.observation_noise() {
+            if noise.len() != x.nrows() {
+                return Err(GpError::InvalidValueError(format!(
+                    "Observation noise length {} should match the number of training points {}",
+                    noise.len(),
+                    x.nrows()
+                )));
+            }
+        }
+
+        if let Some(weights) = self.sample_weights() {
+            if weights.len() != x.nrows() {
+                return Err(GpError::InvalidValueError(format!(
+                    "Sample weights length {} should match the number of training points {}",
+                    weights.len(),
+                    x.nrows()
                 )));
-            };
+            }
+            if self.observation_noise().is_none() {
+                warn!("Sample weights only scale the nugget as no observation noise is given");
+            }
         }
+        let noise = self.noise_diag();
 
-        let xtrain = NormalizedData::new(x);
-        let ytrain = NormalizedData::new(&y);
+        let xtrain = NormalizedData::with_scaler(x, self.scaler());
+        let ytrains = y
+            .columns()
+            .into_iter()
+            .map(|yk| NormalizedData::new(&yk.insert_axis(Axis(1))))
+            .collect::<Vec<_>>();
 
         let mut w_star = Array2::eye(x.ncols());
-        if let Some(n_components) = self.kpls_dim() {
+        if let Some(n_components) = kpls_dim {
             let ds = Dataset::new(x.to_owned(), y.to_owned());
-            w_star = PlsRegression::params(*n_components).fit(&ds).map_or_else(
+            w_star = PlsRegression::params(n_components).fit(&ds).map_or_else(
                 |e| match e {
-                    linfa_pls::PlsError::PowerMethodConstantResidualError() => {
-                        Ok(Array2::zeros((x.ncols(), *n_components)))
+                    linfa_pls::PlsError::PowerMethodConstantResidualError()
+                        if !self.strict_kpls() =>
+                    {
+                        warn!(
+                            "PLS components can not be extracted from training data, set to zero"
+                        );
+                        Ok(Array2::zeros((x.ncols(), n_components)))
                     }
                     err => Err(err),
                 },
@@ -774,36 +1353,122 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>, D: Data<Elem
             .d
             .mapv(|v| num_traits::float::Float::abs(v))
             .sum_axis(Axis(1));
-        if *sums.min().unwrap() == F::zero() {
+        if sums.min().is_ok_and(|m| *m == F::zero()) {
             println!(
                 "Warning: multiple x input features have the same value (at least same row twice)."
             );
         }
+        if self.merge_duplicates().is_none() {
+            let pairs = near_duplicates(x, F::cast(NEAR_DUPLICATE_TOL));
+            if !pairs.is_empty() {
+                warn!(
+                    "Training points nearly coincide (rows {:?}) which may make the fit fail, \
+                    consider removing them or using `merge_duplicates` option",
+                    pairs
+                );
+            }
+        }
         let fx = self.mean().value(&xtrain.data);
+        if fx.ncols() > x.nrows() {
+            return Err(GpError::InvalidValueError(format!(
+                "Regression model requires at least {} training points, got {}",
+                fx.ncols(),
+                x.nrows()
+            )));
+        }
+
+        let categories = self.categorical_inputs();
+        for &(first, k) in categories {
+            if first + k > x.ncols() {
+                return Err(GpError::InvalidValueError(format!(
+                    "Categorical input columns {}..{} out of training input dimension {}",
+                    first,
+                    first + k,
+                    x.ncols()
+                )));
+            }
+            let one_hot = x
+                .slice(s![.., first..first + k])
+                .rows()
+                .into_iter()
+                .all(|r| {
+                    r.sum() == F::one() && r.iter().all(|v| *v == F::zero() || *v == F::one())
+                });
+            if !one_hot {
+                return Err(GpError::InvalidValueError(format!(
+                    "Categorical input columns {}..{} should be one-hot encoded",
+                    first,
+                    first + k
+                )));
+            }
+        }
+        let n_theta = self.corr().n_theta(w_star.ncols());
+        let n_latent = self.n_latent();
+        let training_corr = |theta: &ArrayView1<F>| {
+            training_correlations(self, &xtrain, &x_distances, theta, &w_star)
+        };
 
+        let likelihood_evals = AtomicUsize::new(0);
         let opt_params = match self.theta_tuning() {
             ThetaTuning::Fixed(init) => {
                 // Easy path no optimization
+                if init.len() != n_theta + n_latent {
+                    return Err(GpError::InvalidValueError(format!(
+                        "Fixed theta should be of length {}, got {}",
+                        n_theta + n_latent,
+                        init.len()
+                    )));
+                }
                 Array1::from_vec(init.to_vec())
             }
             ThetaTuning::Optimized { init, bounds } => {
                 // Initial guess for theta
                 let theta0_dim = init.len();
-                let theta0 = if theta0_dim == 1 {
-                    Array1::from_elem(w_star.ncols(), init[0])
-                } else if theta0_dim == w_star.ncols() {
-                    Array::from_vec(init.to_vec())
+                let theta0 = if self.theta_init_from_data() {
+                    // same estimate for each combined correlation model if any
+                    let theta = theta_init_from_distances(&x_distances.d, &w_star);
+                    Array1::from_iter(theta.iter().cycle().take(n_theta).cloned())
+                } else if theta0_dim == 1 {
+                    Array1::from_elem(n_theta, init[0])
+                } else if theta0_dim == n_theta || theta0_dim == n_theta + n_latent {
+                    Array::from_iter(init.iter().take(n_theta).cloned())
                 } else {
                     panic!("Initial guess for theta should be either 1-dim or dim of xtrain (w_star.ncols()), got {}", theta0_dim)
                 };
-
+                // Levels latent coordinates start evenly spread in [0, 1] unless given
+                let latent0 = if theta0_dim == n_theta + n_latent && n_latent > 0 {
+                    Array::from_iter(init.iter().skip(n_theta).cloned())
+                } else {
+                    categories
+                        .iter()
+                        .flat_map(|&(_, k)| (1..k).map(move |l| F::cast(l) / F::cast(k - 1)))
+                        .collect()
+                };
+                // Theta of one-hot columns is left to one, their latent coordinates being optimized instead
+                let free = (0..n_theta)
+                    .filter(|i| {
+                        !categories
+                            .iter()
+                            .any(|&(first, k)| (first..first + k).contains(&(i % w_star.ncols())))
+                    })
+                    .collect::<Vec<_>>();
+                let n_free = free.len();
+                // Optimization parameters are log10 of free theta values followed by latent coordinates
                 let base: f64 = 10.;
+                let to_theta = |x: &[f64]| -> Array1<F> {
+                    let mut theta = Array1::<F>::ones(n_theta + n_latent);
+                    for (j, i) in free.iter().enumerate() {
+                        theta[*i] = F::cast(base.powf(x[j]));
+                    }
+                    for (j, v) in x[n_free..].iter().enumerate() {
+                        theta[n_theta + j] = F::cast(*v);
+                    }
+                    theta
+                };
+
                 let objfn = |x: &[f64], _gradient: Option<&mut [f64]>, _params: &mut ()| -> f64 {
-                    let theta = Array1::from_shape_vec(
-                        (x.len(),),
-                        x.iter().map(|v| base.powf(*v)).collect(),
-                    )
-                    .unwrap();
+                    likelihood_evals.fetch_add(1, Ordering::Relaxed);
+                    let theta = to_theta(x);
                     for v in theta.iter() {
                         // check theta as optimizer may return nan values
                         if v.is_nan() {
@@ -811,20 +1476,33 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>, D: Data<Elem
                             return f64::INFINITY;
                         }
                     }
-                    let theta = theta.mapv(F::cast);
-                    let rxx = self.corr().value(&x_distances.d, &theta, &w_star);
-                    match reduced_likelihood(&fx, rxx, &x_distances, &ytrain, self.nugget()) {
-                        Ok(r) => unsafe { -(*(&r.0 as *const F as *const f64)) },
-                        Err(_) => f64::INFINITY,
+                    let rxx = training_corr(&theta.view());
+                    let Ok(r_chol) = correlation_cholesky(
+                        rxx,
+                        &x_distances,
+                        self.nugget() + self.fit_jitter(),
+                        noise.as_ref(),
+                    ) else {
+                        return f64::INFINITY;
+                    };
+                    let mut likelihood = 0.;
+                    for (ytrain, w) in ytrains.iter().zip(weights.iter()) {
+                        if *w > F::zero() {
+                            match reduced_likelihood_from_chol(&fx, r_chol.to_owned(), ytrain) {
+                                Ok(r) => likelihood += (*w * r.0).to_f64().unwrap(),
+                                Err(_) => return f64::INFINITY,
+                            }
+                        }
                     }
+                    -likelihood
                 };
 
                 // Multistart: user theta0 + 1e-5, 1e-4, 1e-3, 1e-2, 0.1, 1., 10.
                 // let bounds = vec![(F::cast(-6.), F::cast(2.)); theta0.len()];
                 let bounds_dim = bounds.len();
                 let bounds = if bounds_dim == 1 {
-                    vec![bounds[0]; w_star.ncols()]
-                } else if bounds_dim == w_star.ncols() {
+                    vec![bounds[0]; n_theta]
+                } else if bounds_dim == n_theta {
                     bounds.to_vec()
                 } else {
                     panic!(
@@ -834,57 +1512,201 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>, D: Data<Elem
                     )
                 };
 
-                let (params, bounds) = prepare_multistart(self.n_start(), &theta0, &bounds);
-                debug!(
-                    "Optimize with multistart theta = {:?} and bounds = {:?}",
-                    params, bounds
-                );
-                let now = Instant::now();
-                let opt_params = (0..params.nrows())
-                    .into_par_iter()
-                    .map(|i| {
-                        let opt_res = optimize_params(
-                            objfn,
-                            &params.row(i).to_owned(),
-                            &bounds,
-                            CobylaParams {
-                                maxeval: (10 * theta0_dim).max(CobylaParams::default().maxeval),
-                                ..CobylaParams::default()
-                            },
-                        );
+                let theta0 = if self.theta_init_from_data() || x.nrows() == 1 {
+                    Zip::from(&theta0)
+                        .and(&bounds)
+                        .map_collect(|t, (lo, up)| t.max(*lo).min(*up))
+                } else {
+                    theta0
+                };
 
-                        opt_res
-                    })
-                    .reduce(
-                        || (Array::ones((params.ncols(),)), f64::INFINITY),
-                        |a, b| if b.1 < a.1 { b } else { a },
+                if x.nrows() == 1 {
+                    // Likelihood is flat with a single point, nothing to optimize
+                    let mut theta = Array1::ones(n_theta);
+                    free.iter().for_each(|i| theta[*i] = theta0[*i]);
+                    concatenate![Axis(0), theta, latent0]
+                } else {
+                    let theta0 = free.iter().map(|i| theta0[*i]).collect::<Array1<_>>();
+                    let bounds = free.iter().map(|i| bounds[*i]).collect::<Vec<_>>();
+                    let (params, mut bounds) = prepare_multistart(self.n_start(), &theta0, &bounds);
+                    // Latent coordinates are optimized from the same start in all restarts
+                    let latent0 = latent0
+                        .broadcast((params.nrows(), n_latent))
+                        .unwrap()
+                        .to_owned();
+                    let params = concatenate![Axis(1), params, latent0];
+                    bounds.extend(vec![
+                        (F::cast(-LATENT_BOUND), F::cast(LATENT_BOUND));
+                        n_latent
+                    ]);
+                    debug!(
+                        "Optimize with multistart theta = {:?} and bounds = {:?}",
+                        params, bounds
                     );
-                debug!("elapsed optim = {:?}", now.elapsed().as_millis());
-                opt_params.0.mapv(|v| F::cast(base.powf(v)))
+                    let now = Instant::now();
+                    let opt_params = (0..params.nrows())
+                        .into_par_iter()
+                        .map(|i| {
+                            let opt_res = optimize_params(
+                                objfn,
+                                &params.row(i).to_owned(),
+                                &bounds,
+                                CobylaParams {
+                                    maxeval: (10 * theta0_dim).max(CobylaParams::default().maxeval),
+                                    ..CobylaParams::default()
+                                },
+                            );
+
+                            opt_res
+                        })
+                        .reduce(
+                            || (Array::ones((params.ncols(),)), f64::INFINITY),
+                            |a, b| if b.1 < a.1 { b } else { a },
+                        );
+                    debug!("elapsed optim = {:?}", now.elapsed().as_millis());
+                    to_theta(opt_params.0.as_slice().unwrap())
+                }
             }
         };
-        let rxx = self.corr().value(&x_distances.d, &opt_params, &w_star);
-        let (lkh, inner_params) =
-            reduced_likelihood(&fx, rxx, &x_distances, &ytrain, self.nugget())?;
-        Ok(GaussianProcess {
+        let rxx = training_corr(&opt_params.view());
+        let ytrain = ytrains.into_iter().next().unwrap();
+        let (lkh, mut inner_params) = reduced_likelihood(
+            &fx,
+            rxx,
+            &x_distances,
+            &ytrain,
+            self.nugget() + self.fit_jitter(),
+            noise.as_ref(),
+        )?;
+        if x.nrows() == 1 {
+            // Process variance cannot be estimated from a single point: use unit prior variance
+            inner_params.sigma2 = ytrain.std[0] * ytrain.std[0];
+        }
+        let gp = GaussianProcess {
             theta: opt_params,
             likelihood: lkh,
+            likelihood_evals: likelihood_evals.into_inner(),
             inner_params,
             w_star,
             xt_norm: xtrain,
             yt_norm: ytrain,
-            training_data: (x.to_owned(), y.to_owned().remove_axis(Axis(1))),
+            training_data: (x.to_owned(), y.column(0).to_owned()),
             params: self.clone(),
-        })
+        };
+        Ok((gp, y))
     }
 }
 
+/// Correlations between `xtrain` training points with pairwise `x_distances` given `theta`,
+/// latent coordinates of categorical inputs levels being appended to the correlation model theta
+fn training_correlations<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>>(
+    params: &GpValidParams<F, Mean, Corr>,
+    xtrain: &NormalizedData<F>,
+    x_distances: &DistanceMatrix<F>,
+    theta: &ArrayView1<F>,
+    w_star: &Array2<F>,
+) -> Array2<F> {
+    let n_theta = theta.len() - params.n_latent();
+    let (theta, latent) = theta.view().split_at(Axis(0), n_theta);
+    if params.categorical_inputs.is_empty() {
+        params.corr.value(&x_distances.d, &theta, w_star)
+    } else {
+        let z = latent_inputs(
+            &xtrain.data,
+            &params.categorical_inputs,
+            &latent,
+            &xtrain.std,
+        );
+        params
+            .corr
+            .value(&DistanceMatrix::new(&z).d, &theta, w_star)
+    }
+}
+
+/// Bound of the absolute value of categorical inputs levels latent coordinates
+const LATENT_BOUND: f64 = 5.;
+
+/// Distance between training points under which they are considered nearly coincident
+const NEAR_DUPLICATE_TOL: f64 = 1e-10;
+
+/// Euclidean distance between two points, the metric used to detect and merge duplicates
+fn distance<F: Float>(a: &ArrayView1<F>, b: &ArrayView1<F>) -> F {
+    Zip::from(a)
+        .and(b)
+        .fold(F::zero(), |acc, &u, &v| acc + (u - v) * (u - v))
+        .sqrt()
+}
+
+/// Pairs of rows of training points closer than `tol` as they make the correlation matrix
+/// ill-conditioned. Repeated x inputs (replicated observations, possibly with different
+/// noisy outputs) are handled by the nugget and not reported.
+fn near_duplicates<F: Float>(
+    x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    tol: F,
+) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    for i in 0..x.nrows() {
+        for j in (i + 1)..x.nrows() {
+            let dist = distance(&x.row(i), &x.row(j));
+            if dist > F::zero() && dist < tol {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+/// Merge training points closer than `tol` to a previous point, the output values (n, n_outputs)
+/// of the kept point being the mean of the outputs of merged points.
+fn merge_duplicates<F: Float>(
+    x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    y: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    tol: F,
+) -> (Array2<F>, Array2<F>) {
+    let mut merged = vec![false; x.nrows()];
+    let mut kept = vec![];
+    let mut values = Array2::zeros((0, y.ncols()));
+    for i in 0..x.nrows() {
+        if merged[i] {
+            continue;
+        }
+        let (mut sum, mut count) = (y.row(i).to_owned(), 1);
+        for (j, merged_j) in merged.iter_mut().enumerate().skip(i + 1) {
+            if !*merged_j && distance(&x.row(i), &x.row(j)) < tol {
+                *merged_j = true;
+                sum += &y.row(j);
+                count += 1;
+            }
+        }
+        kept.push(i);
+        values.push_row((sum / F::cast(count)).view()).unwrap();
+    }
+    if kept.len() < x.nrows() {
+        debug!(
+            "{} training point(s) merged as duplicates",
+            x.nrows() - kept.len()
+        );
+    }
+    (x.select(Axis(0), &kept), values)
+}
+
+/// Diagonal part of the correlation matrix of n samples: 1 + nugget
+/// plus noise variances of samples if any
+fn correlation_diag<F: Float>(n: usize, nugget: F, noise: Option<&Array1<F>>) -> Array2<F> {
+    let mut r_mx: Array2<F> = Array2::<F>::eye(n).mapv(|v| v + v * nugget);
+    if let Some(noise) = noise {
+        r_mx.diag_mut().zip_mut_with(noise, |r, v| *r += *v);
+    }
+    r_mx
+}
+
 /// Compute reduced likelihood function
 /// fx: mean factors term at x samples,
 /// rxx: correlation factors at x samples,
 /// x_distances: pairwise distances between x samples
 /// ytrain: normalized output training values
-/// nugget: factor to improve numerical stability  
+/// nugget: factor to improve numerical stability
+/// noise: optional noise variances of x samples added to the diagonal of the correlation matrix
 #[cfg(not(feature = "blas"))]
 fn reduced_likelihood<F: Float>(
     fx: &ArrayBase<impl Data<Elem = F>, Ix2>,
@@ -892,16 +1714,45 @@ fn reduced_likelihood<F: Float>(
     x_distances: &DistanceMatrix<F>,
     ytrain: &NormalizedData<F>,
     nugget: F,
+    noise: Option<&Array1<F>>,
 ) -> Result<(F, GpInnerParams<F>)> {
-    // Set up R
-    let mut r_mx: Array2<F> = Array2::<F>::eye(x_distances.n_obs).mapv(|v| (v + v * nugget));
-    for (i, ij) in x_distances.d_indices.outer_iter().enumerate() {
-        r_mx[[ij[0], ij[1]]] = rxx[[i, 0]];
+    let r_chol = correlation_cholesky(rxx, x_distances, nugget, noise)?;
+    reduced_likelihood_from_chol(fx, r_chol, ytrain)
+}
+
+/// Compute the lower triangular cholesky factor of the correlation matrix of x samples
+/// rxx: correlation factors at x samples,
+/// x_distances: pairwise distances between x samples
+/// nugget: factor to improve numerical stability
+/// noise: optional noise variances of x samples added to the diagonal of the correlation matrix
+#[cfg(not(feature = "blas"))]
+fn correlation_cholesky<F: Float>(
+    rxx: ArrayBase<impl Data<Elem = F>, Ix2>,
+    x_distances: &DistanceMatrix<F>,
+    nugget: F,
+    noise: Option<&Array1<F>>,
+) -> Result<Array2<F>> {
+    // Set up R
+    let mut r_mx = correlation_diag(x_distances.n_obs, nugget, noise);
+    for (i, ij) in x_distances.d_indices.outer_iter().enumerate() {
+        r_mx[[ij[0], ij[1]]] = rxx[[i, 0]];
         r_mx[[ij[1], ij[0]]] = rxx[[i, 0]];
     }
-    let fxl = fx;
     // R cholesky decomposition
-    let r_chol = r_mx.cholesky()?;
+    Ok(r_mx.cholesky()?)
+}
+
+/// Compute reduced likelihood function given the cholesky factor of the correlation matrix
+/// fx: mean factors term at x samples,
+/// r_chol: lower triangular cholesky factor of the correlation matrix at x samples,
+/// ytrain: normalized output training values
+#[cfg(not(feature = "blas"))]
+fn reduced_likelihood_from_chol<F: Float>(
+    fx: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    r_chol: Array2<F>,
+    ytrain: &NormalizedData<F>,
+) -> Result<(F, GpInnerParams<F>)> {
+    let fxl = fx;
     // Solve generalized least squared problem
     let ft = r_chol.solve_triangular(fxl, UPLO::Lower)?;
     let (ft_qr_q, ft_qr_r) = ft.qr().unwrap().into_decomp();
@@ -934,7 +1785,7 @@ fn reduced_likelihood<F: Float>(
     let gamma = r_chol.t().solve_triangular_into(rho, UPLO::Upper)?;
     // The determinant of R is equal to the squared product of
     // the diagonal elements of its Cholesky decomposition r_chol
-    let n_obs: F = F::cast(x_distances.n_obs);
+    let n_obs: F = F::cast(r_chol.nrows());
 
     let logdet = r_chol.diag().mapv(|v: F| v.log10()).sum() * F::cast(2.) / n_obs;
 
@@ -963,18 +1814,41 @@ fn reduced_likelihood<F: Float>(
     x_distances: &DistanceMatrix<F>,
     ytrain: &NormalizedData<F>,
     nugget: F,
+    noise: Option<&Array1<F>>,
 ) -> Result<(F, GpInnerParams<F>)> {
+    let r_chol = correlation_cholesky(rxx, x_distances, nugget, noise)?;
+    reduced_likelihood_from_chol(fx, r_chol, ytrain)
+}
+
+/// See non blas version
+#[cfg(feature = "blas")]
+fn correlation_cholesky<F: Float>(
+    rxx: ArrayBase<impl Data<Elem = F>, Ix2>,
+    x_distances: &DistanceMatrix<F>,
+    nugget: F,
+    noise: Option<&Array1<F>>,
+) -> Result<Array2<F>> {
     // Set up R
-    let mut r_mx: Array2<F> = Array2::<F>::eye(x_distances.n_obs).mapv(|v| (v + v * nugget));
+    let mut r_mx = correlation_diag(x_distances.n_obs, nugget, noise);
     for (i, ij) in x_distances.d_indices.outer_iter().enumerate() {
         r_mx[[ij[0], ij[1]]] = rxx[[i, 0]];
         r_mx[[ij[1], ij[0]]] = rxx[[i, 0]];
     }
 
-    let fxl = fx.to_owned().with_lapack();
-
     // R cholesky decomposition
     let r_chol = r_mx.with_lapack().cholesky(UPLO::Lower)?;
+    Ok(r_chol.without_lapack())
+}
+
+/// See non blas version
+#[cfg(feature = "blas")]
+fn reduced_likelihood_from_chol<F: Float>(
+    fx: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    r_chol: Array2<F>,
+    ytrain: &NormalizedData<F>,
+) -> Result<(F, GpInnerParams<F>)> {
+    let fxl = fx.to_owned().with_lapack();
+    let r_chol = r_chol.with_lapack();
 
     // Solve generalized least squared problem
     let ft = r_chol.solve_triangular(UPLO::Lower, Diag::NonUnit, &fxl)?;
@@ -1018,7 +1892,7 @@ fn reduced_likelihood<F: Float>(
 
     // The determinant of R is equal to the squared product of
     // the diagonal elements of its Cholesky decomposition r_chol
-    let n_obs: F = F::cast(x_distances.n_obs);
+    let n_obs: F = F::cast(r_chol.nrows());
 
     let logdet = r_chol
         .to_owned()
@@ -1556,6 +2430,797 @@ mod tests {
         assert_abs_diff_eq!(*gp.theta().to_vec(), expected);
     }
 
+    #[test]
+    fn test_predict_valvar() {
+        let xt = array![[0.0], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![0.0, 1.0, 1.5, 0.9, 1.0];
+        let gp = GaussianProcess::<f64, LinearMean, SquaredExponentialCorr>::params(
+            LinearMean::default(),
+            SquaredExponentialCorr::default(),
+        )
+        .fit(&Dataset::new(xt, yt))
+        .expect("GP fit error");
+
+        let x = Array::linspace(-1., 5., 20).insert_axis(Axis(1));
+        let (y, var) = gp.predict_valvar(&x).expect("GP prediction");
+        assert_abs_diff_eq!(y, gp.predict(&x).unwrap(), epsilon = 1e-12);
+        assert_abs_diff_eq!(var, gp.predict_var(&x).unwrap(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_fit_no_point() {
+        let xt = Array2::<f64>::zeros((0, 2));
+        let yt = Array1::<f64>::zeros(0);
+        let res = Kriging::params().fit(&Dataset::new(xt, yt));
+        assert!(matches!(res, Err(GpError::InvalidValueError(_))));
+    }
+
+    #[test]
+    fn test_fit_single_point() {
+        let xt = array![[0.5, 1.5]];
+        let yt = array![3.];
+        let gp = Kriging::params()
+            .fit(&Dataset::new(xt.clone(), yt))
+            .expect("GP fit error");
+
+        let x = array![[0.5, 1.5], [0., 0.], [2., -1.], [10., 10.]];
+        let (y, var) = gp.predict_valvar(&x).expect("GP prediction");
+        assert_abs_diff_eq!(y, Array1::from_elem(4, 3.), epsilon = 1e-12);
+        assert!(var.iter().all(|v| f64::is_finite(*v)));
+        assert_abs_diff_eq!(var[[0, 0]], 0., epsilon = 1e-6);
+        // far from the training point the variance recovers the prior
+        assert!(var[[3, 0]] >= gp.variance());
+
+        // a linear trend cannot be fitted with a single point
+        let res = GaussianProcess::<f64, LinearMean, SquaredExponentialCorr>::params(
+            LinearMean::default(),
+            SquaredExponentialCorr::default(),
+        )
+        .fit(&Dataset::new(xt, array![3.]));
+        assert!(matches!(res, Err(GpError::InvalidValueError(_))));
+    }
+
+    #[test]
+    fn test_predict_contributions() {
+        let xt = array![[0.0], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![0.0, 1.0, 1.5, 0.9, 1.0];
+        let gp = Kriging::params()
+            .theta_tuning(ThetaTuning::Fixed(vec![1.0]))
+            .fit(&Dataset::new(xt, yt))
+            .expect("GP fit error");
+
+        let x = array![2.1];
+        let contribs = gp.predict_contributions(&x).expect("GP contributions");
+        assert_eq!(contribs.len(), 5);
+
+        let y = gp.predict(&x.clone().insert_axis(Axis(0))).unwrap()[0];
+        let trend = gp.inner_params.beta[[0, 0]] * gp.yt_norm.std[0] + gp.yt_norm.mean[0];
+        assert_abs_diff_eq!(contribs.sum(), y - trend, epsilon = 1e-12);
+
+        let nearest = contribs
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| f64::abs(**a).total_cmp(&f64::abs(**b)))
+            .map(|(i, _)| i)
+            .unwrap();
+        assert_eq!(nearest, 2);
+    }
+
+    #[test]
+    fn test_predict_single() {
+        let xlimits = array![[-5., 5.], [-5., 5.], [-5., 5.]];
+        let rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Lhs::new(&xlimits).with_rng(rng.clone()).sample(30);
+        let yt = griewank(&xt);
+        let gp = GaussianProcess::<f64, LinearMean, Matern52Corr>::params(
+            LinearMean::default(),
+            Matern52Corr::default(),
+        )
+        .kpls_dim(Some(2))
+        .fit(&Dataset::new(xt, yt))
+        .expect("GP fit error");
+
+        let x = Lhs::new(&xlimits).with_rng(rng).sample(20);
+        let expected = gp.predict(&x).unwrap();
+        for (xi, y) in x.rows().into_iter().zip(expected.iter()) {
+            assert_abs_diff_eq!(
+                gp.predict_single(&xi),
+                *y,
+                epsilon = 1e-12 * y.abs().max(1.)
+            );
+        }
+    }
+
+    #[test]
+    fn test_predict_into() {
+        let xt = array![[0.0], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![0.0, 1.0, 1.5, 0.9, 1.0];
+        let gp = Kriging::params()
+            .fit(&Dataset::new(xt, yt))
+            .expect("GP fit error");
+        let x = Array::linspace(0., 4., 20).insert_axis(Axis(1));
+        let mut out = Array1::zeros(20);
+        gp.predict_into(&x, &mut out).expect("GP prediction");
+        assert_abs_diff_eq!(out, gp.predict(&x).unwrap(), epsilon = 1e-12);
+        // writes into a view as well
+        let mut buffer = Array1::zeros(30);
+        gp.predict_into(&x, &mut buffer.slice_mut(s![5..25]))
+            .expect("GP prediction");
+        assert_abs_diff_eq!(buffer.slice(s![5..25]), out, epsilon = 1e-12);
+        assert!(gp.predict_into(&x, &mut Array1::zeros(3)).is_err());
+    }
+
+    #[test]
+    fn test_variance_near_duplicate_points() {
+        // near-duplicate training points make the correlation matrix ill-conditioned
+        let xt = array![[0.0], [1.0], [1.0 + 1e-9], [2.0], [3.0], [4.0]];
+        let yt = array![0.0, 1.0, 1.0, 1.5, 0.9, 1.0];
+        let gp = Kriging::params()
+            .theta_tuning(ThetaTuning::Fixed(vec![0.1]))
+            .fit(&Dataset::new(xt.clone(), yt))
+            .expect("GP fit error");
+
+        let x = ndarray::concatenate![
+            Axis(0),
+            xt,
+            Array::linspace(0., 4., 50).insert_axis(Axis(1))
+        ];
+        let var = gp.predict_var(&x).expect("GP variance prediction");
+        assert!(var.iter().all(|&v| v >= 0.));
+        assert!(var.iter().all(|&v| f64::sqrt(v).is_finite()));
+        let (_, var) = gp.predict_valvar(&x).expect("GP prediction");
+        assert!(var.iter().all(|&v| v >= 0.));
+    }
+
+    #[test]
+    fn test_merge_duplicates() {
+        let xt = array![[0.0], [1.0], [1.0 + 1e-12], [2.0], [3.0], [4.0]];
+        let yt = array![0.0, 1.0, 1.2, 1.5, 0.9, 1.0];
+        let ds = Dataset::new(xt, yt);
+
+        assert_eq!(near_duplicates(ds.records(), 1e-10), vec![(1, 2)]);
+        assert_eq!(near_duplicates(ds.records(), 1e-14), vec![]);
+
+        let gp = Kriging::params()
+            .merge_duplicates(1e-8)
+            .fit(&ds)
+            .expect("GP fit error");
+        assert_eq!(gp.training_data.0.nrows(), 5);
+        assert_abs_diff_eq!(gp.training_data.1[1], 1.1, epsilon = 1e-12);
+        assert_abs_diff_eq!(gp.predict(&array![[1.0]]).unwrap()[0], 1.1, epsilon = 1e-3);
+
+        // repeated inputs are accepted whatever the outputs
+        let xt = array![[0.0], [1.0], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![0.0, 1.0, 1.0, 1.5, 0.9, 1.0];
+        assert!(Kriging::params().fit(&Dataset::new(xt.clone(), yt)).is_ok());
+        let yt = array![0.0, 1.0, 1.2, 1.5, 0.9, 1.0];
+        assert!(Kriging::params().fit(&Dataset::new(xt, yt)).is_ok());
+    }
+
+    #[test]
+    fn test_kriging_f32() {
+        let xt = array![[0.0f32], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![0.0f32, 1.0, 1.5, 0.9, 1.0];
+        let gp = Kriging::params()
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        assert_abs_diff_eq!(gp.predict(&xt).unwrap(), yt, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_predict_mode() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((20, 1), Uniform::new(0., 5.), &mut rng);
+        let noise = Array1::random_using(20, Uniform::new(-0.1, 0.1), &mut rng);
+        let yt = xt.column(0).mapv(f64::sin) + noise;
+        let gp = Kriging::params()
+            .nugget(1e-1)
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+
+        let interpolated = gp.predict_with_mode(&xt, PredictMode::Interpolate).unwrap();
+        assert_abs_diff_eq!(interpolated, yt, epsilon = 1e-6);
+
+        let filtered = gp.predict_with_mode(&xt, PredictMode::Filter).unwrap();
+        assert_eq!(filtered, gp.predict(&xt).unwrap());
+        assert!(!filtered.abs_diff_eq(&yt, 1e-3));
+
+        // Both modes agree away from training points
+        let x = array![[0.123], [2.345], [4.567]];
+        assert_eq!(
+            gp.predict_with_mode(&x, PredictMode::Interpolate).unwrap(),
+            gp.predict(&x).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_minmax_scaler() {
+        let xt = Lhs::new(&array![[0., 10.], [-2., 3.]])
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(15);
+        let yt = xt.column(0).mapv(f64::sin) + xt.column(1).mapv(|v| v * v);
+        let theta = array![0.5, 2.];
+        let gp = Kriging::params()
+            .scaler(Scaler::MinMax)
+            .theta_tuning(ThetaTuning::Fixed(theta.to_vec()))
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        assert!(gp.xt_norm.data.iter().all(|v| (0. ..=1.).contains(v)));
+        assert_abs_diff_eq!(
+            gp.xt_norm
+                .data
+                .column(0)
+                .fold(f64::INFINITY, |m, v| m.min(*v)),
+            0.
+        );
+        assert_abs_diff_eq!(
+            gp.xt_norm.data.column(1).fold(0., |m: f64, v| m.max(*v)),
+            1.
+        );
+
+        // equivalent fit on unscaled inputs with theta expressed in the original input space
+        let range = &gp.xt_norm.std;
+        let gp_unscaled = Kriging::params()
+            .scaler(Scaler::None)
+            .theta_tuning(ThetaTuning::Fixed((&theta / (range * range)).to_vec()))
+            .fit(&Dataset::new(xt, yt))
+            .expect("GP fit error");
+
+        let xv = Lhs::new(&array![[0., 10.], [-2., 3.]])
+            .with_rng(Xoshiro256Plus::seed_from_u64(0))
+            .sample(20);
+        assert_abs_diff_eq!(
+            gp.predict(&xv).unwrap(),
+            gp_unscaled.predict(&xv).unwrap(),
+            epsilon = 1e-6
+        );
+        assert_abs_diff_eq!(
+            gp.predict_var(&xv).unwrap(),
+            gp_unscaled.predict_var(&xv).unwrap(),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_theta_init_from_data() {
+        // poorly scaled inputs with a known length scale of 100
+        let xt = Lhs::new(&array![[0., 1000.]])
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(12);
+        let yt = xt.column(0).mapv(|v: f64| (v / 100.).sin());
+        let gp = Kriging::params()
+            .theta_init_from_data(true)
+            .n_start(0)
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        let gp_multistart = Kriging::params()
+            .fit(&Dataset::new(xt, yt))
+            .expect("GP fit error");
+        println!(
+            "theta = {}, multistart theta = {}",
+            gp.theta(),
+            gp_multistart.theta()
+        );
+        // Optimization from data-driven start only reaches the optimum found with multistart
+        assert_abs_diff_eq!(
+            f64::log10(gp.theta()[0]),
+            f64::log10(gp_multistart.theta()[0]),
+            epsilon = 0.1
+        );
+    }
+
+    #[test]
+    fn test_relevance() {
+        let xt = Lhs::new(&array![[-1., 1.], [-1., 1.], [-1., 1.]])
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(30);
+        // y does not depend on the last input
+        let yt = xt.map_axis(Axis(1), |x| f64::sin(3. * x[0]) + x[1]);
+        let gp = Kriging::params()
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        let relevance = gp.relevance();
+        println!("relevance = {relevance}");
+        assert_abs_diff_eq!(relevance.sum(), 1., epsilon = 1e-12);
+        assert!(relevance[2] < 1e-2 * relevance[0].min(relevance[1]));
+
+        let gp = Kriging::params()
+            .kpls_dim(Some(2))
+            .fit(&Dataset::new(xt, yt))
+            .expect("GP fit error");
+        let relevance = gp.relevance();
+        println!("kpls relevance = {relevance}");
+        assert_abs_diff_eq!(relevance.sum(), 1., epsilon = 1e-12);
+        // PLS directions are estimated from data hence not exactly orthogonal to the last input
+        assert!(relevance[2] < 0.1 * relevance[0].min(relevance[1]));
+    }
+
+    // Periodic basis [1, sin(w.x), cos(w.x)] with w frequency in normalized space
+    #[derive(Clone, Copy, Debug, Default)]
+    struct SineMean(f64);
+
+    impl fmt::Display for SineMean {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "SineMean")
+        }
+    }
+
+    impl RegressionModel<f64> for SineMean {
+        fn value(&self, x: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array2<f64> {
+            let mut res = Array2::ones((x.nrows(), 3));
+            res.column_mut(1)
+                .assign(&x.column(0).mapv(|v| (self.0 * v).sin()));
+            res.column_mut(2)
+                .assign(&x.column(0).mapv(|v| (self.0 * v).cos()));
+            res
+        }
+
+        fn jacobian(&self, x: &ArrayBase<impl Data<Elem = f64>, Ix1>) -> Array2<f64> {
+            array![
+                [0.],
+                [self.0 * (self.0 * x[0]).cos()],
+                [-self.0 * (self.0 * x[0]).sin()]
+            ]
+        }
+    }
+
+    #[test]
+    fn test_custom_mean() {
+        let xt = Lhs::new(&array![[0., 6. * std::f64::consts::PI]])
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(8);
+        let yt = xt.column(0).mapv(f64::sin);
+        // sin(x) = sin(std * xnorm + mean) is a combination of sin(std * xnorm) and cos(std * xnorm)
+        let xstd = xt.std_axis(Axis(0), 1.)[0];
+
+        let sine_gp = GaussianProcess::<f64, SineMean, SquaredExponentialCorr>::params(
+            SineMean(xstd),
+            SquaredExponentialCorr::default(),
+        )
+        .fit(&Dataset::new(xt.clone(), yt.clone()))
+        .expect("GP fit error");
+        let quad_gp = GaussianProcess::<f64, QuadraticMean, SquaredExponentialCorr>::params(
+            QuadraticMean::default(),
+            SquaredExponentialCorr::default(),
+        )
+        .fit(&Dataset::new(xt, yt))
+        .expect("GP fit error");
+
+        let x = Array::linspace(0., 6. * std::f64::consts::PI, 100).insert_axis(Axis(1));
+        let y = x.column(0).mapv(f64::sin);
+        let sine_err = sine_gp.predict(&x).unwrap().l2_dist(&y).unwrap();
+        let quad_err = quad_gp.predict(&x).unwrap().l2_dist(&y).unwrap();
+        println!("Sine mean error = {sine_err}, quadratic mean error = {quad_err}");
+        assert!(sine_err < 1e-3);
+        assert!(sine_err < quad_err);
+
+        // derivatives are available as well
+        let xv = array![[1.], [5.]];
+        assert_abs_diff_eq!(
+            sine_gp.predict_gradients(&xv),
+            xv.mapv(f64::cos),
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn test_combined_corr() {
+        // smooth signal plus a small periodic component
+        let f = |x: &Array2<f64>| x.column(0).mapv(|v| (2. * v).sin() + 0.1 * (12. * v).sin());
+        let xt = Lhs::new(&array![[0., 4.]])
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(30);
+        let yt = f(&xt);
+        let x = Array::linspace(0., 4., 200).insert_axis(Axis(1));
+        let y = f(&x);
+
+        macro_rules! pred_error {
+            ($corr:ident) => {{
+                let gp = GpParams::new(ConstantMean::default(), $corr::default())
+                    .fit(&Dataset::new(xt.clone(), yt.clone()))
+                    .expect("GP fit error");
+                let err = gp.predict(&x).unwrap().l2_dist(&y).unwrap();
+                println!("{} error = {err}", stringify!($corr));
+                err
+            }};
+        }
+        let best_single = [
+            pred_error!(SquaredExponentialCorr),
+            pred_error!(AbsoluteExponentialCorr),
+            pred_error!(Matern32Corr),
+            pred_error!(Matern52Corr),
+        ]
+        .into_iter()
+        .fold(f64::INFINITY, f64::min);
+        let combined = pred_error!(SquaredExponentialPlusAbsoluteExponentialCorr);
+        assert!(combined < 0.5 * best_single);
+    }
+
+    #[test]
+    fn test_fit_output() {
+        let xt = array![[0.0], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![[0.0, 1.0], [1.0, 3.0], [1.5, 4.0], [0.9, 2.5], [1.0, 0.0]];
+        let gp = Kriging::params()
+            .fit(&Dataset::new(xt.clone(), yt.column(0).to_owned()))
+            .expect("GP fit error");
+        let gp2 = gp.fit_output(&yt.column(1)).expect("GP output fit");
+        assert_eq!(gp2.theta(), gp.theta());
+
+        // Same as a fit with the same theta on the second output
+        let expected = Kriging::params()
+            .theta_tuning(ThetaTuning::Fixed(gp.theta().to_vec()))
+            .fit(&Dataset::new(xt.clone(), yt.column(1).to_owned()))
+            .expect("GP fit error");
+        let x = Array::linspace(0., 4., 20).insert_axis(Axis(1));
+        assert_abs_diff_eq!(
+            gp2.predict(&x).unwrap(),
+            expected.predict(&x).unwrap(),
+            epsilon = 1e-8
+        );
+        assert_abs_diff_eq!(
+            gp2.predict_var(&x).unwrap(),
+            expected.predict_var(&x).unwrap(),
+            epsilon = 1e-8
+        );
+        assert_abs_diff_eq!(gp2.predict(&xt).unwrap(), yt.column(1), epsilon = 1e-6);
+
+        assert!(gp.fit_output(&array![1., 2.]).is_err());
+    }
+
+    #[test]
+    fn test_fit_outputs() {
+        let xt = array![[0.0], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![[0.0, 1.0], [1.0, 3.0], [1.5, 4.0], [0.9, 2.5], [1.0, 0.0]];
+        let params = linfa::ParamGuard::check(Kriging::params()).unwrap();
+
+        // a null weight output does not contribute to theta
+        let gps = params
+            .fit_outputs(&xt, &yt, &array![1., 0.])
+            .expect("GP fit error");
+        let gp = params
+            .fit(&Dataset::new(xt.clone(), yt.column(0).to_owned()))
+            .expect("GP fit error");
+        assert_eq!(gps.len(), 2);
+        assert_eq!(gps[0].theta(), gp.theta());
+        assert_eq!(gps[1].theta(), gp.theta());
+
+        // theta maximizes the weighted sum of the outputs likelihoods
+        let gps = params
+            .fit_outputs(&xt, &yt, &array![1., 1.])
+            .expect("GP fit error");
+        let gp1 = gp.fit_output(&yt.column(1)).expect("GP output fit");
+        assert_eq!(gps[1].theta(), gps[0].theta());
+        assert!(
+            gps[0].likelihood() + gps[1].likelihood() >= gp.likelihood() + gp1.likelihood() - 1e-6
+        );
+
+        assert!(params.fit_outputs(&xt, &yt, &array![1.]).is_err());
+        assert!(params.fit_outputs(&xt, &yt, &array![0., 0.]).is_err());
+        assert!(params.fit_outputs(&xt, &yt, &array![1., f64::NAN]).is_err());
+    }
+
+    fn categorical_function(x: &Array2<f64>) -> Array1<f64> {
+        x.outer_iter()
+            .map(|row| {
+                let level = row.slice(s![1..]).iter().position(|v| *v == 1.).unwrap();
+                match level {
+                    0 => (6. * row[0]).sin(),
+                    1 => (6. * row[0]).sin() + 0.5,
+                    _ => row[0] * row[0],
+                }
+            })
+            .collect()
+    }
+
+    fn categorical_doe(xs: &Array1<f64>) -> Array2<f64> {
+        let n = xs.len();
+        let mut x = Array2::zeros((3 * n, 4));
+        for level in 0..3 {
+            for (i, v) in xs.iter().enumerate() {
+                x[[level * n + i, 0]] = *v;
+                x[[level * n + i, 1 + level]] = 1.;
+            }
+        }
+        x
+    }
+
+    #[test]
+    fn test_categorical_inputs() {
+        let xt = categorical_doe(&Array::linspace(0., 1., 8));
+        let yt = categorical_function(&xt);
+        let gp = Kriging::params()
+            .categorical_inputs(vec![(1, 3)])
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+
+        // One theta per input plus k-1 latent coordinates
+        assert_eq!(gp.theta().len(), 4 + 2);
+        assert_eq!(gp.theta().slice(s![1..4]), array![1., 1., 1.]);
+        assert_eq!(gp.corr_theta().len(), 4);
+
+        let xtest = categorical_doe(&Array::linspace(0.05, 0.95, 7));
+        let ytest = categorical_function(&xtest);
+        let pred = gp.predict(&xtest).expect("GP prediction");
+        assert_abs_diff_eq!(pred, ytest, epsilon = 5e-2);
+        assert_abs_diff_eq!(gp.predict(&xt).unwrap(), yt, epsilon = 1e-4);
+
+        // Variances and derivatives go through the latent transform as well
+        let var = gp.predict_var(&xtest).expect("GP variance");
+        assert!(var.iter().all(|v| v.is_finite() && *v >= 0.));
+        let x = xtest.row(3);
+        let jac = gp.predict_jacobian(&x);
+        let h = 1e-6;
+        let mut xp = x.to_owned();
+        xp[0] += h;
+        let fd = (gp.predict(&xp.insert_axis(Axis(0))).unwrap()[0]
+            - gp.predict(&x.insert_axis(Axis(0))).unwrap()[0])
+            / h;
+        assert_abs_diff_eq!(jac[[0, 0]], fd, epsilon = 1e-3);
+
+        // Fixed theta with latent coordinates gives back the same model
+        let fixed = Kriging::params()
+            .categorical_inputs(vec![(1, 3)])
+            .theta_tuning(ThetaTuning::Fixed(gp.theta().to_vec()))
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        assert_abs_diff_eq!(fixed.predict(&xtest).unwrap(), pred, epsilon = 1e-8);
+
+        assert!(Kriging::params()
+            .categorical_inputs(vec![(1, 3)])
+            .theta_tuning(ThetaTuning::Fixed(vec![0.1; 4]))
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .is_err());
+        assert!(Kriging::params()
+            .categorical_inputs(vec![(1, 1)])
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .is_err());
+        assert!(Kriging::params()
+            .categorical_inputs(vec![(1, 3)])
+            .kpls_dim(Some(1))
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .is_err());
+        // Categorical columns have to be one-hot encoded
+        let mut xbad = xt.clone();
+        xbad[[0, 1]] = 0.5;
+        assert!(Kriging::params()
+            .categorical_inputs(vec![(1, 3)])
+            .fit(&Dataset::new(xbad, yt.clone()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_add_observation() {
+        let xt = array![[0.0], [1.0], [2.0], [3.0], [4.0]];
+        let yt = array![0.0, 1.0, 1.5, 0.9, 1.0];
+        let mut gp = Kriging::params()
+            .theta_tuning(ThetaTuning::Fixed(vec![0.5]))
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+
+        let xnew = array![[0.5], [2.5], [3.7]];
+        let ynew = array![0.6, 1.2, 1.1];
+        for (x, y) in xnew.outer_iter().zip(ynew.iter()) {
+            gp.add_observation(&x, *y).expect("GP update");
+        }
+        assert_eq!(gp.training_data.0.nrows(), 8);
+
+        // Full computation with the same data, theta and normalization
+        let x = ndarray::concatenate![Axis(0), xt, xnew];
+        let y = ndarray::concatenate![Axis(0), yt, ynew].insert_axis(Axis(1));
+        let xt_norm = NormalizedData {
+            data: (&x - &gp.xt_norm.mean) / &gp.xt_norm.std,
+            mean: gp.xt_norm.mean.clone(),
+            std: gp.xt_norm.std.clone(),
+        };
+        let yt_norm = NormalizedData {
+            data: (&y - &gp.yt_norm.mean) / &gp.yt_norm.std,
+            mean: gp.yt_norm.mean.clone(),
+            std: gp.yt_norm.std.clone(),
+        };
+        let x_distances = DistanceMatrix::new(&xt_norm.data);
+        let rxx = gp.params.corr.value(&x_distances.d, &gp.theta, &gp.w_star);
+        let fx = gp.params.mean.value(&xt_norm.data);
+        let (likelihood, inner_params) =
+            reduced_likelihood(&fx, rxx, &x_distances, &yt_norm, gp.params.nugget, None)
+                .expect("Likelihood computation");
+        let expected = GaussianProcess {
+            theta: gp.theta.clone(),
+            likelihood,
+            likelihood_evals: gp.likelihood_evals,
+            inner_params,
+            w_star: gp.w_star.clone(),
+            xt_norm,
+            yt_norm,
+            training_data: (x, y.remove_axis(Axis(1))),
+            params: gp.params.clone(),
+        };
+
+        assert_abs_diff_eq!(gp.likelihood(), expected.likelihood(), epsilon = 1e-8);
+        let xv = Array::linspace(-1., 5., 20).insert_axis(Axis(1));
+        assert_abs_diff_eq!(
+            gp.predict(&xv).unwrap(),
+            expected.predict(&xv).unwrap(),
+            epsilon = 1e-8
+        );
+        assert_abs_diff_eq!(
+            gp.predict_var(&xv).unwrap(),
+            expected.predict_var(&xv).unwrap(),
+            epsilon = 1e-8
+        );
+        // Updated model interpolates new observations
+        assert_abs_diff_eq!(gp.predict(&xnew).unwrap(), ynew, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_strict_kpls() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((20, 5), Uniform::new(-1., 1.), &mut rng);
+        let yt = xt.map_axis(Axis(1), |x| x.mapv(|v| v * v).sum());
+
+        let res = Kriging::params()
+            .kpls_dim(Some(20))
+            .strict_kpls(true)
+            .fit(&Dataset::new(xt.clone(), yt.clone()));
+        assert!(res.is_err());
+
+        // clamped to the input dimension, hence no reduction
+        let gp = Kriging::params()
+            .kpls_dim(Some(20))
+            .fit(&Dataset::new(xt, yt))
+            .expect("GP fit error");
+        assert_eq!(gp.kpls_dim(), None);
+        assert_eq!(gp.theta().len(), 5);
+    }
+
+    #[test]
+    fn test_likelihood_profile() {
+        let xt = array![[0.0], [1.0], [2.0], [3.0], [4.0], [5.5], [7.0]];
+        let yt = xt.column(0).mapv(f64::sin);
+        let gp = Kriging::params()
+            .fit(&Dataset::new(xt, yt))
+            .expect("GP fit error");
+
+        let log_thetas = Array1::linspace(-3., 2., 201);
+        let theta_grid = log_thetas.mapv(|v| 10f64.powf(v)).insert_axis(Axis(1));
+        let profile = gp
+            .likelihood_profile(&theta_grid)
+            .expect("Likelihood profile");
+        assert_eq!(profile.len(), 201);
+        let best = theta_grid[[profile.argmax().unwrap(), 0]];
+        assert_abs_diff_eq!(best.log10(), gp.theta()[0].log10(), epsilon = 0.05);
+
+        let at_theta = gp
+            .likelihood_profile(&gp.theta().view().insert_axis(Axis(0)))
+            .expect("Likelihood profile");
+        assert_abs_diff_eq!(at_theta[0], gp.likelihood(), epsilon = 1e-10);
+
+        assert!(gp.likelihood_profile(&Array2::ones((3, 2))).is_err());
+    }
+
+    #[test]
+    fn test_fit_jitter() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((20, 1), Uniform::new(0., 5.), &mut rng);
+        let noise = Array1::random_using(20, Uniform::new(-0.1, 0.1), &mut rng);
+        let yt = xt.column(0).mapv(f64::sin) + noise;
+        let fit = |params: GpParams<f64, ConstantMean, SquaredExponentialCorr>| {
+            params
+                .theta_tuning(ThetaTuning::Fixed(vec![1.]))
+                .fit(&Dataset::new(xt.clone(), yt.clone()))
+                .expect("GP fit error")
+        };
+
+        let jittered = fit(Kriging::params().fit_jitter(1e-1));
+        assert_abs_diff_eq!(jittered.predict(&xt).unwrap(), yt, epsilon = 1e-6);
+        let var = jittered.predict_var(&xt).unwrap();
+        assert!(var.iter().all(|v| *v < 1e-6), "{var}");
+        for (x, y) in xt.rows().into_iter().zip(yt.iter()) {
+            assert_abs_diff_eq!(jittered.predict_single(&x), *y, epsilon = 1e-6);
+        }
+
+        let smoothed = fit(Kriging::params().nugget(1e-1));
+        assert!(!smoothed.predict(&xt).unwrap().abs_diff_eq(&yt, 1e-3));
+
+        // Same factorization hence same predictions away from training points
+        let x = array![[0.123], [2.345], [4.567]];
+        assert_abs_diff_eq!(
+            jittered.predict(&x).unwrap(),
+            smoothed.predict(&x).unwrap(),
+            epsilon = 1e-10
+        );
+
+        assert!(Kriging::params()
+            .fit_jitter(-1.)
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_observation_noise() {
+        let xt = Array::linspace(0., 6., 13).insert_axis(Axis(1));
+        let mut yt = xt.mapv(f64::sin).remove_axis(Axis(1));
+        // Corrupt one observation declared as noisy
+        yt[6] += 0.5;
+        let mut noise = Array1::zeros(13);
+        noise[6] = 1.;
+        let gp = Kriging::params()
+            .observation_noise(noise)
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        let ypred = gp.predict(&xt).unwrap();
+        assert!((ypred[6] - yt[6]).abs() > 0.1);
+        for i in [0, 1, 2, 10, 11, 12] {
+            assert_abs_diff_eq!(ypred[i], yt[i], epsilon = 1e-3);
+        }
+        let noise_var = gp.predict_noise_var(&array![[3.], [3.2]]);
+        let nugget_var = gp.nugget() * gp.variance();
+        assert_abs_diff_eq!(noise_var[[0, 0]], nugget_var + gp.variance());
+        assert_abs_diff_eq!(noise_var[[1, 0]], nugget_var);
+
+        let res = Kriging::params()
+            .observation_noise(Array1::zeros(5))
+            .fit(&Dataset::new(xt, yt));
+        assert!(matches!(res, Err(GpError::InvalidValueError(_))));
+    }
+
+    #[test]
+    fn test_sample_weights() {
+        let xt = Array::linspace(0., 6., 13).insert_axis(Axis(1));
+        let mut yt = xt.mapv(f64::sin).remove_axis(Axis(1));
+        yt[6] += 0.5;
+        let noise = Array1::from_elem(13, 0.1);
+        let gp = Kriging::params()
+            .observation_noise(noise.clone())
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        let ypred = gp.predict(&xt).unwrap();
+
+        // Upweighted observation is trusted more
+        let mut weights = Array1::ones(13);
+        weights[6] = 100.;
+        let gp_weighted = Kriging::params()
+            .observation_noise(noise)
+            .sample_weights(weights)
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        let ypred_weighted = gp_weighted.predict(&xt).unwrap();
+        assert!((ypred_weighted[6] - yt[6]).abs() < 0.5 * (ypred[6] - yt[6]).abs());
+
+        let res = Kriging::params()
+            .sample_weights(Array1::zeros(13))
+            .fit(&Dataset::new(xt.clone(), yt.clone()));
+        assert!(matches!(res, Err(GpError::InvalidValueError(_))));
+        let res = Kriging::params()
+            .sample_weights(Array1::from_elem(13, f64::NAN))
+            .fit(&Dataset::new(xt.clone(), yt.clone()));
+        assert!(matches!(res, Err(GpError::InvalidValueError(_))));
+        let res = Kriging::params()
+            .sample_weights(Array1::ones(5))
+            .fit(&Dataset::new(xt, yt));
+        assert!(matches!(res, Err(GpError::InvalidValueError(_))));
+    }
+
+    #[test]
+    fn test_large_scale_inputs() {
+        let xt = array![[0.0], [5.0], [10.0], [15.0], [18.0], [20.0], [25.0]];
+        let yt = xt.mapv(|v: f64| (v - 3.5) * ((v - 3.5) / std::f64::consts::PI).sin());
+        let yt = yt.remove_axis(Axis(1));
+        let gp = Kriging::params()
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("GP fit error");
+        let scale = 1e6;
+        let gp_scaled = Kriging::params()
+            .fit(&Dataset::new(&xt * scale, yt))
+            .expect("GP fit error");
+
+        let xv = Array::linspace(0., 25., 26).insert_axis(Axis(1));
+        assert_abs_diff_eq!(
+            gp.predict(&xv).unwrap(),
+            gp_scaled.predict(&(&xv * scale)).unwrap(),
+            epsilon = 1e-6
+        );
+    }
+
     fn x2sinx(x: &Array2<f64>) -> Array1<f64> {
         ((x * x) * (x).mapv(|v| v.sin())).remove_axis(Axis(1))
     }
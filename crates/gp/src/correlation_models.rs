@@ -5,6 +5,9 @@
 //! * absolute exponential,
 //! * matern 3/2,
 //! * matern 5/2.
+//!
+//! Those can be combined as a sum ([SumCorr]) or a product ([ProductCorr]) of two correlation models,
+//! each one having its own theta hyperparameters.
 
 use crate::utils::differences;
 use linfa::Float;
@@ -39,6 +42,29 @@ pub trait CorrelationModel<F: Float>: Clone + Copy + Default + fmt::Display + Sy
         theta: &ArrayBase<impl Data<Elem = F>, Ix1>,
         weights: &ArrayBase<impl Data<Elem = F>, Ix2>,
     ) -> Array2<F>;
+
+    /// Number of theta hyperparameters given the input dimension `dim`
+    /// (the reduced dimension h when PLS is used), one per dimension by default.
+    fn n_theta(&self, dim: usize) -> usize {
+        dim
+    }
+}
+
+/// Split `theta` hyperparameters of a combination of correlation models `a` and `b`
+/// in dimension `dim`: first `a.n_theta(dim)` values go to `a`, the remaining ones to `b`.
+/// Otherwise (e.g. a single theta value) theta is shared by both models.
+fn split_theta<F: Float>(
+    a: &impl CorrelationModel<F>,
+    b: &impl CorrelationModel<F>,
+    theta: &ArrayBase<impl Data<Elem = F>, Ix1>,
+    dim: usize,
+) -> (Array1<F>, Array1<F>) {
+    if theta.len() == a.n_theta(dim) + b.n_theta(dim) {
+        let (ta, tb) = theta.view().split_at(Axis(0), a.n_theta(dim));
+        (ta.to_owned(), tb.to_owned())
+    } else {
+        (theta.to_owned(), theta.to_owned())
+    }
 }
 
 /// Squared exponential correlation models
@@ -458,6 +484,171 @@ impl fmt::Display for Matern52Corr {
     }
 }
 
+/// Sum of two correlation models `(r_a + r_b) / 2`, the half factor keeping the correlation
+/// equal to 1 at zero distance. Each model has its own theta hyperparameters: theta of the sum
+/// is the concatenation of theta of `a` and theta of `b`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serializable",
+    derive(Serialize, Deserialize),
+    serde(into = "String"),
+    serde(try_from = "String"),
+    serde(bound(
+        serialize = "A: Clone + Into<String>, B: Clone + Into<String>",
+        deserialize = "A: TryFrom<String, Error = &'static str>, B: TryFrom<String, Error = &'static str>"
+    ))
+)]
+pub struct SumCorr<A, B>(pub A, pub B);
+
+impl<A: Into<String>, B: Into<String>> From<SumCorr<A, B>> for String {
+    fn from(item: SumCorr<A, B>) -> String {
+        format!("{}Plus{}", item.0.into(), item.1.into())
+    }
+}
+
+impl<A, B> TryFrom<String> for SumCorr<A, B>
+where
+    A: TryFrom<String, Error = &'static str>,
+    B: TryFrom<String, Error = &'static str>,
+{
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let (a, b) = s.split_once("Plus").ok_or_else(|| {
+            format!("Bad string value for SumCorr, should be \'<Corr>Plus<Corr>\', got {s}")
+        })?;
+        Ok(Self(
+            A::try_from(a.to_string())?,
+            B::try_from(b.to_string())?,
+        ))
+    }
+}
+
+impl<F: Float, A: CorrelationModel<F>, B: CorrelationModel<F>> CorrelationModel<F>
+    for SumCorr<A, B>
+{
+    /// (r_a(d, theta_a) + r_b(d, theta_b)) / 2
+    fn value(
+        &self,
+        d: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        theta: &ArrayBase<impl Data<Elem = F>, Ix1>,
+        weights: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    ) -> Array2<F> {
+        let (ta, tb) = split_theta(&self.0, &self.1, theta, weights.ncols());
+        (self.0.value(d, &ta, weights) + self.1.value(d, &tb, weights)) * F::cast(0.5)
+    }
+
+    fn jacobian(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix1>,
+        xtrain: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        theta: &ArrayBase<impl Data<Elem = F>, Ix1>,
+        weights: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    ) -> Array2<F> {
+        let (ta, tb) = split_theta(&self.0, &self.1, theta, weights.ncols());
+        (self.0.jacobian(x, xtrain, &ta, weights) + self.1.jacobian(x, xtrain, &tb, weights))
+            * F::cast(0.5)
+    }
+
+    fn n_theta(&self, dim: usize) -> usize {
+        self.0.n_theta(dim) + self.1.n_theta(dim)
+    }
+}
+
+impl<A: fmt::Display, B: fmt::Display> fmt::Display for SumCorr<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}Plus{}", self.0, self.1)
+    }
+}
+
+/// Product of two correlation models `r_a * r_b`. Each model has its own theta hyperparameters:
+/// theta of the product is the concatenation of theta of `a` and theta of `b`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serializable",
+    derive(Serialize, Deserialize),
+    serde(into = "String"),
+    serde(try_from = "String"),
+    serde(bound(
+        serialize = "A: Clone + Into<String>, B: Clone + Into<String>",
+        deserialize = "A: TryFrom<String, Error = &'static str>, B: TryFrom<String, Error = &'static str>"
+    ))
+)]
+pub struct ProductCorr<A, B>(pub A, pub B);
+
+impl<A: Into<String>, B: Into<String>> From<ProductCorr<A, B>> for String {
+    fn from(item: ProductCorr<A, B>) -> String {
+        format!("{}Times{}", item.0.into(), item.1.into())
+    }
+}
+
+impl<A, B> TryFrom<String> for ProductCorr<A, B>
+where
+    A: TryFrom<String, Error = &'static str>,
+    B: TryFrom<String, Error = &'static str>,
+{
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let (a, b) = s.split_once("Times").ok_or_else(|| {
+            format!("Bad string value for ProductCorr, should be \'<Corr>Times<Corr>\', got {s}")
+        })?;
+        Ok(Self(
+            A::try_from(a.to_string())?,
+            B::try_from(b.to_string())?,
+        ))
+    }
+}
+
+impl<F: Float, A: CorrelationModel<F>, B: CorrelationModel<F>> CorrelationModel<F>
+    for ProductCorr<A, B>
+{
+    /// r_a(d, theta_a) * r_b(d, theta_b)
+    fn value(
+        &self,
+        d: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        theta: &ArrayBase<impl Data<Elem = F>, Ix1>,
+        weights: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    ) -> Array2<F> {
+        let (ta, tb) = split_theta(&self.0, &self.1, theta, weights.ncols());
+        self.0.value(d, &ta, weights) * self.1.value(d, &tb, weights)
+    }
+
+    fn jacobian(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix1>,
+        xtrain: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        theta: &ArrayBase<impl Data<Elem = F>, Ix1>,
+        weights: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    ) -> Array2<F> {
+        let (ta, tb) = split_theta(&self.0, &self.1, theta, weights.ncols());
+        let d = differences(x, xtrain);
+        let ra = self.0.value(&d, &ta, weights);
+        let rb = self.1.value(&d, &tb, weights);
+        self.0.jacobian(x, xtrain, &ta, weights) * &rb
+            + self.1.jacobian(x, xtrain, &tb, weights) * &ra
+    }
+
+    fn n_theta(&self, dim: usize) -> usize {
+        self.0.n_theta(dim) + self.1.n_theta(dim)
+    }
+}
+
+impl<A: fmt::Display, B: fmt::Display> fmt::Display for ProductCorr<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}Times{}", self.0, self.1)
+    }
+}
+
+/// Sum of matern 5/2 and squared exponential correlation models
+pub type Matern52PlusSquaredExponentialCorr = SumCorr<Matern52Corr, SquaredExponentialCorr>;
+
+/// Sum of squared exponential and absolute exponential correlation models
+pub type SquaredExponentialPlusAbsoluteExponentialCorr =
+    SumCorr<SquaredExponentialCorr, AbsoluteExponentialCorr>;
+
+/// Product of squared exponential and absolute exponential correlation models
+pub type SquaredExponentialTimesAbsoluteExponentialCorr =
+    ProductCorr<SquaredExponentialCorr, AbsoluteExponentialCorr>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -585,6 +776,36 @@ mod tests {
     test_correlation!(AbsoluteExponential, true);
     test_correlation!(Matern32, true);
     test_correlation!(Matern52, true);
+    test_correlation!(Matern52PlusSquaredExponential, false);
+    test_correlation!(SquaredExponentialTimesAbsoluteExponential, false);
+    test_correlation!(Matern52PlusSquaredExponential, true);
+    test_correlation!(SquaredExponentialTimesAbsoluteExponential, true);
+
+    #[test]
+    fn test_combined_corr() {
+        let xt = array![[0., 1.], [2., 3.], [4., 5.]];
+        let dm = DistanceMatrix::new(&xt);
+        let weights = array![[1., 0.], [0., 1.]];
+        let se = SquaredExponentialCorr::default().value(&dm.d, &arr1(&[1., 2.]), &weights);
+        let ae = AbsoluteExponentialCorr::default().value(&dm.d, &arr1(&[0.1, 0.2]), &weights);
+
+        let theta = arr1(&[1., 2., 0.1, 0.2]);
+        let sum = SquaredExponentialPlusAbsoluteExponentialCorr::default();
+        assert_eq!(CorrelationModel::<f64>::n_theta(&sum, 2), 4);
+        assert_abs_diff_eq!(sum.value(&dm.d, &theta, &weights), (&se + &ae) / 2.);
+        let prod = SquaredExponentialTimesAbsoluteExponentialCorr::default();
+        assert_abs_diff_eq!(prod.value(&dm.d, &theta, &weights), &se * &ae);
+
+        assert_eq!(
+            prod.to_string(),
+            "SquaredExponentialTimesAbsoluteExponential"
+        );
+        assert_eq!(
+            SquaredExponentialPlusAbsoluteExponentialCorr::try_from(sum.to_string()),
+            Ok(sum)
+        );
+        assert!(Matern52PlusSquaredExponentialCorr::try_from(sum.to_string()).is_err());
+    }
 
     #[test]
     fn test_matern52_2d() {
@@ -28,3 +28,4 @@ pub use errors::*;
 pub use parameters::*;
 pub use sparse_algorithm::*;
 pub use sparse_parameters::*;
+pub use utils::{check_finite, numeric_gradient, outside_bounding_box, NUMERIC_GRADIENT_STEP};
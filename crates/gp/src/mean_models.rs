@@ -6,6 +6,9 @@
 //! * constant,
 //! * linear,
 //! * quadratic
+//!
+//! Other basis functions can be used as mean of the GP by implementing the [RegressionModel] trait
+//! for a user-defined type which can then be used as `Mean` type parameter of [crate::GaussianProcess].
 
 use linfa::Float;
 use ndarray::{concatenate, s, Array2, ArrayBase, Axis, Data, Ix1, Ix2};
@@ -16,13 +19,18 @@ use std::convert::TryFrom;
 use std::fmt;
 
 /// A trait for mean models used in GP regression
+///
+/// Note: training inputs are normalized (zero mean, unit standard deviation) before being used
+/// to fit the GP, hence basis functions are evaluated in the normalized input space.
 pub trait RegressionModel<F: Float>: Clone + Copy + Default + fmt::Display + Sync {
     /// Compute regression coefficients defining the mean behaviour of the GP model
     /// for the given `x` data points specified as (n, nx) matrix.
+    /// Returns a (n, p) matrix where p is the number of basis functions.
     fn value(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array2<F>;
 
     /// Compute regression derivative coefficients
     /// at the given `x` data point specified as (nx,) vector.
+    /// Returns a (p, nx) matrix where p is the number of basis functions.
     fn jacobian(&self, x: &ArrayBase<impl Data<Elem = F>, Ix1>) -> Array2<F>;
 }
 
@@ -87,9 +87,7 @@ where
     let base: f64 = 10.;
     // block to drop optimizer and allow self.corr borrowing after
     let mut optimizer = Nlopt::new(Algorithm::Cobyla, param0.len(), objfn, Target::Minimize, ());
-    let mut param = param0
-        .map(|v| unsafe { *(v as *const F as *const f64) })
-        .into_raw_vec();
+    let mut param = param0.map(|v| v.to_f64().unwrap()).into_raw_vec();
 
     let lower_bounds = bounds.iter().map(|b| into_f64(&b.0)).collect::<Vec<_>>();
     optimizer.set_lower_bounds(&lower_bounds).unwrap();
@@ -171,5 +169,5 @@ where
 
 #[inline(always)]
 fn into_f64<F: Float>(v: &F) -> f64 {
-    unsafe { *(v as *const F as *const f64) }
+    v.to_f64().unwrap()
 }
@@ -2,6 +2,7 @@ use crate::correlation_models::{CorrelationModel, SquaredExponentialCorr};
 use crate::errors::{GpError, Result};
 use crate::mean_models::{ConstantMean, RegressionModel};
 use linfa::{Float, ParamGuard};
+use ndarray::{Array1, Zip};
 
 #[cfg(feature = "serializable")]
 use serde::{Deserialize, Serialize};
@@ -47,6 +48,19 @@ impl<F: Float> ThetaTuning<F> {
     }
 }
 
+/// An enum to select the scaling of training inputs before fitting
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+pub enum Scaler {
+    /// Inputs are used as is
+    None,
+    /// Inputs are centered and reduced by their standard deviation (z-score)
+    #[default]
+    Standard,
+    /// Inputs are mapped to [0, 1] using their min and max values
+    MinMax,
+}
+
 /// A set of validated GP parameters.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
@@ -70,6 +84,28 @@ pub struct GpValidParams<F: Float, Mean: RegressionModel<F>, Corr: CorrelationMo
     pub(crate) n_start: usize,
     /// Parameter to improve numerical stability
     pub(crate) nugget: F,
+    /// Optional noise variances of training observations relative to the process variance
+    pub(crate) observation_noise: Option<Array1<F>>,
+    /// Optional importance weights of training observations
+    pub(crate) sample_weights: Option<Array1<F>>,
+    /// Optional distance under which training points are merged
+    pub(crate) merge_duplicates: Option<F>,
+    /// Whether theta optimization starts from a value estimated from training inputs
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub(crate) theta_init_from_data: bool,
+    /// Scaling of training inputs
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub(crate) scaler: Scaler,
+    /// Jitter added to the diagonal of the training correlation matrix at fit time
+    /// and kept in the correlation with training points at prediction time
+    #[cfg_attr(feature = "serializable", serde(default = "F::zero"))]
+    pub(crate) fit_jitter: F,
+    /// Whether an infeasible PLS dimension reduction is an error instead of being adjusted
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub(crate) strict_kpls: bool,
+    /// One-hot encoded categorical inputs as (index of first column, number of levels)
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub(crate) categorical_inputs: Vec<(usize, usize)>,
 }
 
 impl<F: Float> Default for GpValidParams<F, ConstantMean, SquaredExponentialCorr> {
@@ -81,6 +117,14 @@ impl<F: Float> Default for GpValidParams<F, ConstantMean, SquaredExponentialCorr
             kpls_dim: None,
             n_start: 10,
             nugget: F::cast(100.0) * F::epsilon(),
+            observation_noise: None,
+            sample_weights: None,
+            merge_duplicates: None,
+            theta_init_from_data: false,
+            scaler: Scaler::default(),
+            fit_jitter: F::zero(),
+            strict_kpls: false,
+            categorical_inputs: vec![],
         }
     }
 }
@@ -115,6 +159,70 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GpValidParam
     pub fn nugget(&self) -> F {
         self.nugget
     }
+
+    /// Get noise variances of training observations if any
+    pub fn observation_noise(&self) -> Option<&Array1<F>> {
+        self.observation_noise.as_ref()
+    }
+
+    /// Get importance weights of training observations if any
+    pub fn sample_weights(&self) -> Option<&Array1<F>> {
+        self.sample_weights.as_ref()
+    }
+
+    /// Get distance under which training points are merged if any
+    pub fn merge_duplicates(&self) -> Option<F> {
+        self.merge_duplicates
+    }
+
+    /// Whether theta optimization starts from a value estimated from training inputs
+    pub fn theta_init_from_data(&self) -> bool {
+        self.theta_init_from_data
+    }
+
+    /// Get the scaling of training inputs
+    pub fn scaler(&self) -> Scaler {
+        self.scaler
+    }
+
+    /// Get the jitter added to the correlation matrix diagonal for factorization only
+    pub fn fit_jitter(&self) -> F {
+        self.fit_jitter
+    }
+
+    /// Whether an infeasible PLS dimension reduction is an error instead of being adjusted
+    pub fn strict_kpls(&self) -> bool {
+        self.strict_kpls
+    }
+
+    /// Get one-hot encoded categorical inputs as (index of first column, number of levels)
+    pub fn categorical_inputs(&self) -> &[(usize, usize)] {
+        &self.categorical_inputs
+    }
+
+    /// Number of latent coordinates of categorical inputs levels appended to theta
+    pub fn n_latent(&self) -> usize {
+        self.categorical_inputs.iter().map(|(_, k)| k - 1).sum()
+    }
+
+    /// Variances of training observations added to the nugget on the diagonal
+    /// of the training correlation matrix: observation noise if any, the whole
+    /// being divided by sample weights if any.
+    pub(crate) fn noise_diag(&self) -> Option<Array1<F>> {
+        match (&self.observation_noise, &self.sample_weights) {
+            (noise, Some(weights)) => {
+                let noise = noise
+                    .clone()
+                    .unwrap_or_else(|| Array1::zeros(weights.len()));
+                Some(
+                    Zip::from(&noise)
+                        .and(weights)
+                        .map_collect(|n, w| (self.nugget + *n) / *w - self.nugget),
+                )
+            }
+            (noise, None) => noise.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -134,6 +242,14 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GpParams<F,
             kpls_dim: None,
             n_start: 10,
             nugget: F::cast(100.0) * F::epsilon(),
+            observation_noise: None,
+            sample_weights: None,
+            merge_duplicates: None,
+            theta_init_from_data: false,
+            scaler: Scaler::default(),
+            fit_jitter: F::zero(),
+            strict_kpls: false,
+            categorical_inputs: vec![],
         })
     }
 
@@ -175,6 +291,17 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GpParams<F,
         self
     }
 
+    /// Set whether theta optimization starts from a value estimated from training inputs
+    /// instead of the `theta_init` value.
+    ///
+    /// Along each (PLS reduced) input dimension, theta is started from the inverse of the median of squared
+    /// distances between (normalized) training inputs, clipped to theta bounds.
+    /// This function is no-op when theta tuning is fixed.
+    pub fn theta_init_from_data(mut self, enabled: bool) -> Self {
+        self.0.theta_init_from_data = enabled;
+        self
+    }
+
     /// Set theta hyper parameter search space.
     ///
     /// This function is no-op when theta tuning is fixed
@@ -208,6 +335,85 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> GpParams<F,
         self.0.nugget = nugget;
         self
     }
+
+    /// Set noise variances of training observations.
+    ///
+    /// Noise variances are added to the nugget on the diagonal of the correlation matrix
+    /// of training points. They are expressed relatively to the process variance:
+    /// an absolute noise variance has to be divided by the output variance.
+    /// Values should be non-negative and length should match the number of training points.
+    pub fn observation_noise(mut self, noise: Array1<F>) -> Self {
+        self.0.observation_noise = Some(noise);
+        self
+    }
+
+    /// Set importance weights of training observations.
+    ///
+    /// The nugget and noise variance of each training point are divided by its weight,
+    /// hence a point with a larger weight is trusted more and the GP mean is pulled toward it.
+    /// Weights only act through these noise terms: with the default tiny nugget and without
+    /// [GpParams::observation_noise], the GP interpolates training points and weights have no effect.
+    /// Weights should be finite, strictly positive and length should match the number of training points.
+    pub fn sample_weights(mut self, weights: Array1<F>) -> Self {
+        self.0.sample_weights = Some(weights);
+        self
+    }
+
+    /// Set the distance under which training points are considered duplicates.
+    ///
+    /// Before fitting, training points closer than `tol` (euclidean distance) are merged
+    /// into one point whose output value is the mean of the outputs of merged points.
+    /// Without this option, a warning is emitted when training points nearly (but not exactly)
+    /// coincide, i.e. are closer than 1e-10, as it may make the fit fail.
+    pub fn merge_duplicates(mut self, tol: F) -> Self {
+        self.0.merge_duplicates = Some(tol);
+        self
+    }
+
+    /// Set the scaling of training inputs (default to [Scaler::Standard]).
+    ///
+    /// Prediction inputs are scaled the same way, hence the choice is transparent
+    /// apart from theta values which are expressed in the scaled input space.
+    pub fn scaler(mut self, scaler: Scaler) -> Self {
+        self.0.scaler = scaler;
+        self
+    }
+
+    /// Set a jitter added to the diagonal of the training correlation matrix
+    /// for numerical stability of its factorization only (default to 0).
+    ///
+    /// Unlike the nugget which is considered as noise by the default smoothing predictor,
+    /// the jitter is kept in the correlation of training points with themselves
+    /// at prediction time, hence the GP still interpolates training outputs
+    /// with a vanishing predictive variance at training points.
+    pub fn fit_jitter(mut self, fit_jitter: F) -> Self {
+        self.0.fit_jitter = fit_jitter;
+        self
+    }
+
+    /// Set whether the PLS dimension reduction given by `kpls_dim` is strictly enforced.
+    ///
+    /// By default, a number of components greater than the training input dimension is
+    /// clamped to the input dimension and PLS components which can not be extracted from
+    /// training data are left null, a warning being logged.
+    /// In strict mode, both cases are reported as errors at fit time.
+    pub fn strict_kpls(mut self, strict: bool) -> Self {
+        self.0.strict_kpls = strict;
+        self
+    }
+
+    /// Set unordered categorical inputs, each one being one-hot encoded in training inputs
+    /// as `k` consecutive columns starting at index `first` given as `(first, k)` pairs.
+    ///
+    /// Each level of a categorical input is mapped to a scalar latent coordinate, the first level
+    /// being at the origin, and the correlation between two levels is given by the correlation
+    /// model applied to the difference of their latent coordinates. The `k - 1` latent coordinates
+    /// are optimized along with theta and appended to it, while theta values of one-hot columns
+    /// are left to one. Categorical inputs can not be used with PLS dimension reduction.
+    pub fn categorical_inputs(mut self, categorical_inputs: Vec<(usize, usize)>) -> Self {
+        self.0.categorical_inputs = categorical_inputs;
+        self
+    }
 }
 
 impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>>
@@ -241,6 +447,65 @@ impl<F: Float, Mean: RegressionModel<F>, Corr: CorrelationModel<F>> ParamGuard
                 )));
             };
         }
+        if let Some(noise) = self.0.observation_noise.as_ref() {
+            if noise.iter().any(|v| !v.is_finite() || *v < F::zero()) {
+                return Err(GpError::InvalidValueError(
+                    "`observation_noise` values should be finite and non-negative".to_string(),
+                ));
+            }
+        }
+        let mut cat_inputs = self.0.categorical_inputs.clone();
+        cat_inputs.sort();
+        for (i, (first, k)) in cat_inputs.iter().enumerate() {
+            if *k < 2 {
+                return Err(GpError::InvalidValueError(format!(
+                    "Categorical input at column {} should have at least 2 levels, got {}",
+                    first, k
+                )));
+            }
+            if cat_inputs
+                .get(i + 1)
+                .is_some_and(|(next, _)| *next < first + k)
+            {
+                return Err(GpError::InvalidValueError(
+                    "`categorical_inputs` columns should not overlap".to_string(),
+                ));
+            }
+        }
+        if !cat_inputs.is_empty() && self.0.kpls_dim.is_some() {
+            return Err(GpError::InvalidValueError(
+                "`categorical_inputs` cannot be used with `kpls_dim`".to_string(),
+            ));
+        }
+        if self.0.fit_jitter < F::zero() {
+            return Err(GpError::InvalidValueError(
+                "`fit_jitter` should be positive".to_string(),
+            ));
+        }
+        if let Some(weights) = self.0.sample_weights.as_ref() {
+            if weights.iter().any(|v| !v.is_finite() || *v <= F::zero()) {
+                return Err(GpError::InvalidValueError(
+                    "`sample_weights` values should be finite and strictly positive".to_string(),
+                ));
+            }
+        }
+        if let Some(tol) = self.0.merge_duplicates {
+            if tol < F::zero() {
+                return Err(GpError::InvalidValueError(
+                    "`merge_duplicates` tolerance should be positive".to_string(),
+                ));
+            }
+            if self.0.observation_noise.is_some() {
+                return Err(GpError::InvalidValueError(
+                    "`merge_duplicates` cannot be used with `observation_noise`".to_string(),
+                ));
+            }
+            if self.0.sample_weights.is_some() {
+                return Err(GpError::InvalidValueError(
+                    "`merge_duplicates` cannot be used with `sample_weights`".to_string(),
+                ));
+            }
+        }
         Ok(&self.0)
     }
 
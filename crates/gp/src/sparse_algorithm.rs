@@ -1,13 +1,18 @@
 use crate::errors::{GpError, Result};
 use crate::optimization::{optimize_params, prepare_multistart, CobylaParams};
 use crate::sparse_parameters::{Inducings, ParamTuning, SgpParams, SgpValidParams, SparseMethod};
+use crate::utils::{
+    check_finite, normalized_relevance, numeric_gradient, outside_bounding_box,
+    pairwise_differences,
+};
 use crate::ThetaTuning;
-use crate::{correlation_models::*, sample, utils::pairwise_differences, GpSamplingMethod};
-use finitediff::FiniteDiff;
+use crate::{correlation_models::*, sample, GpSamplingMethod};
 use linfa::prelude::{Dataset, DatasetBase, Fit, Float, PredictInplace};
 use linfa_linalg::{cholesky::*, triangular::*};
 use linfa_pls::PlsRegression;
-use ndarray::{s, Array, Array1, Array2, ArrayBase, ArrayView2, Axis, Data, Ix1, Ix2, Zip};
+use ndarray::{
+    s, Array, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, DataMut, Ix1, Ix2, Zip,
+};
 use ndarray_einsum_beta::*;
 use ndarray_rand::rand::seq::SliceRandom;
 use ndarray_rand::rand::SeedableRng;
@@ -239,6 +244,35 @@ impl<F: Float, Corr: CorrelationModel<F>> SparseGaussianProcess<F, Corr> {
         Ok(mu)
     }
 
+    /// Predict output values at n given `x` points of nx components specified as a (n, nx) matrix
+    /// into the given (n,) `out` vector which is written in place.
+    /// Returns an error if `out` length is not n.
+    pub fn predict_into(
+        &self,
+        x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        out: &mut ArrayBase<impl DataMut<Elem = F>, Ix1>,
+    ) -> Result<()> {
+        if out.len() != x.nrows() {
+            return Err(GpError::InvalidValueError(format!(
+                "Output length should be {}, got {}",
+                x.nrows(),
+                out.len()
+            )));
+        }
+        let w = self.w_data.vec.column(0);
+        Zip::from(out).and(x.rows()).for_each(|y, xi| {
+            let kx = self.compute_k(
+                &xi.insert_axis(Axis(0)),
+                &self.inducings,
+                &self.w_star,
+                &self.theta,
+                self.sigma2,
+            );
+            *y = kx.row(0).dot(&w);
+        });
+        Ok(())
+    }
+
     /// Predict variance values at n given `x` points of nx components specified as a (n, nx) matrix.
     /// Returns n variance values as (n, 1) column vector.
     pub fn predict_var(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Result<Array2<F>> {
@@ -260,6 +294,16 @@ impl<F: Float, Corr: CorrelationModel<F>> SparseGaussianProcess<F, Corr> {
         &self.theta
     }
 
+    /// Relevance of each input dimension derived from the optimized hyperparameters theta
+    /// (projected back onto the original input space when KPLS is used),
+    /// normalized to sum to one: the larger the value the more influential the input
+    pub fn relevance(&self) -> Array1<F> {
+        // training inputs are not normalized: theta are scaled by inputs variances
+        let xvar = self.training_data.0.var_axis(Axis(0), F::one());
+        let w_star = &self.w_star * &xvar.mapv(|v| v.sqrt()).insert_axis(Axis(1));
+        normalized_relevance(&self.theta, &w_star)
+    }
+
     /// Estimated variance
     pub fn variance(&self) -> F {
         self.sigma2
@@ -275,11 +319,26 @@ impl<F: Float, Corr: CorrelationModel<F>> SparseGaussianProcess<F, Corr> {
         self.likelihood
     }
 
+    /// Retrieve training data (inputs (n, nx), outputs (n,)) the model was fitted on
+    pub fn training_data(&self) -> &(Array2<F>, Array1<F>) {
+        &self.training_data
+    }
+
+    /// Retrieve parameters used to fit the model (e.g. to fit a model of the same kind on other data)
+    pub fn fit_params(&self) -> &SgpValidParams<F, Corr> {
+        &self.params
+    }
+
     /// Inducing points
     pub fn inducings(&self) -> &Array2<F> {
         &self.inducings
     }
 
+    /// Flags the points of `x` (n, nx) lying outside the bounding box of the training inputs
+    pub fn is_extrapolating(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array1<bool> {
+        outside_bounding_box(&self.training_data.0, x)
+    }
+
     /// Retrieve number of PLS components 1 <= n <= x dimension
     pub fn kpls_dim(&self) -> Option<usize> {
         if self.w_star.ncols() < self.training_data.0.ncols() {
@@ -289,6 +348,12 @@ impl<F: Float, Corr: CorrelationModel<F>> SparseGaussianProcess<F, Corr> {
         }
     }
 
+    /// Retrieve the PLS rotation matrix (x dimension, number of PLS components)
+    /// projecting inputs onto the reduced space if any
+    pub fn pls_rotation(&self) -> Option<&Array2<F>> {
+        self.kpls_dim().map(|_| &self.w_star)
+    }
+
     /// Retrieve input and output dimensions
     pub fn dims(&self) -> (usize, usize) {
         (self.training_data.0.ncols(), self.training_data.1.len())
@@ -296,32 +361,32 @@ impl<F: Float, Corr: CorrelationModel<F>> SparseGaussianProcess<F, Corr> {
 
     pub fn predict_gradients(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array2<F> {
         let mut drv = Array2::<F>::zeros((x.nrows(), self.training_data.0.ncols()));
-        let f = |x: &Array1<f64>| -> f64 {
+        let f = |x: &ArrayView1<f64>| -> f64 {
             let x = x.to_owned().insert_axis(Axis(0)).mapv(|v| F::cast(v));
             let v = self.predict(&x).unwrap()[0];
-            unsafe { *(&v as *const F as *const f64) }
+            v.to_f64().unwrap()
         };
         Zip::from(drv.rows_mut())
             .and(x.rows())
             .for_each(|mut row, xi| {
-                let xi = xi.mapv(|v| unsafe { *(&v as *const F as *const f64) });
-                let grad = xi.central_diff(&f).mapv(|v| F::cast(v));
+                let xi = xi.mapv(|v| v.to_f64().unwrap());
+                let grad = numeric_gradient(f, &xi, None).mapv(|v| F::cast(v));
                 row.assign(&grad);
             });
         drv
     }
     pub fn predict_var_gradients(&self, x: &ArrayBase<impl Data<Elem = F>, Ix2>) -> Array2<F> {
         let mut drv = Array2::<F>::zeros((x.nrows(), self.training_data.0.ncols()));
-        let f = |x: &Array1<f64>| -> f64 {
+        let f = |x: &ArrayView1<f64>| -> f64 {
             let x = x.to_owned().insert_axis(Axis(0)).mapv(|v| F::cast(v));
             let v = self.predict_var(&x).unwrap()[[0, 0]];
-            unsafe { *(&v as *const F as *const f64) }
+            v.to_f64().unwrap()
         };
         Zip::from(drv.rows_mut())
             .and(x.rows())
             .for_each(|mut row, xi| {
-                let xi = xi.mapv(|v| unsafe { *(&v as *const F as *const f64) });
-                let grad = xi.central_diff(&f).mapv(|v| F::cast(v));
+                let xi = xi.mapv(|v| v.to_f64().unwrap());
+                let grad = numeric_gradient(f, &xi, None).mapv(|v| F::cast(v));
                 row.assign(&grad);
             });
         drv
@@ -417,6 +482,7 @@ impl<F: Float, Corr: CorrelationModel<F>, D: Data<Elem = F> + Sync>
         dataset: &DatasetBase<ArrayBase<D, Ix2>, ArrayBase<D, Ix1>>,
     ) -> Result<Self::Object> {
         let x = dataset.records();
+        check_finite(x, dataset.targets())?;
         let y = dataset.targets().to_owned().insert_axis(Axis(1));
 
         if let Some(d) = self.kpls_dim() {
@@ -472,9 +538,10 @@ impl<F: Float, Corr: CorrelationModel<F>, D: Data<Elem = F> + Sync>
 
         // Initial guess for theta
         let theta0_dim = init.len();
+        let n_theta = self.corr().n_theta(w_star.ncols());
         let theta0 = if theta0_dim == 1 {
-            Array1::from_elem(w_star.ncols(), self.theta_tuning().init()[0])
-        } else if theta0_dim == w_star.ncols() {
+            Array1::from_elem(n_theta, self.theta_tuning().init()[0])
+        } else if theta0_dim == n_theta {
             Array::from_vec(self.theta_tuning().init().to_vec())
         } else {
             panic!("Initial guess for theta should be either 1-dim or dim of xtrain (w_star.ncols()), got {}", theta0_dim)
@@ -534,7 +601,7 @@ impl<F: Float, Corr: CorrelationModel<F>, D: Data<Elem = F> + Sync>
                 &z,
                 self.nugget(),
             ) {
-                Ok(r) => unsafe { -(*(&r.0 as *const F as *const f64)) },
+                Ok(r) => -r.0.to_f64().unwrap(),
                 Err(_) => f64::INFINITY,
             }
         };
@@ -924,6 +991,10 @@ mod tests {
         // assert_abs_diff_eq!(eta2, sgp.noise_variance());
 
         let sgp_vals = sgp.predict(&xplot).unwrap().insert_axis(Axis(1));
+        let mut out = Array1::zeros(xplot.nrows());
+        sgp.predict_into(&xplot, &mut out).expect("SGP prediction");
+        assert_abs_diff_eq!(out, sgp_vals.column(0), epsilon = 1e-9);
+        assert!(sgp.predict_into(&xplot, &mut Array1::zeros(3)).is_err());
         let yplot = f_obj(&xplot);
         let errvals = (yplot - &sgp_vals).mapv(|v| v.abs());
         assert_abs_diff_eq!(errvals, Array2::zeros((xplot.nrows(), 1)), epsilon = 0.5);
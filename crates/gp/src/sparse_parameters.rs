@@ -2,7 +2,7 @@ use crate::correlation_models::{CorrelationModel, SquaredExponentialCorr};
 use crate::errors::{GpError, Result};
 use crate::mean_models::ConstantMean;
 use crate::parameters::GpValidParams;
-use crate::ThetaTuning;
+use crate::{Scaler, ThetaTuning};
 use linfa::{Float, ParamGuard};
 use ndarray::Array2;
 #[cfg(feature = "serializable")]
@@ -151,6 +151,14 @@ impl<F: Float, Corr: CorrelationModel<F>> SgpParams<F, Corr> {
                 kpls_dim: None,
                 n_start: 10,
                 nugget: F::cast(1000.0) * F::epsilon(),
+                observation_noise: None,
+                sample_weights: None,
+                merge_duplicates: None,
+                theta_init_from_data: false,
+                scaler: Scaler::default(),
+                fit_jitter: F::zero(),
+                strict_kpls: false,
+                categorical_inputs: vec![],
             },
             noise: ParamTuning::default(),
             z: inducings,
@@ -1,5 +1,7 @@
+use crate::errors::{GpError, Result};
+use crate::parameters::Scaler;
 use linfa::Float;
-use ndarray::{s, Array, Array1, Array2, ArrayBase, Axis, Data, Ix1, Ix2};
+use ndarray::{s, Array, Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix1, Ix2};
 #[cfg(feature = "serializable")]
 use serde::{Deserialize, Serialize};
 
@@ -36,6 +38,33 @@ impl<F: Float> NormalizedData<F> {
         }
     }
 
+    /// Constructor using the given scaling: mean and std vectors store
+    /// respectively the offset and the scale factors of the data
+    pub fn with_scaler(
+        x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+        scaler: Scaler,
+    ) -> NormalizedData<F> {
+        match scaler {
+            Scaler::Standard => NormalizedData::new(x),
+            Scaler::None => NormalizedData {
+                data: x.to_owned(),
+                mean: Array1::zeros(x.ncols()),
+                std: Array1::ones(x.ncols()),
+            },
+            Scaler::MinMax => {
+                let xmin = x.fold_axis(Axis(0), F::infinity(), |m, v| m.min(*v));
+                let xmax = x.fold_axis(Axis(0), F::neg_infinity(), |m, v| m.max(*v));
+                // range is zero for constant features
+                let range = (xmax - &xmin).mapv(|v| if v > F::zero() { v } else { F::one() });
+                NormalizedData {
+                    data: (x - &xmin) / &range,
+                    mean: xmin,
+                    std: range,
+                }
+            }
+        }
+    }
+
     /// Dimension of data points
     pub fn ncols(&self) -> usize {
         self.data.ncols()
@@ -47,7 +76,14 @@ pub fn normalize<F: Float>(
 ) -> (Array2<F>, Array1<F>, Array1<F>) {
     let x_mean = x.mean_axis(Axis(0)).unwrap();
     let mut x_std = x.std_axis(Axis(0), F::one());
-    x_std.mapv_inplace(|v| if v == F::zero() { F::one() } else { v });
+    // std is zero for constant features and undefined (nan) for a single point
+    x_std.mapv_inplace(|v| {
+        if v == F::zero() || v.is_nan() {
+            F::one()
+        } else {
+            v
+        }
+    });
     let xnorm = (x - &x_mean) / &x_std;
 
     (xnorm, x_mean, x_std)
@@ -130,12 +166,212 @@ pub fn differences<F: Float>(
     x.to_owned() - y
 }
 
+/// Maps one-hot encoded categorical inputs of scaled points `xnorm` (n, nx) to latent coordinates.
+/// For each `(first, k)` of `categories`, column `first` is set to the latent coordinate
+/// `sum_l latent_l * x_l` of the point level (the first level being at the origin, `latent` giving
+/// the k-1 coordinates of the other levels) computed from unscaled values using `scale` (nx,),
+/// the other k-1 columns being set to zero. Other columns are left unchanged.
+pub fn latent_inputs<F: Float>(
+    xnorm: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    categories: &[(usize, usize)],
+    latent: &ArrayBase<impl Data<Elem = F>, Ix1>,
+    scale: &ArrayBase<impl Data<Elem = F>, Ix1>,
+) -> Array2<F> {
+    let mut z = xnorm.to_owned();
+    let mut offset = 0;
+    for &(first, k) in categories {
+        let mut coord = Array1::zeros(xnorm.nrows());
+        for l in 1..k {
+            coord.scaled_add(
+                latent[offset + l - 1] * scale[first + l],
+                &xnorm.column(first + l),
+            );
+        }
+        z.slice_mut(s![.., first..first + k]).fill(F::zero());
+        z.column_mut(first).assign(&coord);
+        offset += k - 1;
+    }
+    z
+}
+
+/// Estimates a starting value of theta hyperparameters from pairwise `distances` (n_pairs, nx)
+/// between training inputs and projection `weights` (nx, h) as the inverse of the median
+/// of non-zero squared distances along each of the h projected dimensions.
+/// Theta defaults to one along a dimension without any non-zero distance.
+pub fn theta_init_from_distances<F: Float>(
+    distances: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    weights: &ArrayBase<impl Data<Elem = F>, Ix2>,
+) -> Array1<F> {
+    let projected = distances.dot(weights);
+    projected.map_axis(Axis(0), |d| {
+        let mut d2: Vec<F> = d
+            .iter()
+            .map(|v| *v * *v)
+            .filter(|v| *v > F::zero())
+            .collect();
+        if d2.is_empty() {
+            F::one()
+        } else {
+            d2.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            F::one() / d2[d2.len() / 2]
+        }
+    })
+}
+
+/// Computes relevance of each input dimension from `theta` (h,) and projection `weights` (nx, h)
+/// (theta being k concatenated (h,) sets in case of k combined correlation models)
+/// as `sum_l theta_l * weights_il^2` normalized to sum to one
+/// (uniform relevance is returned when all values are zero)
+pub fn normalized_relevance<F: Float>(
+    theta: &ArrayBase<impl Data<Elem = F>, Ix1>,
+    weights: &ArrayBase<impl Data<Elem = F>, Ix2>,
+) -> Array1<F> {
+    // theta sets of combined correlation models are summed up
+    let theta = if theta.len() > weights.ncols() {
+        theta
+            .to_owned()
+            .into_shape((theta.len() / weights.ncols(), weights.ncols()))
+            .unwrap()
+            .sum_axis(Axis(0))
+    } else {
+        theta.to_owned()
+    };
+    let theta_w = (weights.mapv(|v| v * v) * theta).sum_axis(Axis(1));
+    let total = theta_w.sum();
+    if total > F::zero() {
+        theta_w / total
+    } else {
+        Array1::from_elem(weights.nrows(), F::one() / F::cast(weights.nrows()))
+    }
+}
+
+/// Flags the points of `x` (n, nx) lying outside the bounding box of the `xt` (nt, nx)
+/// training points, i.e. having at least one component outside the training range
+pub fn outside_bounding_box<F: Float>(
+    xt: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+) -> Array1<bool> {
+    let xmin = xt.fold_axis(Axis(0), F::infinity(), |m, &v| m.min(v));
+    let xmax = xt.fold_axis(Axis(0), F::neg_infinity(), |m, &v| m.max(v));
+    x.map_axis(Axis(1), |xi| {
+        xi.iter()
+            .zip(xmin.iter().zip(xmax.iter()))
+            .any(|(&v, (&lo, &up))| v < lo || v > up)
+    })
+}
+
+/// Checks that training inputs `x` (n, nx) and outputs `y` (n,) only contain finite values.
+///
+/// Returns a [`GpError::InvalidValueError`] naming the first offending row (and column)
+/// otherwise, e.g. when a failed simulation returned NaN.
+pub fn check_finite<F: Float>(
+    x: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    y: &ArrayBase<impl Data<Elem = F>, Ix1>,
+) -> Result<()> {
+    if let Some(((i, j), v)) = x.indexed_iter().find(|(_, v)| !v.is_finite()) {
+        return Err(GpError::InvalidValueError(format!(
+            "Training input x[{i}, {j}] should be finite, got {v}"
+        )));
+    }
+    if let Some((i, v)) = y.indexed_iter().find(|(_, v)| !v.is_finite()) {
+        return Err(GpError::InvalidValueError(format!(
+            "Training output y[{i}] should be finite, got {v}"
+        )));
+    }
+    Ok(())
+}
+
+/// Default relative step used by [`numeric_gradient`]: cubic root of machine epsilon
+/// (about 6e-6) which balances truncation and rounding errors of central differences
+pub const NUMERIC_GRADIENT_STEP: f64 = 6.055454452393343e-6;
+
+/// Computes the gradient of `f` at `x` with central finite differences.
+///
+/// Along each dimension i, the step is `h * max(1, |x_i|)`, `h` defaulting to
+/// [`NUMERIC_GRADIENT_STEP`] when `None`. Intended as a fallback when no analytic
+/// derivative is available.
+pub fn numeric_gradient(
+    f: impl Fn(&ArrayView1<f64>) -> f64,
+    x: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+    h: Option<f64>,
+) -> Array1<f64> {
+    let h = h.unwrap_or(NUMERIC_GRADIENT_STEP);
+    let mut xh = x.to_owned();
+    Array1::from_shape_fn(x.len(), |i| {
+        let xi = x[i];
+        let step = h * xi.abs().max(1.);
+        xh[i] = xi + step;
+        let f_plus = f(&xh.view());
+        xh[i] = xi - step;
+        let f_minus = f(&xh.view());
+        xh[i] = xi;
+        (f_plus - f_minus) / (2. * step)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use approx::assert_abs_diff_eq;
     use ndarray::array;
 
+    #[test]
+    fn test_check_finite() {
+        let x = array![[0., 1.], [2., 3.]];
+        assert!(check_finite(&x, &array![0., 1.]).is_ok());
+        let err = check_finite(&x, &array![0., f64::NAN]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "InvalidValue error: Training output y[1] should be finite, got NaN"
+        );
+        let x = array![[0., 1.], [f64::INFINITY, 3.]];
+        let err = check_finite(&x, &array![0., 1.]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "InvalidValue error: Training input x[1, 0] should be finite, got inf"
+        );
+    }
+
+    #[test]
+    fn test_theta_init_from_distances() {
+        // regular grid with spacing 10 along x0 and 0.1 along x1
+        let x = array![[0., 0.], [10., 0.1], [20., 0.2], [30., 0.3]];
+        let dm = DistanceMatrix::new(&x);
+        let theta = theta_init_from_distances(&dm.d, &Array2::eye(2));
+        // median squared distance is (2 * spacing)^2
+        assert_abs_diff_eq!(theta, array![1. / 400., 1. / 0.04], epsilon = 1e-10);
+        // projection onto the first dimension
+        let theta = theta_init_from_distances(&dm.d, &array![[1.], [0.]]);
+        assert_abs_diff_eq!(theta, array![1. / 400.], epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_outside_bounding_box() {
+        let xt = array![[0., 1.], [2., -1.], [1., 0.]];
+        let x = array![[1., 0.5], [0., -1.], [2.5, 0.], [1., 1.1], [-3., 5.]];
+        assert_eq!(
+            outside_bounding_box(&xt, &x),
+            array![false, false, true, true, true]
+        );
+    }
+
+    #[test]
+    fn test_numeric_gradient() {
+        // f(x) = x^T A x + b^T x with gradient (A + A^T) x + b
+        let a = array![[2., 1.], [0., 3.]];
+        let b = array![1., -2.];
+        let f = |x: &ArrayView1<f64>| x.dot(&a.dot(x)) + b.dot(x);
+        for x in [array![0., 0.], array![1.5, -2.], array![1e3, 2e-3]] {
+            let expected = (&a + &a.t()).dot(&x) + &b;
+            let grad = numeric_gradient(f, &x, None);
+            assert_abs_diff_eq!(grad, expected, epsilon = 1e-6 * x[0].abs().max(1.));
+        }
+        // central differences are exact on quadratics whatever the step
+        let x = array![0.5, 0.25];
+        let grad = numeric_gradient(f, &x, Some(1e-1));
+        assert_abs_diff_eq!(grad, (&a + &a.t()).dot(&x) + &b, epsilon = 1e-10);
+    }
+
     #[test]
     fn test_pairwise_differences() {
         let x = array![[-0.9486833], [-0.82219219]];
@@ -0,0 +1,63 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use egobox_doe::{Lhs, SamplingMethod};
+use egobox_moe::*;
+use linfa::prelude::*;
+use ndarray::{array, s, Array1, Array2, Axis, Zip};
+use ndarray_rand::rand::SeedableRng;
+use rand_xoshiro::Xoshiro256Plus;
+
+fn function_test_1d(x: &Array2<f64>) -> Array1<f64> {
+    let mut y = Array2::zeros(x.dim());
+    Zip::from(&mut y).and(x).for_each(|yi, &xi| {
+        if xi < 0.4 {
+            *yi = xi * xi;
+        } else if (0.4..0.8).contains(&xi) {
+            *yi = 3. * xi + 1.;
+        } else {
+            *yi = f64::sin(10. * xi);
+        }
+    });
+    y.remove_axis(Axis(1))
+}
+
+// Run with `--features parallel` to compare with sequential prediction
+fn criterion_benchmark(c: &mut Criterion) {
+    let rng = Xoshiro256Plus::seed_from_u64(42);
+    let doe = Lhs::new(&array![[0., 1.]]).with_rng(rng);
+    let xtrain = doe.sample(100);
+    let ytrain = function_test_1d(&xtrain);
+    let moe = GpMixture::params()
+        .n_clusters(3)
+        .with_rng(Xoshiro256Plus::seed_from_u64(42))
+        .fit(&Dataset::new(xtrain, ytrain))
+        .expect("MOE fitted");
+    let x = Array1::linspace(0., 1., 100_000).insert_axis(Axis(1));
+
+    let mut group = c.benchmark_group("predict");
+    group.sample_size(10);
+    group.bench_function("predict", |b| b.iter(|| moe.predict(&x).unwrap()));
+    group.bench_function("predict_par", |b| {
+        b.iter(|| moe.predict_par(&x.view()).unwrap())
+    });
+    group.finish();
+
+    // Clustering probabilities on a strided view of a large buffer (no copy of x points)
+    let gmx = moe.gmx();
+    let buffer = Array1::linspace(0., 1., 200_000)
+        .into_shape((100_000, 2))
+        .unwrap();
+    let x = buffer.slice(s![.., ..1]);
+    let mut group = c.benchmark_group("gmx");
+    group.sample_size(10);
+    group.bench_function("predict_probas", |b| b.iter(|| gmx.predict_probas(&x)));
+    // Repeated calls on a single point dominated by per-call overhead
+    let x = array![[0.5]];
+    group.bench_function("predict_probas_single", |b| {
+        b.iter(|| gmx.predict_probas(&x))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use egobox_doe::{Lhs, SamplingMethod};
+use egobox_moe::*;
+use linfa::prelude::*;
+use ndarray::{array, Array1, Array2, Axis, Zip};
+use ndarray_rand::rand::SeedableRng;
+use rand_xoshiro::Xoshiro256Plus;
+
+fn function_test_1d(x: &Array2<f64>) -> Array1<f64> {
+    let mut y = Array2::zeros(x.dim());
+    Zip::from(&mut y).and(x).for_each(|yi, &xi| {
+        if xi < 0.4 {
+            *yi = xi * xi;
+        } else if (0.4..0.8).contains(&xi) {
+            *yi = 3. * xi + 1.;
+        } else {
+            *yi = f64::sin(10. * xi);
+        }
+    });
+    y.remove_axis(Axis(1))
+}
+
+// Run with `--features parallel` to compare with sequential experts training
+fn criterion_benchmark(c: &mut Criterion) {
+    let rng = Xoshiro256Plus::seed_from_u64(42);
+    let doe = Lhs::new(&array![[0., 1.]]).with_rng(rng);
+    let xtrain = doe.sample(200);
+    let ytrain = function_test_1d(&xtrain);
+    let ds = Dataset::new(xtrain, ytrain);
+
+    let mut group = c.benchmark_group("train_experts");
+    group.sample_size(10);
+    group.bench_function("train_experts", |b| {
+        b.iter(|| {
+            GpMixture::params()
+                .n_clusters(5)
+                .regression_spec(RegressionSpec::ALL)
+                .correlation_spec(CorrelationSpec::ALL)
+                .with_rng(Xoshiro256Plus::seed_from_u64(42))
+                .fit(&ds)
+                .expect("MOE fitted")
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,5 +1,7 @@
 use super::gaussian_mixture::GaussianMixture;
-use crate::clustering::{find_best_number_of_clusters, sort_by_cluster};
+use crate::clustering::{
+    find_best_number_of_clusters, find_best_number_of_clusters_bic, sort_by_cluster,
+};
 use crate::errors::MoeError;
 use crate::errors::Result;
 use crate::parameters::{GpMixtureParams, GpMixtureValidParams};
@@ -8,11 +10,14 @@ use crate::types::*;
 use crate::{expertise_macros::*, GpType};
 
 use egobox_gp::metrics::CrossValScore;
-use egobox_gp::{correlation_models::*, mean_models::*, GaussianProcess, SparseGaussianProcess};
+use egobox_gp::{
+    check_finite, correlation_models::*, mean_models::*, outside_bounding_box, GaussianProcess,
+};
 use linfa::dataset::Records;
 use linfa::traits::{Fit, Predict, PredictInplace};
 use linfa::{Dataset, DatasetBase, Float, ParamGuard};
-use linfa_clustering::GaussianMixtureModel;
+use linfa_clustering::{GaussianMixtureModel, GmmError, GmmInitMethod, KMeans};
+use linfa_pls::PlsRegression;
 use log::{debug, info, trace};
 use paste::paste;
 use std::cmp::Ordering;
@@ -21,7 +26,8 @@ use std::ops::Sub;
 #[cfg(not(feature = "blas"))]
 use linfa_linalg::norm::*;
 use ndarray::{
-    concatenate, s, Array1, Array2, Array3, ArrayBase, ArrayView2, Axis, Data, Ix1, Ix2, Zip,
+    concatenate, s, Array1, Array2, Array3, ArrayBase, ArrayView2, Axis, CowArray, Data, Ix1, Ix2,
+    Zip,
 };
 
 #[cfg(feature = "blas")]
@@ -29,6 +35,8 @@ use ndarray_linalg::Norm;
 use ndarray_rand::rand::Rng;
 use ndarray_stats::QuantileExt;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 #[cfg(feature = "serializable")]
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "persistent")]
@@ -74,7 +82,56 @@ impl GpMixtureValidParams<f64> {
         xt: &ArrayBase<impl Data<Elem = f64>, Ix2>,
         yt: &ArrayBase<impl Data<Elem = f64>, Ix1>,
     ) -> Result<GpMixture> {
+        check_finite(xt, yt)?;
         trace!("Moe training...");
+        let (clustering, kept, n_clusters) = self.train_clustering(xt, yt)?;
+
+        trace!("Train on clusters...");
+        if kept.len() < n_clusters {
+            info!(
+                "Clusters too small (< {} points) removed, {} clusters kept",
+                self.min_cluster_size(),
+                kept.len()
+            );
+            let theta_tunings = if self.theta_tunings().len() > 1 {
+                kept.iter()
+                    .map(|&k| self.theta_tunings()[k].clone())
+                    .collect()
+            } else {
+                self.theta_tunings().clone()
+            };
+            GpMixtureParams::from(self.clone())
+                .n_clusters(if self.n_clusters() == 0 {
+                    0
+                } else {
+                    kept.len()
+                })
+                .theta_tunings(&theta_tunings)
+                .check()?
+                .train_on_clusters(&xt.view(), &yt.view(), &clustering)
+        } else {
+            self.train_on_clusters(&xt.view(), &yt.view(), &clustering)
+        }
+    }
+
+    /// Train only the clustering (gaussian mixture) of the mixture of experts on (xt, yt)
+    /// data, experts can then be trained with [GpMixtureValidParams::train_on_clusters].
+    /// Clusters with too few points (see `min_cluster_size`) are removed.
+    pub fn fit_clustering(
+        &self,
+        xt: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        yt: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+    ) -> Result<Clustering> {
+        Ok(self.train_clustering(xt, yt)?.0)
+    }
+
+    /// Train the clustering and returns it together with the indices of the kept clusters
+    /// and the initial number of clusters
+    fn train_clustering(
+        &self,
+        xt: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        yt: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+    ) -> Result<(Clustering, Vec<usize>, usize)> {
         let nx = xt.ncols();
         let data = concatenate(
             Axis(1),
@@ -82,10 +139,10 @@ impl GpMixtureValidParams<f64> {
         )
         .unwrap();
 
-        let (n_clusters, recomb) = if self.n_clusters() == 0 {
+        let max_nb_clusters = xt.nrows() / 10 + 1;
+        let (n_clusters, recomb) = match (self.n_clusters(), self.cluster_selection()) {
             // automatic mode
-            let max_nb_clusters = xt.nrows() / 10 + 1;
-            find_best_number_of_clusters(
+            (0, ClusterSelection::CrossValidation) => find_best_number_of_clusters(
                 xt,
                 yt,
                 max_nb_clusters,
@@ -93,9 +150,13 @@ impl GpMixtureValidParams<f64> {
                 self.regression_spec(),
                 self.correlation_spec(),
                 self.rng(),
-            )
-        } else {
-            (self.n_clusters(), self.recombination())
+            ),
+            // automatic mode based on gaussian mixture only
+            (0, ClusterSelection::Bic) => (
+                find_best_number_of_clusters_bic(xt, yt, max_nb_clusters, self.rng()),
+                self.recombination(),
+            ),
+            (n_clusters, _) => (n_clusters, self.recombination()),
         };
         if self.n_clusters() == 0 {
             debug!("Automatic settings {} {:?}", n_clusters, recomb);
@@ -109,32 +170,108 @@ impl GpMixtureValidParams<f64> {
         } else {
             data.to_owned()
         };
-        let dataset = Dataset::from(training);
+        let projection = match self.cluster_in_pls_space() {
+            Some(n_components) => Some(pls_projection(xt, yt, n_components)?),
+            None => None,
+        };
+        let dataset = match &projection {
+            Some((offset, matrix)) => {
+                let zt = (&training.slice(s![.., ..nx]) - offset).dot(matrix);
+                Dataset::from(
+                    concatenate(Axis(1), &[zt.view(), training.slice(s![.., nx..])]).unwrap(),
+                )
+            }
+            None => Dataset::from(training),
+        };
+        let nz = dataset.records().ncols() - 1;
 
         let gmx = if self.gmx().is_some() {
             self.gmx().unwrap().clone()
         } else {
             trace!("GMM training...");
+            let init_method = match self.gmm_init() {
+                GmmInit::KMeans => GmmInitMethod::KMeans,
+                GmmInit::Random => GmmInitMethod::Random,
+            };
+            let records = dataset.records();
+            let (gmm_data, scaling) = match self.clustering_space() {
+                ClusteringSpace::Joint => (records.to_owned(), None),
+                ClusteringSpace::Input => (records.slice(s![.., ..nz]).to_owned(), None),
+                ClusteringSpace::StandardizedJoint => {
+                    let mean = records.mean_axis(Axis(0)).unwrap();
+                    let std = records
+                        .std_axis(Axis(0), 1.)
+                        .mapv(|v| if v > 0. { v } else { 1. });
+                    ((records - &mean) / &std, Some((mean, std)))
+                }
+            };
             let gmm = GaussianMixtureModel::params(n_clusters)
-                .n_runs(20)
+                .n_runs(self.gmm_n_init() as u64)
+                .max_n_iterations(self.gmm_max_iter() as u64)
+                .tolerance(self.gmm_tol())
+                .init_method(init_method)
                 .with_rng(self.rng())
-                .fit(&dataset)?;
+                .fit(&DatasetBase::from(gmm_data.view()));
+            let threshold = self.gmm_empty_cluster_threshold();
+            let gmm = match gmm {
+                Ok(gmm) => GaussianMixture::new(
+                    gmm.weights().to_owned(),
+                    gmm.means().to_owned(),
+                    gmm.covariances().to_owned(),
+                )?,
+                // linfa EM declares a cluster empty under its own 10 * eps threshold:
+                // run EM again with a looser configured threshold
+                Err(GmmError::EmptyCluster(msg))
+                    if threshold < crate::default_gmm_empty_cluster_threshold() =>
+                {
+                    debug!("{msg}, EM restarted with empty cluster threshold {threshold}");
+                    kmeans_mixture(&gmm_data, n_clusters, self.rng())?
+                        .empty_cluster_threshold(threshold)
+                        .em(&gmm_data, self.gmm_max_iter(), self.gmm_tol(), None)?
+                }
+                Err(err) => return Err(err.into()),
+            };
 
             // GMX for prediction
             let weights = gmm.weights().to_owned();
-            let means = gmm.means().slice(s![.., ..nx]).to_owned();
-            let covariances = gmm.covariances().slice(s![.., ..nx, ..nx]).to_owned();
+            let n_points = dataset.records().nrows() as f64;
+            if let Some(k) = weights
+                .iter()
+                .position(|w| w * n_points < self.gmm_empty_cluster_threshold())
+            {
+                return Err(MoeError::EmptyCluster(format!(
+                    "Cluster #{} has no more point during EM",
+                    k + 1
+                )));
+            }
+            let mut means = gmm.means().slice(s![.., ..nz]).to_owned();
+            let mut covariances = gmm.covariances().slice(s![.., ..nz, ..nz]).to_owned();
+            if let Some((mean, std)) = scaling {
+                // back to the (projected) input space
+                let (mean, std) = (mean.slice(s![..nz]), std.slice(s![..nz]));
+                means = means * std + mean;
+                let std = std.to_owned();
+                let scale = std
+                    .view()
+                    .insert_axis(Axis(1))
+                    .dot(&std.view().insert_axis(Axis(0)));
+                covariances = covariances * scale;
+            }
             let factor = match recomb {
                 Recombination::Smooth(Some(f)) => f,
                 Recombination::Smooth(None) => 1.,
                 Recombination::Hard => 1.,
             };
-            GaussianMixture::new(weights, means, covariances)?.heaviside_factor(factor)
+            let gmx = GaussianMixture::new(weights, means, covariances)?.heaviside_factor(factor);
+            match projection {
+                Some((offset, matrix)) => gmx.projection(offset, matrix),
+                None => gmx,
+            }
         };
 
-        trace!("Train on clusters...");
-        let clustering = Clustering::new(gmx, recomb);
-        self.train_on_clusters(&xt.view(), &yt.view(), &clustering)
+        let n_clusters = gmx.n_clusters();
+        let (gmx, kept) = remove_small_clusters(xt, gmx, self.min_cluster_size())?;
+        Ok((Clustering::new(gmx, recomb), kept, n_clusters))
     }
 
     /// Using the current state of the clustering, select and train the experts
@@ -160,19 +297,17 @@ impl GpMixtureValidParams<f64> {
         check_number_of_points(&clusters, xt.ncols())?;
 
         // Fit GPs on clustered data
-        let mut experts = Vec::new();
         let nb_clusters = clusters.len();
-        for (nc, cluster) in clusters.iter().enumerate() {
-            if nb_clusters > 1 && cluster.nrows() < 3 {
+        if nb_clusters > 1 {
+            if let Some(cluster) = clusters.iter().find(|cluster| cluster.nrows() < 3) {
                 return Err(MoeError::ClusteringError(format!(
                     "Not enough points in cluster, requires at least 3, got {}",
                     cluster.nrows()
                 )));
             }
-            debug!("nc={} theta_tuning={:?}", nc, self.theta_tunings());
-            let expert = self.find_best_expert(nc, nx, cluster)?;
-            experts.push(expert);
         }
+        // Experts are independent hence trained in parallel when `parallel` feature is enabled
+        let experts = self.train_experts(nx, &clusters, cfg!(feature = "parallel"))?;
 
         if recomb == Recombination::Smooth(None) && self.n_clusters() > 1 {
             // Extract 5% of data for validation to find best heaviside factor
@@ -204,6 +339,27 @@ impl GpMixtureValidParams<f64> {
 
     /// Select the surrogate which gives the smallest prediction error on the given data
     /// The error is computed using cross-validation
+    /// Train an expert on each cluster data (x, y), in parallel if `parallel` is true
+    /// which requires the `parallel` feature, experts being trained sequentially otherwise.
+    fn train_experts(
+        &self,
+        nx: usize,
+        clusters: &[Array2<f64>],
+        parallel: bool,
+    ) -> Result<Vec<Box<dyn FullGpSurrogate>>> {
+        let fit_expert = |(nc, cluster): (usize, &Array2<f64>)| {
+            debug!("nc={} theta_tuning={:?}", nc, self.theta_tunings());
+            self.find_best_expert(nc, nx, cluster)
+        };
+        #[cfg(feature = "parallel")]
+        if parallel {
+            return clusters.par_iter().enumerate().map(fit_expert).collect();
+        }
+        #[cfg(not(feature = "parallel"))]
+        let _ = parallel;
+        clusters.iter().enumerate().map(fit_expert).collect()
+    }
+
     fn find_best_expert(
         &self,
         nc: usize,
@@ -234,6 +390,24 @@ impl GpMixtureValidParams<f64> {
         );
         check_allowed!(correlation_spec, Correlation, Matern32, allowed_corrs);
         check_allowed!(correlation_spec, Correlation, Matern52, allowed_corrs);
+        check_allowed!(
+            correlation_spec,
+            Correlation,
+            Matern52PlusSquaredExponential,
+            allowed_corrs
+        );
+        check_allowed!(
+            correlation_spec,
+            Correlation,
+            SquaredExponentialPlusAbsoluteExponential,
+            allowed_corrs
+        );
+        check_allowed!(
+            correlation_spec,
+            Correlation,
+            SquaredExponentialTimesAbsoluteExponential,
+            allowed_corrs
+        );
 
         debug!("Find best expert");
         let best = if allowed_means.len() == 1 && allowed_corrs.len() == 1 {
@@ -254,40 +428,11 @@ impl GpMixtureValidParams<f64> {
         debug!("after Find best expert");
 
         let expert = match self.gp_type() {
-            GpType::FullGp { .. } => {
-                let best_expert_params: std::result::Result<Box<dyn GpSurrogateParams>, MoeError> =
-                    match best.0.as_str() {
-                        "Constant_SquaredExponential" => {
-                            Ok(make_surrogate_params!(Constant, SquaredExponential))
-                        }
-                        "Constant_AbsoluteExponential" => {
-                            Ok(make_surrogate_params!(Constant, AbsoluteExponential))
-                        }
-                        "Constant_Matern32" => Ok(make_surrogate_params!(Constant, Matern32)),
-                        "Constant_Matern52" => Ok(make_surrogate_params!(Constant, Matern52)),
-                        "Linear_SquaredExponential" => {
-                            Ok(make_surrogate_params!(Linear, SquaredExponential))
-                        }
-                        "Linear_AbsoluteExponential" => {
-                            Ok(make_surrogate_params!(Linear, AbsoluteExponential))
-                        }
-                        "Linear_Matern32" => Ok(make_surrogate_params!(Linear, Matern32)),
-                        "Linear_Matern52" => Ok(make_surrogate_params!(Linear, Matern52)),
-                        "Quadratic_SquaredExponential" => {
-                            Ok(make_surrogate_params!(Quadratic, SquaredExponential))
-                        }
-                        "Quadratic_AbsoluteExponential" => {
-                            Ok(make_surrogate_params!(Quadratic, AbsoluteExponential))
-                        }
-                        "Quadratic_Matern32" => Ok(make_surrogate_params!(Quadratic, Matern32)),
-                        "Quadratic_Matern52" => Ok(make_surrogate_params!(Quadratic, Matern52)),
-                        _ => {
-                            return Err(MoeError::ExpertError(format!("Unknown expert {}", best.0)))
-                        }
-                    };
-                let mut expert_params = best_expert_params?;
+            GpType::FullGp => {
+                let mut expert_params = make_surrogate_params_from_kind(&best.0)?;
                 expert_params.n_start(self.n_start());
                 expert_params.kpls_dim(self.kpls_dim());
+                expert_params.categorical_inputs(self.categorical_inputs().to_vec());
                 if nc > 0 && self.theta_tunings().len() == 1 {
                     expert_params.theta_tuning(self.theta_tunings()[0].clone());
                 } else {
@@ -302,22 +447,10 @@ impl GpMixtureValidParams<f64> {
                 sparse_method,
                 ..
             } => {
-                let inducings = inducings.to_owned();
-                let best_expert_params: std::result::Result<Box<dyn SgpSurrogateParams>, MoeError> =
-                    match best.0.as_str() {
-                        "Constant_SquaredExponential" => {
-                            Ok(make_sgp_surrogate_params!(SquaredExponential, inducings))
-                        }
-                        "Constant_AbsoluteExponential" => {
-                            Ok(make_sgp_surrogate_params!(AbsoluteExponential, inducings))
-                        }
-                        "Constant_Matern32" => Ok(make_sgp_surrogate_params!(Matern32, inducings)),
-                        "Constant_Matern52" => Ok(make_sgp_surrogate_params!(Matern52, inducings)),
-                        _ => {
-                            return Err(MoeError::ExpertError(format!("Unknown expert {}", best.0)))
-                        }
-                    };
-                let mut expert_params = best_expert_params?;
+                // sparse GP experts only use a constant mean
+                let kind = best.0.replacen("Constant_", "Sgp_", 1);
+                let mut expert_params =
+                    make_sgp_surrogate_params_from_kind(&kind, inducings.to_owned())?;
                 let seed = self.rng().gen();
                 debug!("Theta tuning = {:?}", self.theta_tunings());
                 expert_params.sparse_method(*sparse_method);
@@ -334,7 +467,7 @@ impl GpMixtureValidParams<f64> {
         if let Some(v) = best.1 {
             info!("Best expert {} accuracy={}", best.0, v);
         }
-        expert.map_err(MoeError::from)
+        expert
     }
 
     /// Take the best heaviside factor from 0.1 to 2.1 (step 0.1).
@@ -369,6 +502,78 @@ impl GpMixtureValidParams<f64> {
     }
 }
 
+/// Compute the affine projection `(x - offset).matrix` of x onto the first `n_components`
+/// PLS components of the training data (xt, yt), standardization of x being folded in the matrix.
+fn pls_projection(
+    xt: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    yt: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+    n_components: usize,
+) -> Result<(Array1<f64>, Array2<f64>)> {
+    let ds = Dataset::new(xt.to_owned(), yt.to_owned().insert_axis(Axis(1)));
+    let pls = PlsRegression::params(n_components).fit(&ds)?;
+    let offset = xt.mean_axis(Axis(0)).unwrap();
+    let std = xt
+        .std_axis(Axis(0), 1.)
+        .mapv(|v| if v == 0. { 1. } else { v });
+    let matrix = pls.rotations().0 / &std.insert_axis(Axis(1));
+    Ok((offset, matrix))
+}
+
+/// Gaussian mixture of `n_clusters` initialized from a k-means clustering of `data`:
+/// weights, means and covariances (regularized as in EM) of the points of each cluster.
+fn kmeans_mixture<R: Rng + Clone>(
+    data: &Array2<f64>,
+    n_clusters: usize,
+    rng: R,
+) -> Result<GaussianMixture<f64>> {
+    let kmeans = KMeans::params_with_rng(n_clusters, rng)
+        .fit(&DatasetBase::from(data.view()))
+        .map_err(|err| MoeError::ClusteringError(err.to_string()))?;
+    let labels = kmeans.predict(data);
+    let nx = data.ncols();
+    let mut weights = Array1::zeros(n_clusters);
+    let mut covariances = Array3::zeros((n_clusters, nx, nx));
+    for (k, mean) in kmeans.centroids().rows().into_iter().enumerate() {
+        let indices: Vec<usize> = (0..data.nrows()).filter(|&i| labels[i] == k).collect();
+        let dx: Array2<f64> = &data.select(Axis(0), &indices) - &mean;
+        weights[k] = indices.len() as f64 / data.nrows() as f64;
+        let mut cov = covariances.index_axis_mut(Axis(0), k);
+        cov.assign(&(dx.t().dot(&dx) / (indices.len().max(1) as f64)));
+        cov.diag_mut().mapv_inplace(|v| v + 1e-6);
+    }
+    GaussianMixture::new(weights, kmeans.centroids().to_owned(), covariances)
+}
+
+/// Remove clusters of `gmx` with less than `min_cluster_size` points of `xt` one at a time
+/// (smallest first) until all clusters are big enough or only one cluster remains.
+/// Points of a removed cluster are reassigned to the clusters with highest responsabilities.
+/// Returns the resulting gaussian mixture and the indices of the kept clusters in `gmx`.
+fn remove_small_clusters(
+    xt: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    mut gmx: GaussianMixture<f64>,
+    min_cluster_size: usize,
+) -> Result<(GaussianMixture<f64>, Vec<usize>)> {
+    let mut kept: Vec<usize> = (0..gmx.n_clusters()).collect();
+    while min_cluster_size > 0 && gmx.n_clusters() > 1 {
+        let clustering = gmx.predict(xt);
+        let counts: Vec<usize> = (0..gmx.n_clusters())
+            .map(|k| clustering.iter().filter(|&&c| c == k).count())
+            .collect();
+        let (smallest, count) = counts
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &count)| count)
+            .unwrap();
+        if *count >= min_cluster_size {
+            break;
+        }
+        debug!("Remove cluster {} with {} points", kept[smallest], count);
+        gmx = gmx.remove_cluster(smallest)?;
+        kept.remove(smallest);
+    }
+    Ok((gmx, kept))
+}
+
 fn check_number_of_points<F>(
     clusters: &[ArrayBase<impl Data<Elem = F>, Ix2>],
     dim: usize,
@@ -469,6 +674,45 @@ impl GpSurrogate for GpMixture {
         self.experts[0].dims()
     }
 
+    fn kind(&self) -> String {
+        String::from("Mixture")
+    }
+
+    /// Relevances of experts weighted by the mixture weights of their clusters,
+    /// experts without relevance being ignored
+    fn relevance(&self) -> Array1<f64> {
+        self.experts.iter().zip(self.gmx.weights()).fold(
+            Array1::zeros(self.dims().0),
+            |acc, (expert, w)| {
+                let relevance = expert.relevance();
+                if relevance.is_empty() {
+                    acc
+                } else {
+                    acc + relevance * *w
+                }
+            },
+        )
+    }
+
+    /// Worst condition number among experts
+    fn condition_number(&self) -> f64 {
+        self.experts
+            .iter()
+            .map(|expert| expert.condition_number())
+            .fold(f64::NAN, f64::max)
+    }
+
+    fn is_extrapolating(&self, x: &ArrayView2<f64>) -> Result<Array1<bool>> {
+        Ok(outside_bounding_box(&self.training_data.0, x))
+    }
+
+    fn training_data(&self) -> (CowArray<'_, f64, Ix2>, CowArray<'_, f64, Ix2>) {
+        (
+            self.training_data.0.view().into(),
+            self.training_data.1.view().insert_axis(Axis(1)).into(),
+        )
+    }
+
     fn predict(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
         match self.recombination {
             Recombination::Hard => self.predict_hard(x),
@@ -485,10 +729,10 @@ impl GpSurrogate for GpMixture {
     /// Save Moe model in given file.
     #[cfg(feature = "persistent")]
     fn save(&self, path: &str, format: GpFileFormat) -> Result<()> {
-        let mut file = fs::File::create(path).unwrap();
+        let mut file = fs::File::create(path)?;
 
         let bytes = match format {
-            GpFileFormat::Json => serde_json::to_vec(self).map_err(MoeError::SaveJsonError)?,
+            GpFileFormat::Json => to_json_with_dtype(self, self.dtype())?,
             GpFileFormat::Binary => bincode::serialize(self).map_err(MoeError::SaveBinaryError)?,
         };
         file.write_all(&bytes)?;
@@ -547,6 +791,62 @@ impl GpMixture {
         GpMixtureParams::new()
     }
 
+    /// Assemble a mixture of experts from a gaussian mixture `gmx` and `experts` fitted beforehand,
+    /// the ith expert being used for the ith cluster of the gaussian mixture, with the given `recombination`.
+    ///
+    /// No training is done: the training data of the mixture is gathered from the experts ones.
+    /// Returns an [MoeError::InvalidValueError] if the number of experts does not match
+    /// the number of clusters or if experts input dimensions do not match the mixture one.
+    pub fn from_parts(
+        gmx: GaussianMixture<f64>,
+        experts: Vec<Box<dyn FullGpSurrogate>>,
+        recombination: Recombination<f64>,
+    ) -> Result<GpMixture> {
+        if experts.len() != gmx.n_clusters() {
+            return Err(MoeError::InvalidValueError(format!(
+                "Number of experts ({}) should match the number of clusters ({})",
+                experts.len(),
+                gmx.n_clusters()
+            )));
+        }
+        if let Some(expert) = experts
+            .iter()
+            .find(|expert| expert.dims().0 != gmx.n_features())
+        {
+            return Err(MoeError::InvalidValueError(format!(
+                "Expert {expert} input dimension ({}) should match the mixture one ({})",
+                expert.dims().0,
+                gmx.n_features()
+            )));
+        }
+        let factor = match recombination {
+            Recombination::Smooth(Some(factor)) => factor,
+            _ => 1.,
+        };
+        let (xs, ys): (Vec<_>, Vec<_>) =
+            experts.iter().map(|expert| expert.training_data()).unzip();
+        let xs = xs.iter().map(|x| x.view()).collect::<Vec<_>>();
+        let ys = ys.iter().map(|y| y.column(0)).collect::<Vec<_>>();
+        // dimensions are consistent: concatenations cannot fail
+        let training_data = (
+            concatenate(Axis(0), &xs).unwrap(),
+            concatenate(Axis(0), &ys).unwrap(),
+        );
+        let params = GpMixtureParams::new()
+            .n_clusters(gmx.n_clusters())
+            .recombination(recombination)
+            .check_unwrap();
+        let moe = GpMixture {
+            recombination,
+            experts,
+            gmx: gmx.heaviside_factor(factor),
+            gp_type: params.gp_type().clone(),
+            training_data,
+            params,
+        };
+        Ok(moe.set_recombination(recombination))
+    }
+
     /// Retrieve output dimensions from
     pub fn gp_type(&self) -> &GpType<f64> {
         &self.gp_type
@@ -587,6 +887,57 @@ impl GpMixture {
         self
     }
 
+    /// Training diagnostics of each expert: cluster size, kind, fitted theta,
+    /// number of PLS components and leave-one-out error.
+    ///
+    /// Beware the leave-one-out error requires to retrain each expert as many times
+    /// as its number of training points.
+    pub fn expert_report(&self) -> Vec<ExpertInfo> {
+        let clustering = self.gmx.predict(&self.training_data.0);
+        self.experts
+            .iter()
+            .enumerate()
+            .map(|(i, expert)| {
+                // kind is the display prefix before the underlying (S)GP description
+                let display = expert.to_string();
+                let kind = display
+                    .split_once("SGP(")
+                    .or_else(|| display.split_once("GP("))
+                    .map_or(display.as_str(), |(kind, _)| kind)
+                    .to_string();
+                ExpertInfo {
+                    cluster_size: clustering.iter().filter(|&&c| c == i).count(),
+                    kind,
+                    theta: expert.theta().to_owned(),
+                    kpls_dim: expert.kpls_dim(),
+                    loo_error: expert.loocv_score(),
+                }
+            })
+            .collect()
+    }
+
+    /// Retrain the experts with the given `recombination` reusing the gaussian mixture
+    /// of this model, hence without retraining the clustering.
+    ///
+    /// Returns an error with `Recombination::Smooth(None)` as the heaviside factor optimization
+    /// requires the clustering to be retrained, see [GpMixtureValidParams::train].
+    pub fn fit_experts(&self, recombination: Recombination<f64>) -> Result<GpMixture> {
+        let factor = match recombination {
+            Recombination::Hard => 1.,
+            Recombination::Smooth(Some(factor)) => factor,
+            Recombination::Smooth(None) => {
+                return Err(MoeError::InvalidValueError(
+                    "Heaviside factor should be specified to fit experts only".to_string(),
+                ))
+            }
+        };
+        let clustering = Clustering::new(self.gmx.clone().heaviside_factor(factor), recombination);
+        GpMixtureParams::from(self.params.clone())
+            .recombination(recombination)
+            .check()?
+            .train_on_clusters(&self.training_data.0, &self.training_data.1, &clustering)
+    }
+
     /// Predict outputs at a set of points `x` specified as (n, nx) matrix.
     /// Gaussian Mixture is used to get the probability of the point to belongs to one cluster
     /// or another (ie responsabilities).     
@@ -598,22 +949,32 @@ impl GpMixture {
     /// Predict variances at a set of points `x` specified as (n, nx) matrix.
     /// Gaussian Mixture is used to get the probability of the point to belongs to one cluster
     /// or another (ie responsabilities).
-    /// The smooth recombination of each cluster expert responsabilty is used to get the result.
+    /// Variances are recombined using the law of total variance: with `w_k` the responsabilities,
+    /// `m_k` and `v_k` the mean and variance predictions of the kth expert, the variance is
+    /// `sum_k w_k v_k + sum_k w_k (m_k - M)^2` with `M = sum_k w_k m_k`, accounting for both
+    /// experts variances and the spread between experts predictions.
     pub fn predict_var_smooth(
         &self,
         x: &ArrayBase<impl Data<Elem = f64>, Ix2>,
     ) -> Result<Array2<f64>> {
         let probas = self.gmx.predict_probas(x);
-        let preds: Array2<f64> = self
+        let valvars = self
             .experts
             .iter()
-            .enumerate()
-            .map(|(i, gp)| {
-                let p = probas.column(i).to_owned().insert_axis(Axis(1));
-                gp.predict_var(&x.view()).unwrap() * &p * &p
-            })
-            .fold(Array2::zeros((x.nrows(), 1)), |acc, pred| acc + pred);
-        Ok(preds)
+            .map(|gp| gp.predict_valvar(&x.view()))
+            .collect::<Result<Vec<_>>>()?;
+        let mut mean = Array1::<f64>::zeros(x.nrows());
+        for (i, (pred, _)) in valvars.iter().enumerate() {
+            mean += &(pred * &probas.column(i));
+        }
+        // Centered form avoids the cancellation of sum_k w_k (v_k + m_k^2) - M^2
+        // when variances are small compared to squared predictions
+        let mut variances = Array1::<f64>::zeros(x.nrows());
+        for (i, (pred, var)) in valvars.iter().enumerate() {
+            let spread = (pred - &mean).mapv(|v| v * v);
+            variances += &((&var.column(0) + &spread) * probas.column(i));
+        }
+        Ok(variances.insert_axis(Axis(1)))
     }
 
     /// Predict derivatives of the output at a set of points `x` specified as (n, nx) matrix.
@@ -683,32 +1044,34 @@ impl GpMixture {
             .and(probas_drv.outer_iter())
             .for_each(|mut y, xi, p, pprime| {
                 let xii = xi.insert_axis(Axis(0));
-                let preds: Array1<f64> = self
-                    .experts
-                    .iter()
-                    .map(|gp| gp.predict_var(&xii).unwrap()[[0, 0]])
-                    .collect();
-                let drvs: Vec<Array1<f64>> = self
+                // With M = sum_k p_k m_k, variance V = sum_k p_k (v_k + (m_k - M)^2) then
+                // dV = sum_k [p'_k (v_k + (m_k - M)^2) + p_k (v'_k + 2 (m_k - M) (m'_k - dM))]
+                // where dM = sum_k p'_k m_k + p_k m'_k
+                let preds: Vec<_> = self
                     .experts
                     .iter()
-                    .map(|gp| gp.predict_var_gradients(&xii).unwrap().row(0).to_owned())
+                    .map(|gp| {
+                        (
+                            gp.predict(&xii).unwrap()[0],
+                            gp.predict_var(&xii).unwrap()[[0, 0]],
+                            gp.predict_gradients(&xii).unwrap().row(0).to_owned(),
+                            gp.predict_var_gradients(&xii).unwrap().row(0).to_owned(),
+                        )
+                    })
                     .collect();
-
-                let preds = preds.insert_axis(Axis(1));
-                let mut preds_drv = Array2::zeros((self.experts.len(), xi.len()));
-                Zip::indexed(preds_drv.rows_mut()).for_each(|i, mut jc| jc.assign(&drvs[i]));
-
-                let mut term1 = Array2::zeros((self.experts.len(), xi.len()));
-                Zip::from(term1.rows_mut())
-                    .and(&p)
-                    .and(preds_drv.rows())
-                    .for_each(|mut t, p, der| t.assign(&(der.to_owned().mapv(|v| v * p * p))));
-                let term1 = term1.sum_axis(Axis(0));
-
-                let term2 = (p.to_owned() * pprime * preds).mapv(|v| 2. * v);
-                let term2 = term2.sum_axis(Axis(0));
-
-                y.assign(&(term1 + term2));
+                let mut mean = 0.;
+                let mut mean_drv = Array1::<f64>::zeros(xi.len());
+                for (k, (m, _, dm, _)) in preds.iter().enumerate() {
+                    mean += p[k] * m;
+                    mean_drv += &(&pprime.row(k) * *m + dm * p[k]);
+                }
+                let mut var_drv = Array1::<f64>::zeros(xi.len());
+                for (k, (m, v, dm, dv)) in preds.iter().enumerate() {
+                    let spread = m - mean;
+                    var_drv += &(&pprime.row(k) * (v + spread * spread)
+                        + (dv + &((dm - &mean_drv) * (2. * spread))) * p[k]);
+                }
+                y.assign(&var_drv);
             });
 
         Ok(drv)
@@ -866,8 +1229,10 @@ impl GpMixture {
     pub fn load(path: &str, format: GpFileFormat) -> Result<Box<GpMixture>> {
         let data = fs::read(path)?;
         let moe = match format {
-            GpFileFormat::Json => serde_json::from_slice(&data).unwrap(),
-            GpFileFormat::Binary => bincode::deserialize(&data).unwrap(),
+            GpFileFormat::Json => serde_json::from_slice(&data).map_err(MoeError::LoadJsonError)?,
+            GpFileFormat::Binary => {
+                bincode::deserialize(&data).map_err(MoeError::LoadBinaryError)?
+            }
         };
         Ok(Box::new(moe))
     }
@@ -929,13 +1294,13 @@ impl<D: Data<Elem = f64>> PredictInplace<ArrayBase<D, Ix2>, Array2<f64>>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use approx::assert_abs_diff_eq;
+    use approx::{assert_abs_diff_eq, assert_abs_diff_ne};
     use argmin_testfunctions::rosenbrock;
     use egobox_doe::{Lhs, SamplingMethod};
     use ndarray::{array, Array, Array2, Zip};
     use ndarray_npy::write_npy;
     use ndarray_rand::rand::SeedableRng;
-    use ndarray_rand::rand_distr::Uniform;
+    use ndarray_rand::rand_distr::{Normal, Uniform};
     use ndarray_rand::RandomExt;
     use rand_xoshiro::Xoshiro256Plus;
 
@@ -1002,6 +1367,66 @@ mod tests {
         println!("LOOCV = {}", moe.loocv_score());
     }
 
+    #[test]
+    fn test_moe_experts_reproducibility() {
+        let rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = Lhs::new(&array![[0., 1.]]).with_rng(rng.clone()).sample(50);
+        let yt = f_test_1d(&xt);
+        let ds = Dataset::new(xt, yt);
+        // experts are trained independently (in parallel with `parallel` feature)
+        // but they should be identical from a training to another
+        let fit = || {
+            GpMixture::params()
+                .n_clusters(3)
+                .correlation_spec(CorrelationSpec::SQUAREDEXPONENTIAL | CorrelationSpec::MATERN52)
+                .recombination(Recombination::Hard)
+                .with_rng(rng.clone())
+                .fit(&ds)
+                .expect("MOE fitted")
+        };
+        let (moe1, moe2) = (fit(), fit());
+        assert_eq!(moe1.experts().len(), moe2.experts().len());
+        for (e1, e2) in moe1.experts().iter().zip(moe2.experts()) {
+            assert_eq!(e1.to_string(), e2.to_string());
+            assert_abs_diff_eq!(e1.theta(), e2.theta());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_moe_parallel_experts() {
+        let rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = Lhs::new(&array![[0., 1.]]).with_rng(rng.clone()).sample(60);
+        let yt = f_test_1d(&xt);
+        let data = concatenate![Axis(1), xt, yt.insert_axis(Axis(1))];
+        // three clusters of contiguous points
+        let mut order: Vec<_> = (0..data.nrows()).collect();
+        order.sort_by(|&i, &j| data[[i, 0]].total_cmp(&data[[j, 0]]));
+        let clusters: Vec<_> = data
+            .select(Axis(0), &order)
+            .axis_chunks_iter(Axis(0), 20)
+            .map(|c| c.to_owned())
+            .collect();
+
+        let params = GpMixture::params()
+            .correlation_spec(CorrelationSpec::SQUAREDEXPONENTIAL | CorrelationSpec::MATERN52)
+            .with_rng(rng)
+            .check()
+            .unwrap();
+        let sequential = params.train_experts(1, &clusters, false).unwrap();
+        let parallel = params.train_experts(1, &clusters, true).unwrap();
+        assert_eq!(sequential.len(), 3);
+        let x = Array1::linspace(0., 1., 50).insert_axis(Axis(1));
+        for (e1, e2) in sequential.iter().zip(&parallel) {
+            assert_eq!(e1.to_string(), e2.to_string());
+            assert_eq!(e1.theta(), e2.theta());
+            assert_eq!(
+                e1.predict(&x.view()).unwrap(),
+                e2.predict(&x.view()).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_moe_smooth() {
         let test_dir = "target/tests";
@@ -1051,6 +1476,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_moe_fit_experts() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((60, 1), Uniform::new(0., 1.), &mut rng);
+        let yt = f_test_1d(&xt);
+        let params = GpMixture::params()
+            .n_clusters(3)
+            .recombination(Recombination::Smooth(Some(0.5)))
+            .with_rng(rng.clone());
+        let moe = params
+            .clone()
+            .fit(&Dataset::new(xt.clone(), yt.clone()))
+            .expect("MOE fitted");
+
+        // Clustering only is reproducible
+        let clustering = params
+            .check_ref()
+            .unwrap()
+            .fit_clustering(&xt, &yt)
+            .expect("Clustering fitted");
+        assert_abs_diff_eq!(clustering.gmx().means(), moe.gmx().means());
+
+        // Refit experts with another heaviside factor reusing the clustering
+        let moe2 = moe
+            .fit_experts(Recombination::Smooth(Some(0.1)))
+            .expect("Experts fitted");
+        assert_eq!(moe2.gmx().means(), moe.gmx().means());
+        assert_eq!(moe2.recombination(), Recombination::Smooth(Some(0.1)));
+        let x = array![[0.37]];
+        for (e1, e2) in moe.experts().iter().zip(moe2.experts()) {
+            assert_abs_diff_eq!(
+                e1.predict(&x.view()).unwrap(),
+                e2.predict(&x.view()).unwrap()
+            );
+        }
+        assert_abs_diff_ne!(moe.predict(&x).unwrap(), moe2.predict(&x).unwrap());
+
+        assert!(moe.fit_experts(Recombination::Smooth(None)).is_err());
+    }
+
+    #[test]
+    fn test_moe_combined_corr_experts() {
+        let rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Lhs::new(&array![[0., 1.]]).with_rng(rng.clone()).sample(50);
+        let yt = f_test_1d(&xt);
+        let moe = GpMixture::params()
+            .n_clusters(2)
+            .correlation_spec(
+                CorrelationSpec::MATERN52PLUSSQUAREDEXPONENTIAL
+                    | CorrelationSpec::SQUAREDEXPONENTIALTIMESABSOLUTEEXPONENTIAL,
+            )
+            .recombination(Recombination::Hard)
+            .with_rng(rng)
+            .fit(&Dataset::new(xt, yt))
+            .expect("MOE fitted");
+        for expert in moe.experts() {
+            let name = expert.to_string();
+            assert!(
+                name.starts_with("Constant_Matern52PlusSquaredExponential")
+                    || name.starts_with("Constant_SquaredExponentialTimesAbsoluteExponential")
+            );
+        }
+    }
+
+    #[test]
+    fn test_moe_recombination_at_boundary() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((60, 1), Uniform::new(0., 1.), &mut rng);
+        let yt = f_test_1d(&xt);
+        let moe = GpMixture::params()
+            .n_clusters(3)
+            .recombination(Recombination::Smooth(Some(1.)))
+            .regression_spec(RegressionSpec::CONSTANT)
+            .correlation_spec(CorrelationSpec::SQUAREDEXPONENTIAL)
+            .with_rng(rng)
+            .fit(&Dataset::new(xt, yt))
+            .expect("MOE fitted");
+
+        let x = Array1::linspace(0., 1., 200).insert_axis(Axis(1));
+        let probas = moe.gmx().predict_probas(&x);
+        let labels = moe.gmx().predict(&x);
+        let expert_preds: Vec<Array1<f64>> = moe
+            .experts()
+            .iter()
+            .map(|e| e.predict(&x.view()).unwrap())
+            .collect();
+        let smooth = moe.predict_smooth(&x).unwrap();
+        let hard = moe.predict_hard(&x).unwrap();
+
+        let mut n_blended = 0;
+        for i in 0..x.nrows() {
+            // Hard: prediction of the expert with highest responsability
+            assert_abs_diff_eq!(hard[i], expert_preds[labels[i]][i], epsilon = 1e-12);
+            // Smooth: convex combination of experts predictions
+            assert_abs_diff_eq!(probas.row(i).sum(), 1., epsilon = 1e-12);
+            let blend: f64 = (0..moe.n_clusters())
+                .map(|k| probas[[i, k]] * expert_preds[k][i])
+                .sum();
+            assert_abs_diff_eq!(smooth[i], blend, epsilon = 1e-12);
+            if probas.row(i).iter().filter(|&&p| p > 0.1).count() > 1 {
+                n_blended += 1;
+            }
+        }
+        // Some points near cluster boundaries are actually blended
+        assert!(n_blended > 0);
+        // Hard recombination switches of expert across boundaries
+        assert!(labels.windows(2).into_iter().any(|w| w[0] != w[1]));
+    }
+
     #[test]
     fn test_moe_auto() {
         let mut rng = Xoshiro256Plus::seed_from_u64(42);
@@ -1074,6 +1608,351 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_moe_auto_bic() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        // two well separated groups of points with different behaviours
+        let x1 = Array2::random_using((30, 1), Uniform::new(0., 0.3), &mut rng);
+        let x2 = Array2::random_using((30, 1), Uniform::new(0.7, 1.), &mut rng);
+        let xt = concatenate![Axis(0), x1, x2];
+        let yt = xt
+            .mapv(|v| if v < 0.5 { v } else { 10. - 5. * v })
+            .remove_axis(Axis(1));
+        let moe = GpMixture::params()
+            .n_clusters(0)
+            .cluster_selection(ClusterSelection::Bic)
+            .recombination(Recombination::Hard)
+            .with_rng(rng)
+            .fit(&Dataset::new(xt, yt))
+            .expect("MOE fitted");
+        assert_eq!(moe.n_clusters(), 2);
+    }
+
+    #[test]
+    fn test_moe_min_cluster_size() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let x1 = Array2::random_using((30, 1), Uniform::new(0., 0.3), &mut rng);
+        let x2 = Array2::random_using((30, 1), Uniform::new(0.7, 1.), &mut rng);
+        // tiny group of points far away
+        let x3 = array![[3.], [3.1]];
+        let xt = concatenate![Axis(0), x1, x2, x3];
+        let yt = xt
+            .mapv(|v| if v < 0.5 { v } else { 10. - 5. * v })
+            .remove_axis(Axis(1));
+        let ds = Dataset::new(xt, yt);
+        let params = GpMixture::params()
+            .n_clusters(3)
+            .recombination(Recombination::Hard)
+            .with_rng(rng);
+
+        assert!(params.clone().fit(&ds).is_err());
+
+        let moe = params.min_cluster_size(5).fit(&ds).expect("MOE fitted");
+        assert_eq!(moe.n_clusters(), 2);
+        assert_eq!(moe.experts().len(), 2);
+    }
+
+    #[test]
+    fn test_moe_predict_par() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = Array2::random_using((50, 1), Uniform::new(0., 1.), &mut rng);
+        let yt = f_test_1d(&xt);
+        let moe = GpMixture::params()
+            .n_clusters(3)
+            .with_rng(rng)
+            .fit(&Dataset::new(xt, yt))
+            .expect("MOE fitted");
+        let x = Array::linspace(0., 1., 2 * PREDICT_CHUNK_SIZE + 7).insert_axis(Axis(1));
+        assert_abs_diff_eq!(
+            moe.predict_par(&x.view()).unwrap(),
+            moe.predict(&x).unwrap(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_moe_clustering_space() {
+        // two output regimes with a small jump at x = 0.25 over uniformly spread inputs
+        let xt = Array1::linspace(0., 1., 100).insert_axis(Axis(1));
+        let regime = xt.column(0).mapv(|x| (x > 0.25) as usize);
+        let yt = regime.mapv(|r| 0.01 * r as f64);
+        let matching = |space| {
+            let clustering = GpMixture::params()
+                .n_clusters(2)
+                .recombination(Recombination::Hard)
+                .clustering_space(space)
+                .with_rng(Xoshiro256Plus::seed_from_u64(42))
+                .check_unwrap()
+                .fit_clustering(&xt, &yt)
+                .expect("Clustering fitted");
+            let clusters = clustering.gmx().predict(&xt);
+            let same = Zip::from(&clusters)
+                .and(&regime)
+                .fold(0, |acc, c, r| acc + (c == r) as usize);
+            // up to clusters permutation
+            same.max(xt.nrows() - same) as f64 / xt.nrows() as f64
+        };
+        assert!(matching(ClusteringSpace::StandardizedJoint) > 0.95);
+        assert!(matching(ClusteringSpace::Input) < 0.9);
+    }
+
+    #[test]
+    fn test_moe_gmm_max_iter_tol() {
+        // two overlapping blobs of inputs
+        let mut rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = ndarray::concatenate![
+            Axis(0),
+            Array2::random_using((60, 2), Normal::new(0., 1.).unwrap(), &mut rng),
+            Array2::random_using((40, 2), Normal::new(1.5, 0.7).unwrap(), &mut rng)
+        ];
+        let yt = xt.column(0).to_owned();
+        let fit = |max_iter, tol| {
+            GpMixture::params()
+                .n_clusters(3)
+                .recombination(Recombination::Hard)
+                .clustering_space(ClusteringSpace::Input)
+                .gmm_max_iter(max_iter)
+                .gmm_tol(tol)
+                .with_rng(Xoshiro256Plus::seed_from_u64(42))
+                .check_unwrap()
+                .fit_clustering(&xt, &yt)
+        };
+        let loglikelihood =
+            |clustering: Clustering| clustering.gmx().score_samples(&xt).mean().unwrap();
+
+        let converged = loglikelihood(fit(1000, 1e-10).expect("Clustering fitted"));
+        let early = loglikelihood(fit(1000, 10.).expect("Clustering fitted"));
+        println!("log-likelihood converged = {converged}, early stopped = {early}");
+        assert!(early < converged);
+        // EM cannot converge in one iteration
+        assert!(fit(1, 1e-10).is_err());
+    }
+
+    #[test]
+    fn test_moe_gmm_n_init() {
+        // five well separated blobs of inputs
+        let mut rng = Xoshiro256Plus::seed_from_u64(0);
+        let centers = array![[0., 0.], [6., 0.], [0., 6.], [6., 6.], [3., 12.]];
+        let blobs = centers
+            .outer_iter()
+            .map(|c| Array2::random_using((30, 2), Normal::new(0., 0.5).unwrap(), &mut rng) + c)
+            .collect::<Vec<_>>();
+        let views = blobs.iter().map(|b| b.view()).collect::<Vec<_>>();
+        let xt = ndarray::concatenate(Axis(0), &views).unwrap();
+        let yt = xt.column(0).to_owned();
+        // random initialization makes EM prone to get stuck in bad local optima
+        let loglikelihood = |n_init, seed| {
+            let clustering = GpMixture::params()
+                .n_clusters(5)
+                .recombination(Recombination::Hard)
+                .clustering_space(ClusteringSpace::Input)
+                .gmm_init(GmmInit::Random)
+                .gmm_n_init(n_init)
+                .with_rng(Xoshiro256Plus::seed_from_u64(seed))
+                .check_unwrap()
+                .fit_clustering(&xt, &yt)
+                .expect("Clustering fitted");
+            clustering.gmx().score_samples(&xt).mean().unwrap()
+        };
+
+        let single = (0..10)
+            .map(|seed| loglikelihood(1, seed))
+            .collect::<Vec<_>>();
+        let multi = (0..10)
+            .map(|seed| loglikelihood(10, seed))
+            .collect::<Vec<_>>();
+        println!("log-likelihood n_init=1: {single:?}");
+        println!("log-likelihood n_init=10: {multi:?}");
+        // the first initialization being the single one, multistart is never worse...
+        assert!(single.iter().zip(&multi).all(|(s, m)| *m >= s - 1e-6));
+        // ...and escapes the bad local optimum reached with a single initialization
+        let best_single = single.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        assert!(multi.iter().all(|m| *m > best_single + 1.));
+
+        assert!(GpMixture::params().gmm_n_init(0).check().is_err());
+    }
+
+    #[test]
+    fn test_moe_gmm_empty_cluster_threshold() {
+        // two well separated blobs of 60 and 40 inputs
+        let mut rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = ndarray::concatenate![
+            Axis(0),
+            Array2::random_using((60, 2), Normal::new(0., 0.5).unwrap(), &mut rng),
+            Array2::random_using((40, 2), Normal::new(6., 0.5).unwrap(), &mut rng)
+        ];
+        let yt = xt.column(0).to_owned();
+        let fit = |threshold| {
+            GpMixture::params()
+                .n_clusters(2)
+                .recombination(Recombination::Hard)
+                .clustering_space(ClusteringSpace::Input)
+                .gmm_empty_cluster_threshold(threshold)
+                .with_rng(Xoshiro256Plus::seed_from_u64(42))
+                .check_unwrap()
+                .fit_clustering(&xt, &yt)
+        };
+
+        assert!(fit(crate::default_gmm_empty_cluster_threshold()).is_ok());
+        assert!(matches!(fit(50.), Err(MoeError::EmptyCluster(_))));
+
+        // three groups of nearly duplicated inputs: the gaussian mixture fit with 6 clusters
+        // ends up with an empty cluster unless a looser threshold is used
+        let mut rng = Xoshiro256Plus::seed_from_u64(56);
+        let xt = Array::linspace(0., 1., 24).mapv(|v: f64| (3. * v).floor());
+        let xt = xt.insert_axis(Axis(1));
+        let xt =
+            &xt + &Array2::random_using(xt.raw_dim(), Normal::new(0., 1e-9).unwrap(), &mut rng);
+        let yt = xt.column(0).to_owned();
+        let fit = |threshold| {
+            GpMixture::params()
+                .n_clusters(6)
+                .recombination(Recombination::Hard)
+                .clustering_space(ClusteringSpace::Input)
+                .gmm_init(GmmInit::Random)
+                .gmm_empty_cluster_threshold(threshold)
+                .with_rng(Xoshiro256Plus::seed_from_u64(56))
+                .check_unwrap()
+                .fit_clustering(&xt, &yt)
+        };
+        assert!(matches!(
+            fit(crate::default_gmm_empty_cluster_threshold()),
+            Err(MoeError::LinfaClusteringrror(GmmError::EmptyCluster(_)))
+        ));
+        let clustering = fit(1e-300).expect("Clustering with a looser threshold");
+        assert_eq!(clustering.gmx().n_clusters(), 6);
+        assert!(GpMixture::params()
+            .gmm_empty_cluster_threshold(-1.)
+            .check()
+            .is_err());
+    }
+
+    #[test]
+    fn test_moe_seeded_gmm_init() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = Array2::random_using((50, 1), Uniform::new(0., 1.), &mut rng);
+        let yt = f_test_1d(&xt);
+        let ds = Dataset::new(xt, yt);
+        for init in [GmmInit::KMeans, GmmInit::Random] {
+            let fit = || {
+                GpMixture::params()
+                    .n_clusters(3)
+                    .recombination(Recombination::Hard)
+                    .gmm_init(init)
+                    .with_rng(Xoshiro256Plus::seed_from_u64(42))
+                    .fit(&ds)
+                    .expect("MOE fitted")
+            };
+            let (moe1, moe2) = (fit(), fit());
+            assert_eq!(moe1.gmx().means(), moe2.gmx().means());
+            assert_eq!(moe1.gmx().weights(), moe2.gmx().weights());
+        }
+    }
+
+    #[test]
+    fn test_moe_cluster_in_pls_space() {
+        // 10D inputs, function structure lies in the (x0, x1) subspace
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((100, 10), Uniform::new(0., 1.), &mut rng);
+        let regions = xt.map_axis(Axis(1), |x| x[0] + x[1] >= 1.);
+        let yt = xt.map_axis(Axis(1), |x| {
+            let s = x[0] + x[1];
+            if s < 1. {
+                s
+            } else {
+                5. * s - 4.
+            }
+        });
+        let ds = Dataset::new(xt.clone(), yt);
+        let params = GpMixture::params()
+            .n_clusters(2)
+            .recombination(Recombination::Hard)
+            .with_rng(rng.clone());
+
+        // cluster labels are arbitrary, accuracy is taken up to a permutation
+        let accuracy = |moe: &GpMixture| {
+            let clusters = moe.gmx().predict(&xt);
+            let matches = Zip::from(&clusters)
+                .and(&regions)
+                .fold(0, |acc, &c, &r| acc + ((c == 1) == r) as usize);
+            matches.max(xt.nrows() - matches) as f64 / xt.nrows() as f64
+        };
+
+        let moe = params.clone().fit(&ds).expect("MOE fitted");
+        let moe_pls = params
+            .cluster_in_pls_space(Some(2))
+            .fit(&ds)
+            .expect("MOE fitted");
+        let (acc, acc_pls) = (accuracy(&moe), accuracy(&moe_pls));
+        println!("Clustering accuracy: {acc} without PLS, {acc_pls} with PLS");
+        assert!(acc_pls >= acc);
+        assert!(acc_pls > 0.9);
+
+        // experts still work in the original input space
+        let x = Array2::random_using((10, 10), Uniform::new(0., 1.), &mut rng);
+        assert_eq!(moe_pls.predict(&x).unwrap().len(), 10);
+        assert_eq!(moe_pls.predict_gradients(&x).unwrap().dim(), (10, 10));
+    }
+
+    #[test]
+    fn test_moe_experts_kernel_selection() {
+        // smooth on the left, kinked on the right
+        let f = |x: &Array2<f64>| {
+            x.mapv(|v| {
+                if v < 0.5 {
+                    (6. * v).sin()
+                } else {
+                    10. + 10. * (v - 0.75).abs()
+                }
+            })
+            .remove_axis(Axis(1))
+        };
+        let rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Lhs::new(&array![[0., 1.]]).with_rng(rng.clone()).sample(40);
+        let ds = Dataset::new(xt.clone(), f(&xt));
+        let fit = |spec: CorrelationSpec| {
+            GpMixture::params()
+                .n_clusters(2)
+                .recombination(Recombination::Hard)
+                .correlation_spec(spec)
+                .with_rng(rng.clone())
+                .fit(&ds)
+                .expect("MOE fitted")
+        };
+        // error away from clusters boundary
+        let xv = concatenate![
+            Axis(0),
+            Array1::linspace(0., 0.4, 41),
+            Array1::linspace(0.6, 1., 41)
+        ]
+        .insert_axis(Axis(1));
+        let yv = f(&xv);
+        let error = |moe: &GpMixture| {
+            let pred = moe.predict(&xv).unwrap();
+            (pred - &yv).mapv(|v| v * v).mean().unwrap().sqrt()
+        };
+
+        // Each expert kernel is selected independently
+        let moe = fit(CorrelationSpec::ALL);
+        let kernels: Vec<String> = moe
+            .experts()
+            .iter()
+            .map(|e| e.to_string().split("GP(").next().unwrap().to_string())
+            .collect();
+        assert_ne!(kernels[0], kernels[1]);
+
+        // Better than mixtures using a single kernel for every experts
+        // or almost as good as the best of them
+        let err = error(&moe);
+        for spec in [
+            CorrelationSpec::ABSOLUTEEXPONENTIAL,
+            CorrelationSpec::MATERN32,
+        ] {
+            assert!(err < error(&fit(spec)));
+        }
+        assert!(err < 1.1 * error(&fit(CorrelationSpec::SQUAREDEXPONENTIAL)));
+    }
+
     #[test]
     fn test_moe_variances_smooth() {
         let mut rng = Xoshiro256Plus::seed_from_u64(42);
@@ -1088,9 +1967,73 @@ mod tests {
             .fit(&Dataset::new(xt, yt))
             .expect("MOE fitted");
         // Smoke test: prediction is pretty good hence variance is very low
+        // where one expert is responsible (no spread between experts predictions)
+        let x = Array1::linspace(0., 1., 20).insert_axis(Axis(1));
+        let variances = moe.predict_var(&x).expect("MOE variances prediction");
+        let probas = moe.gmx().predict_probas(&x);
+        let mut n_checked = 0;
+        for (var, p) in variances.column(0).iter().zip(probas.rows()) {
+            if *p.max().unwrap() > 1. - 1e-12 {
+                assert_abs_diff_eq!(*var, 0., epsilon = 1e-10);
+                n_checked += 1;
+            }
+        }
+        assert!(n_checked > 0);
+    }
+
+    #[test]
+    fn test_moe_variances_smooth_single_expert() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((10, 1), Uniform::new(0., 1.), &mut rng);
+        // large offset: squared predictions are far above the variances
+        let yt = xt.column(0).mapv(|v| 1e4 + f64::sin(6. * v));
+        let moe = GpMixture::params()
+            .n_clusters(1)
+            .recombination(Recombination::Smooth(None))
+            .regression_spec(RegressionSpec::CONSTANT)
+            .correlation_spec(CorrelationSpec::SQUAREDEXPONENTIAL)
+            .with_rng(rng)
+            .fit(&Dataset::new(xt, yt))
+            .expect("MOE fitted");
         let x = Array1::linspace(0., 1., 20).insert_axis(Axis(1));
         let variances = moe.predict_var(&x).expect("MOE variances prediction");
-        assert_abs_diff_eq!(*variances.max().unwrap(), 0., epsilon = 1e-10);
+        let expert_variances = moe.experts()[0].predict_var(&x.view()).unwrap();
+        assert_abs_diff_eq!(variances, expert_variances, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_moe_variances_smooth_at_boundary() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Array2::random_using((60, 1), Uniform::new(0., 1.), &mut rng);
+        let yt = f_test_1d(&xt);
+        let moe = GpMixture::params()
+            .n_clusters(3)
+            .recombination(Recombination::Smooth(Some(1.)))
+            .regression_spec(RegressionSpec::CONSTANT)
+            .correlation_spec(CorrelationSpec::SQUAREDEXPONENTIAL)
+            .with_rng(rng)
+            .fit(&Dataset::new(xt, yt))
+            .expect("MOE fitted");
+
+        let x = Array1::linspace(0., 1., 200).insert_axis(Axis(1));
+        let probas = moe.gmx().predict_probas(&x);
+        let variances = moe.predict_var(&x).unwrap();
+        // Point where the two most responsible experts are the most balanced
+        let i = (0..x.nrows())
+            .max_by(|&a, &b| {
+                let blend = |i: usize| {
+                    let mut p = probas.row(i).to_vec();
+                    p.sort_by(|u, v| v.partial_cmp(u).unwrap());
+                    p[1]
+                };
+                blend(a).partial_cmp(&blend(b)).unwrap()
+            })
+            .unwrap();
+        let xi = x.slice(s![i..i + 1, ..]);
+        for expert in moe.experts() {
+            let expert_var = expert.predict_var(&xi).unwrap()[[0, 0]];
+            assert!(variances[[i, 0]] > expert_var);
+        }
     }
 
     fn xsinx(x: &[f64]) -> f64 {
@@ -1279,6 +2222,88 @@ mod tests {
         test_variance_derivatives(rosenb);
     }
 
+    #[test]
+    fn test_moe_expert_report() {
+        let rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = Lhs::new(&array![[0., 1.]]).with_rng(rng.clone()).sample(50);
+        let yt = f_test_1d(&xt);
+
+        let moe = GpMixture::params()
+            .n_clusters(3)
+            .regression_spec(RegressionSpec::CONSTANT)
+            .correlation_spec(CorrelationSpec::SQUAREDEXPONENTIAL)
+            .recombination(Recombination::Hard)
+            .with_rng(rng)
+            .fit(&Dataset::new(xt, yt))
+            .expect("MOE fitted");
+        let report = moe.expert_report();
+        assert_eq!(report.len(), moe.n_clusters());
+        assert_eq!(
+            report.iter().map(|info| info.cluster_size).sum::<usize>(),
+            50
+        );
+        for (info, expert) in report.iter().zip(moe.experts()) {
+            assert_eq!(info.kind, "Constant_SquaredExponential");
+            assert_eq!(&info.theta, expert.theta());
+            assert_eq!(info.kpls_dim, None);
+            assert!(info.cluster_size >= 3);
+            assert!(f64::is_finite(info.loo_error));
+        }
+    }
+
+    #[test]
+    fn test_moe_from_parts() {
+        let xt = Array1::linspace(0., 1., 40).insert_axis(Axis(1));
+        let yt = f_test_1d(&xt);
+        let gmx = GaussianMixture::new(
+            array![0.5, 0.5],
+            array![[0.25], [0.75]],
+            array![[[0.02]], [[0.02]]],
+        )
+        .expect("Gaussian mixture creation failed");
+        let clusters = gmx.predict(&xt);
+        let experts = (0..2)
+            .map(|k| {
+                let indices = (0..xt.nrows())
+                    .filter(|&i| clusters[i] == k)
+                    .collect::<Vec<_>>();
+                GpConstantSquaredExponentialSurrogateParams::new(egobox_gp::GpParams::new(
+                    ConstantMean::default(),
+                    SquaredExponentialCorr::default(),
+                ))
+                .train(
+                    &xt.select(Axis(0), &indices).view(),
+                    &yt.select(Axis(0), &indices).insert_axis(Axis(1)).view(),
+                )
+                .expect("Expert fitted")
+            })
+            .collect::<Vec<_>>();
+
+        let moe = GpMixture::from_parts(gmx.clone(), experts, Recombination::Hard)
+            .expect("MOE assembled");
+        assert_eq!(moe.n_clusters(), 2);
+        assert_eq!(GpSurrogate::training_data(&moe).0.nrows(), 40);
+        assert_abs_diff_eq!(moe.predict(&xt).unwrap(), yt, epsilon = 1e-6);
+        let x = Array1::linspace(0., 1., 13).insert_axis(Axis(1));
+        let clusters = gmx.predict(&x);
+        let preds = moe.predict(&x).unwrap();
+        for (i, (xi, k)) in x.rows().into_iter().zip(clusters.iter()).enumerate() {
+            let expected = moe.experts()[*k].predict(&xi.insert_axis(Axis(0))).unwrap();
+            assert_abs_diff_eq!(preds[i], expected[0], epsilon = 1e-12);
+        }
+
+        let expert = GpConstantSquaredExponentialSurrogateParams::new(egobox_gp::GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ))
+        .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+        .expect("Expert fitted");
+        assert!(matches!(
+            GpMixture::from_parts(gmx, vec![expert], Recombination::Hard),
+            Err(MoeError::InvalidValueError(_))
+        ));
+    }
+
     #[test]
     fn test_moe_display() {
         let rng = Xoshiro256Plus::seed_from_u64(0);
@@ -1,4 +1,6 @@
 #![allow(dead_code)]
+use crate::errors::MoeError;
+use crate::gaussian_mixture::GaussianMixture;
 use crate::parameters::GpMixtureParams;
 use crate::types::*;
 use log::debug; // , info};
@@ -55,6 +57,45 @@ pub(crate) fn sort_by_cluster<F: Float>(
     res
 }
 
+/// Find the number of clusters in [1, `max_nb_clusters`] giving the smallest BIC
+/// of the gaussian mixture fitted on the (x, y) training data.
+/// Numbers of clusters for which the gaussian mixture cannot be fitted
+/// (e.g. because of an empty cluster) are skipped.
+pub fn find_best_number_of_clusters_bic<R: Rng + Clone>(
+    x: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    y: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+    max_nb_clusters: usize,
+    rng: R,
+) -> usize {
+    let data = concatenate(Axis(1), &[x.view(), y.view().insert_axis(Axis(1))]).unwrap();
+    let dataset = Dataset::from(data.to_owned());
+    let mut best = (1, f64::INFINITY);
+    for n_clusters in 1..=max_nb_clusters.max(1) {
+        let gmm = GaussianMixtureModel::params(n_clusters)
+            .n_runs(20)
+            .with_rng(rng.clone())
+            .fit(&dataset);
+        let bic = gmm.map_err(MoeError::from).and_then(|gmm| {
+            GaussianMixture::new(
+                gmm.weights().to_owned(),
+                gmm.means().to_owned(),
+                gmm.covariances().to_owned(),
+            )
+            .map(|gmx| gmx.bic(&data))
+        });
+        match bic {
+            Ok(bic) => {
+                debug!("BIC with {} clusters = {}", n_clusters, bic);
+                if bic < best.1 {
+                    best = (n_clusters, bic);
+                }
+            }
+            Err(err) => debug!("Skip {} clusters: {}", n_clusters, err),
+        }
+    }
+    best.0
+}
+
 /// Find the best number of cluster thanks to cross validation
 pub fn find_best_number_of_clusters<R: Rng + Clone>(
     x: &ArrayBase<impl Data<Elem = f64>, Ix2>,
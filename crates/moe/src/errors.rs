@@ -16,7 +16,7 @@ pub enum MoeError {
     #[error("Empty cluster: {0}")]
     EmptyCluster(String),
     /// When Gaussian Process fails
-    #[error("GP error")]
+    #[error("GP error: {0}")]
     GpError(#[from] egobox_gp::GpError),
     /// When best expert search fails
     #[error("Expert error: {0}")]
@@ -27,6 +27,12 @@ pub enum MoeError {
     /// When sampling fails
     #[error("Sample error: {0}")]
     SampleError(String),
+    /// When a design of experiments can not be built
+    #[error("DoE error: {0}")]
+    DoeError(#[from] egobox_doe::DoeError),
+    /// When arrays shapes are not compatible
+    #[error("Shape error: {0}")]
+    ShapeError(#[from] ndarray::ShapeError),
     /// When error during saving
     #[cfg(feature = "persistent")]
     #[error("Save error: {0}")]
@@ -41,6 +47,14 @@ pub enum MoeError {
     /// When error during loading
     #[error("Load error: {0}")]
     LoadError(String),
+    /// When error during loading from json format
+    #[cfg(feature = "persistent")]
+    #[error("Load error: {0}")]
+    LoadJsonError(#[source] serde_json::Error),
+    /// When error during loading from binary format
+    #[cfg(feature = "persistent")]
+    #[error("Load error: {0}")]
+    LoadBinaryError(#[source] bincode::Error),
     /// When error during loading
     #[error("InvalidValue error: {0}")]
     InvalidValueError(String),
@@ -50,4 +64,7 @@ pub enum MoeError {
     /// When a linfa GMM clustering error occurs
     #[error(transparent)]
     LinfaClusteringrror(#[from] linfa_clustering::GmmError),
+    /// When a linfa PLS error occurs
+    #[error(transparent)]
+    PlsError(#[from] linfa_pls::PlsError),
 }
@@ -19,7 +19,9 @@ macro_rules! compute_error {
             stringify!($corr),
             $dataset.nsamples()
         );
-        let params = make_gp_params!($regr, $corr).kpls_dim($self.kpls_dim());
+        let params = make_gp_params!($regr, $corr)
+            .kpls_dim($self.kpls_dim())
+            .categorical_inputs($self.categorical_inputs().to_vec());
         let mut errors = Vec::new();
         let input_dim = $dataset.records().shape()[1];
         let n_fold = std::cmp::min($dataset.nsamples(), 5);
@@ -96,6 +98,30 @@ macro_rules! compute_errors_with_regr {
                 $regr,
                 Matern52
             );
+            compute_errors_with_corr!(
+                $self,
+                $allowed_corr_models,
+                $dataset,
+                $map_error,
+                $regr,
+                Matern52PlusSquaredExponential
+            );
+            compute_errors_with_corr!(
+                $self,
+                $allowed_corr_models,
+                $dataset,
+                $map_error,
+                $regr,
+                SquaredExponentialPlusAbsoluteExponential
+            );
+            compute_errors_with_corr!(
+                $self,
+                $allowed_corr_models,
+                $dataset,
+                $map_error,
+                $regr,
+                SquaredExponentialTimesAbsoluteExponential
+            );
         }
     }};
 }
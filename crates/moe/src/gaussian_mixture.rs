@@ -2,20 +2,36 @@
 // to allow the specification of the heaviside factor used to tune the
 // smoothness of the mixture smooth recombination
 #![allow(dead_code)]
-use crate::Result;
+use crate::{CovarianceRegularizer, CovarianceType, MoeError, Result};
 #[cfg(feature = "blas")]
 use linfa::{dataset::WithLapack, dataset::WithoutLapack};
 use linfa::{traits::*, Float};
 #[cfg(not(feature = "blas"))]
 use linfa_linalg::{cholesky::*, triangular::*};
-use ndarray::{s, Array, Array1, Array2, Array3, ArrayBase, Axis, Data, Ix1, Ix2, Ix3, Zip};
+use ndarray::{
+    concatenate, s, Array, Array1, Array2, Array3, ArrayBase, Axis, CowArray, Data, Ix1, Ix2, Ix3,
+    Zip,
+};
 #[cfg(feature = "blas")]
 use ndarray_linalg::{cholesky::*, triangular::*};
-use ndarray_stats::QuantileExt;
+use ndarray_rand::rand::Rng;
+use ndarray_rand::{rand_distr::StandardNormal, RandomExt};
+use std::sync::OnceLock;
 
 #[cfg(feature = "serializable")]
 use serde::{Deserialize, Serialize};
 
+/// Diagnostics of expectation-maximization iterations (see [GaussianMixture::em_with_report])
+#[derive(Clone, Copy, Debug)]
+pub struct EmReport<F: Float> {
+    /// Final mean log-likelihood of the points, computed at the last E-step
+    pub log_likelihood: F,
+    /// Number of EM iterations run
+    pub n_iter: usize,
+    /// Whether the log-likelihood improvement fell below the tolerance before `max_iter` iterations
+    pub converged: bool,
+}
+
 /// Gaussian mixture is a set of n weigthed multivariate normal distributions of dimension nx
 /// This structure is derived from `linfa::GaussianMixtureModel` clustering method
 /// to handle the resulting multivariate normals and related computations in one go.
@@ -42,6 +58,26 @@ pub struct GaussianMixture<F: Float> {
     heaviside_factor: F,
     /// determinants of the cholesky decomposition matrices of the precision matrices
     log_det: Array1<F>,
+    /// optional affine projection (offset (nx,), matrix (nx, d)) such that
+    /// multivariate normal distributions are defined in the projected space `(x - offset).matrix`
+    #[cfg_attr(feature = "serializable", serde(default))]
+    projection: Option<(Array1<F>, Array2<F>)>,
+    /// covariance structure, tied covariance matrices are repeated for each component
+    #[cfg_attr(feature = "serializable", serde(default))]
+    covariance_type: CovarianceType,
+    /// regularization of covariances estimated by EM
+    #[cfg_attr(feature = "serializable", serde(default))]
+    covariance_regularizer: CovarianceRegularizer,
+    /// sum of responsibilities under which a cluster is declared empty by EM iterations
+    #[cfg_attr(
+        feature = "serializable",
+        serde(default = "crate::default_gmm_empty_cluster_threshold")
+    )]
+    empty_cluster_threshold: F,
+    /// cache of lower cholesky precisions scaled by the heaviside factor,
+    /// computed on first use and reset when the heaviside factor changes
+    #[cfg_attr(feature = "serializable", serde(skip))]
+    scaled_precisions_chol: OnceLock<Array3<F>>,
 }
 
 impl<F: Float> Clone for GaussianMixture<F> {
@@ -54,16 +90,46 @@ impl<F: Float> Clone for GaussianMixture<F> {
             precisions_chol: self.precisions_chol.to_owned(),
             heaviside_factor: self.heaviside_factor,
             log_det: self.log_det.to_owned(),
+            projection: self.projection.clone(),
+            covariance_type: self.covariance_type,
+            covariance_regularizer: self.covariance_regularizer,
+            empty_cluster_threshold: self.empty_cluster_threshold,
+            scaled_precisions_chol: self.scaled_precisions_chol.clone(),
         }
     }
 }
 
+/// Compact summary of the mixture, use `Debug` formatting to get all parameters
+impl<F: Float> std::fmt::Display for GaussianMixture<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let weights = self
+            .weights
+            .iter()
+            .map(|w| format!("{w:.4}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "GaussianMixture(n_clusters={}, n_features={}, weights=[{}], heaviside_factor={})",
+            self.n_clusters(),
+            self.n_features(),
+            weights,
+            self.heaviside_factor
+        )
+    }
+}
+
 impl<F: Float> GaussianMixture<F> {
+    /// Constructor of a mixture of n multivariate normal distributions of dimension nx given
+    /// `weights` as a (n,) vector, `means` as a (n, nx) matrix and `covariances` as a (n, nx, nx) ndarray.
+    ///
+    /// Returns an [MoeError::InvalidValueError] if shapes are not consistent or weights are negative.
     pub fn new(
         weights: Array1<F>,
         means: Array2<F>,
         covariances: Array3<F>,
     ) -> Result<GaussianMixture<F>> {
+        Self::check_parameters(&weights, &means, &covariances)?;
         let precisions_chol = Self::compute_precisions_cholesky(&covariances)?;
         let precisions = Self::compute_precisions(&precisions_chol);
         let log_det = Self::compute_log_det(&precisions_chol, F::one());
@@ -75,9 +141,83 @@ impl<F: Float> GaussianMixture<F> {
             precisions_chol,
             heaviside_factor: F::one(),
             log_det,
+            projection: None,
+            covariance_type: CovarianceType::Full,
+            covariance_regularizer: CovarianceRegularizer::None,
+            empty_cluster_threshold: crate::default_gmm_empty_cluster_threshold(),
+            scaled_precisions_chol: OnceLock::new(),
+        })
+    }
+
+    /// Constructor of a mixture of n multivariate normal distributions of dimension nx given
+    /// `weights` as a (n,) vector, `means` as a (n, nx) matrix and the cholesky factors of the
+    /// precision matrices `precisions_chol` as a (n, nx, nx) ndarray of upper triangular matrices P
+    /// such that precision = P.P^T (i.e. scikit-learn `precisions_cholesky_` convention).
+    ///
+    /// Unlike [GaussianMixture::new], no cholesky decomposition is computed:
+    /// covariances are derived by inverting the triangular factors.
+    pub fn from_precisions(
+        weights: Array1<F>,
+        means: Array2<F>,
+        precisions_chol: Array3<F>,
+    ) -> Result<GaussianMixture<F>> {
+        Self::check_parameters(&weights, &means, &precisions_chol)?;
+        let covariances = Self::compute_covariances(&precisions_chol)?;
+        let precisions = Self::compute_precisions(&precisions_chol);
+        let log_det = Self::compute_log_det(&precisions_chol, F::one());
+        Ok(GaussianMixture {
+            weights,
+            means,
+            covariances,
+            precisions,
+            precisions_chol,
+            heaviside_factor: F::one(),
+            log_det,
+            projection: None,
+            covariance_type: CovarianceType::Full,
+            covariance_regularizer: CovarianceRegularizer::None,
+            empty_cluster_threshold: crate::default_gmm_empty_cluster_threshold(),
+            scaled_precisions_chol: OnceLock::new(),
         })
     }
 
+    /// Check consistency of mixture parameters shapes and weights
+    fn check_parameters(
+        weights: &Array1<F>,
+        means: &Array2<F>,
+        covariances: &Array3<F>,
+    ) -> Result<()> {
+        let n_clusters = means.nrows();
+        let n_features = means.ncols();
+        if weights.len() != n_clusters {
+            return Err(MoeError::InvalidValueError(format!(
+                "weights length {} does not match {} components",
+                weights.len(),
+                n_clusters
+            )));
+        }
+        if covariances.shape()[0] != n_clusters {
+            return Err(MoeError::InvalidValueError(format!(
+                "covariances count {} does not match {} components",
+                covariances.shape()[0],
+                n_clusters
+            )));
+        }
+        if covariances.shape()[1..] != [n_features, n_features] {
+            return Err(MoeError::InvalidValueError(format!(
+                "covariances matrices shape {:?} does not match means dimension {}",
+                &covariances.shape()[1..],
+                n_features
+            )));
+        }
+        if let Some(w) = weights.iter().find(|w| **w < F::zero() || w.is_nan()) {
+            return Err(MoeError::InvalidValueError(format!(
+                "weights should be nonnegative, got {w}"
+            )));
+        }
+        Ok(())
+    }
+
     /// Number of clusters corresponding to the number of multivariate normal distributions
     /// used in the mixture
     pub fn n_clusters(&self) -> usize {
@@ -96,25 +236,585 @@ impl<F: Float> GaussianMixture<F> {
         &self.covariances
     }
 
+    /// Cholesky factors (n, nx, nx) of the precision matrices (see [GaussianMixture::from_precisions])
+    pub fn precisions_chol(&self) -> &Array3<F> {
+        &self.precisions_chol
+    }
+
+    /// Returns the mixture with the given covariance structure.
+    ///
+    /// With [CovarianceType::Tied], covariance matrices are replaced by the pooled within-cluster covariance
+    /// (i.e. the weighted sum of the covariances) shared by all components, which is then kept
+    /// by subsequent [GaussianMixture::em] iterations.
+    pub fn covariance_type(&self, covariance_type: CovarianceType) -> Result<GaussianMixture<F>> {
+        let mut gmx = self.clone();
+        gmx.covariance_type = covariance_type;
+        if covariance_type == CovarianceType::Tied {
+            let n_features = self.means.ncols();
+            let mut tied = Array2::<F>::zeros((n_features, n_features));
+            Zip::from(self.covariances.outer_iter())
+                .and(&self.weights)
+                .for_each(|cov, &w| tied.scaled_add(w / self.weights.sum(), &cov));
+            gmx.set_covariances(
+                tied.broadcast(self.covariances.raw_dim())
+                    .unwrap()
+                    .to_owned(),
+            )?;
+        }
+        Ok(gmx)
+    }
+
+    /// Returns the mixture with the given regularization of the covariances estimated by
+    /// subsequent [GaussianMixture::em] iterations.
+    pub fn covariance_regularizer(&self, regularizer: CovarianceRegularizer) -> GaussianMixture<F> {
+        let mut gmx = self.clone();
+        gmx.covariance_regularizer = regularizer;
+        gmx
+    }
+
+    /// Returns the mixture with the given threshold on the sum of a cluster responsibilities
+    /// under which subsequent [GaussianMixture::em] iterations declare the cluster empty.
+    /// Default to [crate::default_gmm_empty_cluster_threshold].
+    pub fn empty_cluster_threshold(&self, threshold: F) -> GaussianMixture<F> {
+        let mut gmx = self.clone();
+        gmx.empty_cluster_threshold = threshold;
+        gmx
+    }
+
+    /// Set covariance matrices and refresh precisions related data
+    fn set_covariances(&mut self, covariances: Array3<F>) -> Result<()> {
+        self.precisions_chol = Self::compute_precisions_cholesky(&covariances)?;
+        self.precisions = Self::compute_precisions(&self.precisions_chol);
+        self.log_det = Self::compute_log_det(&self.precisions_chol, self.heaviside_factor);
+        self.scaled_precisions_chol = OnceLock::new();
+        self.covariances = covariances;
+        Ok(())
+    }
+
+    /// Export the mixture parameters as a JSON object whose keys match the attributes
+    /// of a fitted scikit-learn `GaussianMixture` with full covariances: `weights_` (n,),
+    /// `means_` (n, nx), `covariances_` (n, nx, nx) and `precisions_cholesky_` (n, nx, nx).
+    ///
+    /// Note: the heaviside factor and the projection have no scikit-learn equivalent and are not exported.
+    #[cfg(feature = "persistent")]
+    pub fn to_sklearn_json(&self) -> Result<String> {
+        let to_vec1 = |a: &Array1<F>| a.iter().map(|v| v.to_f64().unwrap()).collect::<Vec<_>>();
+        let to_vec2 = |a: &Array2<F>| {
+            a.outer_iter()
+                .map(|r| to_vec1(&r.to_owned()))
+                .collect::<Vec<_>>()
+        };
+        let to_vec3 = |a: &Array3<F>| {
+            a.outer_iter()
+                .map(|m| to_vec2(&m.to_owned()))
+                .collect::<Vec<_>>()
+        };
+        let params = serde_json::json!({
+            "weights_": to_vec1(&self.weights),
+            "means_": to_vec2(&self.means),
+            "covariances_": to_vec3(&self.covariances),
+            "precisions_cholesky_": to_vec3(&self.precisions_chol),
+        });
+        Ok(serde_json::to_string(&params)?)
+    }
+
+    /// Returns the gaussian mixture without the kth multivariate normal distribution,
+    /// remaining weights are normalized to sum to 1.
+    pub fn remove_cluster(&self, k: usize) -> Result<GaussianMixture<F>> {
+        let kept: Vec<usize> = (0..self.n_clusters()).filter(|&i| i != k).collect();
+        let weights = self.weights.select(Axis(0), &kept);
+        let weights = &weights / weights.sum();
+        let gmx = GaussianMixture::new(
+            weights,
+            self.means.select(Axis(0), &kept),
+            self.covariances.select(Axis(0), &kept),
+        )?;
+        let gmx = gmx.heaviside_factor(self.heaviside_factor);
+        Ok(GaussianMixture {
+            projection: self.projection.clone(),
+            covariance_type: self.covariance_type,
+            covariance_regularizer: self.covariance_regularizer,
+            empty_cluster_threshold: self.empty_cluster_threshold,
+            ..gmx
+        })
+    }
+
+    /// Returns the gaussian mixture made of the components of this mixture followed by the ones
+    /// of the `other` mixture (e.g. fitted on another shard of data), weights of this mixture being
+    /// scaled by `weight` and the ones of `other` by `1 - weight`.
+    ///
+    /// Returns an [MoeError::InvalidValueError] if `weight` is not in [0, 1] or if mixtures
+    /// do not share the same number of features and projection.
+    /// The heaviside factor of this mixture is kept and the resulting covariances are [CovarianceType::Full].
+    pub fn merge(&self, other: &GaussianMixture<F>, weight: F) -> Result<GaussianMixture<F>> {
+        if !(F::zero()..=F::one()).contains(&weight) {
+            return Err(MoeError::InvalidValueError(format!(
+                "Merge weight should be in [0, 1], got {weight}"
+            )));
+        }
+        if self.n_features() != other.n_features() || self.means.ncols() != other.means.ncols() {
+            return Err(MoeError::InvalidValueError(format!(
+                "Cannot merge mixtures with {} and {} features",
+                self.n_features(),
+                other.n_features()
+            )));
+        }
+        if self.projection != other.projection {
+            return Err(MoeError::InvalidValueError(
+                "Cannot merge mixtures with different projections".to_string(),
+            ));
+        }
+        let weights = concatenate![
+            Axis(0),
+            &self.weights / self.weights.sum() * weight,
+            &other.weights / other.weights.sum() * (F::one() - weight)
+        ];
+        let gmx = GaussianMixture::new(
+            weights,
+            concatenate![Axis(0), self.means, other.means],
+            concatenate![Axis(0), self.covariances, other.covariances],
+        )?;
+        let gmx = gmx.heaviside_factor(self.heaviside_factor);
+        Ok(GaussianMixture {
+            projection: self.projection.clone(),
+            empty_cluster_threshold: self.empty_cluster_threshold,
+            ..gmx
+        })
+    }
+
+    /// Run expectation-maximization (EM) iterations on n x points given as a (n, nx) matrix
+    /// starting from the current mixture parameters, until the mean log-likelihood improvement
+    /// is below `tol` or `max_iter` iterations are done.
+    ///
+    /// When `batch_size` is specified, responsibilities are computed on chunks of `batch_size` points
+    /// whose sufficient statistics are accumulated, which bounds temporary allocations on large datasets.
+    /// The resulting mixture is the same as with full-batch EM (`batch_size` is `None`).
+    ///
+    /// Returns an [MoeError::EmptyCluster] error if a cluster has no more point, i.e. the sum of
+    /// its responsibilities is below the threshold set with [GaussianMixture::empty_cluster_threshold].
+    pub fn em<D: Data<Elem = F>>(
+        &self,
+        x: &ArrayBase<D, Ix2>,
+        max_iter: usize,
+        tol: F,
+        batch_size: Option<usize>,
+    ) -> Result<GaussianMixture<F>> {
+        self.em_with_report(x, max_iter, tol, batch_size)
+            .map(|(gmx, _)| gmx)
+    }
+
+    /// Same as [GaussianMixture::em] but returns as well the final log-likelihood,
+    /// the number of iterations run and whether EM has converged
+    /// (i.e. `max_iter` was not hit).
+    pub fn em_with_report<D: Data<Elem = F>>(
+        &self,
+        x: &ArrayBase<D, Ix2>,
+        max_iter: usize,
+        tol: F,
+        batch_size: Option<usize>,
+    ) -> Result<(GaussianMixture<F>, EmReport<F>)> {
+        let batch_size = batch_size.unwrap_or(x.nrows()).max(1);
+        let reg_covar = F::cast(1e-6);
+        let n_samples = F::cast(x.nrows());
+        let (n_clusters, n_features) = self.means.dim();
+
+        // EM is done with standard multivariate normal densities
+        let mut gmx = self.clone().heaviside_factor(F::one());
+        let mut lower_bound = F::neg_infinity();
+        let mut report = EmReport {
+            log_likelihood: lower_bound,
+            n_iter: 0,
+            converged: false,
+        };
+        for _ in 0..max_iter {
+            // E-step: accumulate sufficient statistics over chunks of x
+            let mut nk = Array1::<F>::zeros(n_clusters);
+            let mut sx = Array2::<F>::zeros((n_clusters, n_features));
+            let mut sxx = Array3::<F>::zeros((n_clusters, n_features, n_features));
+            let mut log_prob = F::zero();
+            for chunk in x.axis_chunks_iter(Axis(0), batch_size) {
+                let z = gmx.project(&chunk);
+                let (log_prob_norm, log_resp) = gmx.compute_log_prob_resp(&z);
+                let resp = log_resp.mapv(|v| v.exp());
+                log_prob += log_prob_norm.sum();
+                nk += &resp.sum_axis(Axis(0));
+                sx += &resp.t().dot(&z);
+                Zip::from(sxx.outer_iter_mut())
+                    .and(resp.columns())
+                    .for_each(|mut s, r| {
+                        s += &(&z * &r.insert_axis(Axis(1))).t().dot(&z);
+                    });
+            }
+            if let Some(k) = nk
+                .iter()
+                .position(|v| *v <= F::zero() || *v < self.empty_cluster_threshold)
+            {
+                return Err(MoeError::EmptyCluster(format!(
+                    "Cluster #{} has no more point during EM",
+                    k + 1
+                )));
+            }
+
+            // M-step: update weights, means and covariances
+            let means = &sx / &nk.view().insert_axis(Axis(1));
+            let mut covariances = Array3::zeros(sxx.dim());
+            Zip::from(covariances.outer_iter_mut())
+                .and(sxx.outer_iter())
+                .and(means.rows())
+                .and(&nk)
+                .for_each(|mut cov, s, mu, &n| {
+                    let mu = mu.insert_axis(Axis(1));
+                    cov.assign(&(&s / n - mu.dot(&mu.t())));
+                });
+            if self.covariance_type == CovarianceType::Tied {
+                // Pooled within-cluster scatter shared by all components
+                let mut tied = Array2::<F>::zeros((n_features, n_features));
+                Zip::from(covariances.outer_iter())
+                    .and(&nk)
+                    .for_each(|cov, &n| tied.scaled_add(n / n_samples, &cov));
+                covariances.assign(&tied);
+            }
+            if self.covariance_regularizer != CovarianceRegularizer::None {
+                Zip::from(covariances.outer_iter_mut())
+                    .and(&nk)
+                    .for_each(|mut cov, &n| {
+                        let n = if self.covariance_type == CovarianceType::Tied {
+                            n_samples
+                        } else {
+                            n
+                        };
+                        cov.assign(&shrink_covariance(&cov, n, self.covariance_regularizer));
+                    });
+            }
+            covariances
+                .outer_iter_mut()
+                .for_each(|mut cov| cov.diag_mut().mapv_inplace(|v| v + reg_covar));
+            let weights = &nk / n_samples;
+            gmx = GaussianMixture {
+                projection: self.projection.clone(),
+                covariance_type: self.covariance_type,
+                covariance_regularizer: self.covariance_regularizer,
+                empty_cluster_threshold: self.empty_cluster_threshold,
+                ..GaussianMixture::new(weights, means, covariances)?
+            };
+
+            let prev_lower_bound = lower_bound;
+            lower_bound = log_prob / n_samples;
+            report.log_likelihood = lower_bound;
+            report.n_iter += 1;
+            if (lower_bound - prev_lower_bound).abs() < tol {
+                report.converged = true;
+                break;
+            }
+        }
+        Ok((gmx.heaviside_factor(self.heaviside_factor), report))
+    }
+
     /// Setter for heaviside factor which change the transition between
     /// clusters in case of smooth recombination
     pub fn heaviside_factor(mut self, heaviside_factor: F) -> Self {
         self.heaviside_factor = heaviside_factor;
-        // refresh log of precision matrix determinant
+        // refresh log of precision matrix determinant and invalidate scaled precisions
         self.log_det = Self::compute_log_det(&self.precisions_chol, self.heaviside_factor);
+        self.scaled_precisions_chol = OnceLock::new();
         self
     }
 
+    /// Compute the Bayesian Information Criterion of the mixture given n points `x` as a (n, nx) matrix:
+    /// `BIC = -2 * log-likelihood(x) + n_params * ln(n)`, the lower the better.
+    ///
+    /// Note: log-likelihood depends on the heaviside factor, the standard BIC is obtained with the default factor 1.
+    pub fn bic<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> F {
+        let (log_prob_norm, _) = self.compute_log_prob_resp(&self.project(x));
+        let n_clusters = self.n_clusters();
+        let n_features = self.means.ncols();
+        // means, covariances and weights (summing to 1) parameters
+        let n_cov_params = match self.covariance_type {
+            CovarianceType::Full => n_clusters * n_features * (n_features + 1) / 2,
+            CovarianceType::Tied => n_features * (n_features + 1) / 2,
+        };
+        let n_params = n_clusters * n_features + n_cov_params + n_clusters - 1;
+        F::cast(-2.) * log_prob_norm.sum() + F::cast(n_params) * F::cast(x.nrows()).ln()
+    }
+
+    /// Compute the log-likelihood of each n x points given as a (n, nx) matrix,
+    /// i.e. the log of the gaussian mixture density.
+    pub fn score_samples<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        let (log_prob_norm, _) = self.compute_log_prob_resp(&self.project(x));
+        log_prob_norm
+    }
+
+    /// Compute the gradient wrt x of the log-likelihood (see [GaussianMixture::score_samples])
+    /// of each n x points given as a (n, nx) matrix.
+    /// Returns a (n, nx) matrix, the gradient being the sum over clusters of
+    /// the precision-weighted differences to the means weighted by the responsibilities.
+    pub fn score_gradient<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array2<F> {
+        let z = self.project(x);
+        let (_, log_resp) = self.compute_log_prob_resp(&z);
+        let resp = log_resp.mapv(|v| v.exp());
+        let precs = &self.precisions / self.heaviside_factor;
+        let mut grad = Array2::zeros(z.dim());
+        Zip::from(resp.columns())
+            .and(self.means.rows())
+            .and(precs.outer_iter())
+            .for_each(|r, mu, prec| {
+                let dlogp = (&z - &mu).dot(&prec).mapv(|v| -v);
+                grad += &(dlogp * r.insert_axis(Axis(1)));
+            });
+        match &self.projection {
+            // chain rule wrt x
+            Some((_, matrix)) => grad.dot(&matrix.t()),
+            None => grad,
+        }
+    }
+
+    /// Draw n points from the mixture returned as a (n, nx) matrix
+    /// (see [GaussianMixture::sample_with_labels]).
+    pub fn sample<R: Rng>(&self, n: usize, rng: &mut R) -> Array2<F> {
+        self.sample_with_labels(n, rng).0
+    }
+
+    /// Draw n points from the mixture returned as a (n, nx) matrix together with
+    /// the (n,) vector of the indices of the components they are drawn from.
+    ///
+    /// Each point component is selected with a probability given by the mixture weights,
+    /// then the point is drawn from the multivariate normal distribution of that component.
+    /// When a projection is set, points are drawn in the projected space.
+    pub fn sample_with_labels<R: Rng>(&self, n: usize, rng: &mut R) -> (Array2<F>, Array1<usize>) {
+        let cum_weights = self
+            .weights
+            .iter()
+            .scan(F::zero(), |acc, w| {
+                *acc += *w;
+                Some(*acc)
+            })
+            .collect::<Vec<_>>();
+        let total = cum_weights[cum_weights.len() - 1];
+        let labels = Array1::from_shape_fn(n, |_| {
+            let u = F::cast(rng.gen::<f64>()) * total;
+            cum_weights
+                .iter()
+                .position(|c| u < *c)
+                .unwrap_or(cum_weights.len() - 1)
+        });
+
+        // covariance = L.L^T with L = P^-T, hence x = mean + z.P^-1 with z ~ N(0, I)
+        let chol_invs = Self::compute_precisions_chol_inv(&self.precisions_chol)
+            .expect("Mixture precisions cholesky factors should be invertible");
+        let mut samples =
+            Array2::<f64>::random_using((n, self.means.ncols()), StandardNormal, rng).mapv(F::cast);
+        Zip::from(samples.rows_mut())
+            .and(&labels)
+            .for_each(|mut x, &k| {
+                let xk = x.dot(&chol_invs.index_axis(Axis(0), k)) + self.means.row(k);
+                x.assign(&xk);
+            });
+        (samples, labels)
+    }
+
+    /// Setter for an affine projection of x points given as an `offset` (nx,) vector and
+    /// a `matrix` (nx, d): multivariate normal distributions of dimension d are then considered
+    /// in the projected space and x points are projected as `(x - offset).matrix` before computations.
+    pub fn projection(mut self, offset: Array1<F>, matrix: Array2<F>) -> Self {
+        self.projection = Some((offset, matrix));
+        self
+    }
+
+    /// Number of features nx of the x points expected by the mixture
+    pub(crate) fn n_features(&self) -> usize {
+        match &self.projection {
+            Some((offset, _)) => offset.len(),
+            None => self.means.ncols(),
+        }
+    }
+
+    /// Check x points given as a (n, nx) matrix have the number of features expected by the mixture
+    ///
+    /// # Panics
+    ///
+    /// Panics if x does not have the expected number of columns
+    fn check_features<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) {
+        assert_eq!(
+            x.ncols(),
+            self.n_features(),
+            "GaussianMixture: x points should have {} features, got {}",
+            self.n_features(),
+            x.ncols()
+        );
+    }
+
+    /// Project x points given as a (n, nx) matrix in the space of the multivariate normal distributions,
+    /// without copying x when no projection is set
+    fn project<'a, D: Data<Elem = F>>(&self, x: &'a ArrayBase<D, Ix2>) -> CowArray<'a, F, Ix2> {
+        self.check_features(x);
+        match &self.projection {
+            Some((offset, matrix)) => (x - offset).dot(matrix).into(),
+            None => x.view().into(),
+        }
+    }
+
     /// Compute the probability of each n x points given as a (n, nx) matrix to belong to a given cluster.
+    ///
+    /// Like other prediction methods, panics if x does not have the number of features of the mixture.
     pub fn predict_probas<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array2<F> {
+        self.check_features(x);
         if self.n_clusters() == 1 {
             Array::from_elem((x.nrows(), 1), F::one())
         } else {
-            let (_, log_resp) = self.compute_log_prob_resp(x);
+            let (_, log_resp) = self.compute_log_prob_resp(&self.project(x));
             log_resp.mapv(|v| v.exp())
         }
     }
 
+    /// Assign each n x points given as a (n, nx) matrix to the cluster with the highest
+    /// responsibility, ties being broken in favor of the lowest cluster index.
+    ///
+    /// Unlike `predict`, returns an [MoeError::InvalidValueError] if x does not have the number
+    /// of features of the mixture or if the responsibilities of a point are undefined (NaN).
+    pub fn try_predict<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Result<Array1<usize>> {
+        if x.ncols() != self.n_features() {
+            return Err(MoeError::InvalidValueError(format!(
+                "Expected {} features, got {}",
+                self.n_features(),
+                x.ncols()
+            )));
+        }
+        let (_, log_resp) = self.compute_log_prob_resp(&self.project(x));
+        log_resp
+            .outer_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                first_argmax(&row).ok_or_else(|| {
+                    MoeError::InvalidValueError(format!(
+                        "Undefined cluster responsibilities of point {i}"
+                    ))
+                })
+            })
+            .collect::<Result<Array1<_>>>()
+    }
+
+    /// Compute the log of the probability of each n x points given as a (n, nx) matrix
+    /// to belong to a given cluster (see [GaussianMixture::predict_probas]).
+    /// Returns a (n, n_clusters) matrix, more accurate than the log of probabilities when these are small.
+    pub fn log_responsibilities<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array2<F> {
+        self.check_features(x);
+        if self.n_clusters() == 1 {
+            Array::zeros((x.nrows(), 1))
+        } else {
+            let (_, log_resp) = self.compute_log_prob_resp(&self.project(x));
+            log_resp
+        }
+    }
+
+    /// Compute the entropy `-sum_k p_k.ln(p_k)` of the cluster probabilities p of each n x points
+    /// given as a (n, nx) matrix (see [GaussianMixture::predict_probas]).
+    ///
+    /// Entropy is 0 when a point surely belongs to one cluster and is at most ln(n_clusters)
+    /// when it is equally likely to belong to any cluster, hence high values flag points
+    /// at clusters boundaries.
+    pub fn responsibility_entropy<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array1<F> {
+        self.log_responsibilities(x).map_axis(Axis(1), |log_resp| {
+            -log_resp
+                .iter()
+                .filter(|v| v.is_finite())
+                .fold(F::zero(), |acc, &v| acc + v.exp() * v)
+        })
+    }
+
+    /// Label the points of a regular `resolution` x `resolution` grid of the 2D box `xlimits`
+    /// given as a (2, 2) matrix of lower and upper bounds with their cluster (see `predict`),
+    /// e.g. to display the decision boundaries of the mixture.
+    /// Returns the grid points as a (resolution^2, 2) matrix, the second coordinate varying
+    /// the fastest, and their labels (resolution^2,).
+    ///
+    /// Returns an [MoeError::InvalidValueError] if the mixture inputs are not 2D
+    /// or if `xlimits` is not a (2, 2) matrix.
+    pub fn label_grid(
+        &self,
+        xlimits: &Array2<F>,
+        resolution: usize,
+    ) -> Result<(Array2<F>, Array1<usize>)> {
+        if self.n_features() != 2 {
+            return Err(MoeError::InvalidValueError(format!(
+                "Label grid is only available for 2D inputs, got {} dimensions",
+                self.n_features()
+            )));
+        }
+        if xlimits.dim() != (2, 2) {
+            return Err(MoeError::InvalidValueError(format!(
+                "Bounds should be a (2, 2) matrix, got {:?}",
+                xlimits.dim()
+            )));
+        }
+        let resolution = resolution.max(2);
+        let x0 = Array1::linspace(xlimits[[0, 0]], xlimits[[0, 1]], resolution);
+        let x1 = Array1::linspace(xlimits[[1, 0]], xlimits[[1, 1]], resolution);
+        let grid = Array2::from_shape_fn((resolution * resolution, 2), |(k, j)| {
+            if j == 0 {
+                x0[k / resolution]
+            } else {
+                x1[k % resolution]
+            }
+        });
+        let labels = self.predict(&grid);
+        Ok((grid, labels))
+    }
+
+    /// Assign each n x points given as a (n, nx) matrix, considered as an ordered sequence
+    /// (e.g. a trajectory), to a cluster while penalizing cluster switches between consecutive points.
+    ///
+    /// The returned labels maximize the sum of the log-responsibilities of the points minus
+    /// `switch_penalty` times the number of switches (Viterbi algorithm). A zero penalty gives
+    /// the same labels as `predict`, a larger one gives a smoother label sequence.
+    ///
+    /// Panics if `switch_penalty` is negative.
+    pub fn predict_smoothed<D: Data<Elem = F>>(
+        &self,
+        x: &ArrayBase<D, Ix2>,
+        switch_penalty: F,
+    ) -> Array1<usize> {
+        assert!(
+            switch_penalty >= F::zero(),
+            "Switch penalty should be non negative, got {switch_penalty}"
+        );
+        if switch_penalty == F::zero() {
+            return self.predict(x);
+        }
+        let log_resp = self.log_responsibilities(x);
+        let (n, n_clusters) = log_resp.dim();
+        if n == 0 {
+            return Array1::zeros(0);
+        }
+
+        // Forward pass: best score of a label sequence ending in each cluster
+        let mut score = log_resp.row(0).to_owned();
+        let mut backpointers = Array2::<usize>::zeros((n, n_clusters));
+        for (t, row) in log_resp.outer_iter().enumerate().skip(1) {
+            let mut next = Array1::zeros(n_clusters);
+            for k in 0..n_clusters {
+                let from = Array1::from_shape_fn(n_clusters, |j| {
+                    if j == k {
+                        score[j]
+                    } else {
+                        score[j] - switch_penalty
+                    }
+                });
+                let j = first_argmax(&from).unwrap_or(0);
+                backpointers[[t, k]] = j;
+                next[k] = from[j] + row[k];
+            }
+            score = next;
+        }
+
+        // Backward pass: follow back pointers from the best final cluster
+        let mut labels = Array1::zeros(n);
+        labels[n - 1] = first_argmax(&score).unwrap_or(0);
+        for t in (1..n).rev() {
+            labels[t - 1] = backpointers[[t, labels[t]]];
+        }
+        labels
+    }
+
     /// Compute the derivatives of the probability at the x point given as a (nx,) vector
     /// to belong to a given cluster among the n clusters.
     /// Returns a (n, nx) matrix where the ith row is the derivatives wrt to the nx components valued at x
@@ -123,20 +823,25 @@ impl<F: Float> GaussianMixture<F> {
         &self,
         x: &ArrayBase<D, Ix1>,
     ) -> Array2<F> {
-        let v = self.weights.to_owned().dot(&self.pdfs(x));
+        let x = self
+            .project(&x.view().insert_axis(Axis(0)))
+            .into_owned()
+            .remove_axis(Axis(0));
+        let x = &x;
+        let pdfs = self.projected_pdfs(x);
+        let v = self.weights.to_owned().dot(&pdfs);
         let precs = &self.precisions / self.heaviside_factor;
         let mut deriv = Array2::zeros((self.means.nrows(), self.means.ncols()));
         Zip::from(deriv.rows_mut())
             .and(self.means.rows())
             .and(precs.outer_iter())
             .for_each(|mut der, mu, prec| {
-                der.assign(&(&x.to_owned() - &mu).dot(&prec));
+                der.assign(&(x - &mu).dot(&prec));
             });
-        let vprime =
-            deriv.to_owned() * &(-self.weights.to_owned() * self.pdfs(x)).insert_axis(Axis(1));
+        let vprime = deriv.to_owned() * &(-self.weights.to_owned() * &pdfs).insert_axis(Axis(1));
         let vprime = vprime.sum_axis(Axis(0));
 
-        let u = (self.weights.to_owned() * self.pdfs(x))
+        let u = (self.weights.to_owned() * &pdfs)
             .to_owned()
             .insert_axis(Axis(1));
         let uprime = -(deriv.to_owned() * &u.to_owned());
@@ -144,7 +849,11 @@ impl<F: Float> GaussianMixture<F> {
         let prob_deriv = (uprime.mapv(|up| up * v)
             - u.to_owned() * vprime.broadcast((u.nrows(), vprime.len())).unwrap())
         .mapv(|w| w / v2);
-        prob_deriv
+        match &self.projection {
+            // chain rule wrt x
+            Some((_, matrix)) => prob_deriv.dot(&matrix.t()),
+            None => prob_deriv,
+        }
     }
 
     /// Compute the derivatives of the probability of a set of x points given as a (m, nx) vector
@@ -155,6 +864,7 @@ impl<F: Float> GaussianMixture<F> {
         &self,
         x: &ArrayBase<D, Ix2>,
     ) -> Array3<F> {
+        self.check_features(x);
         let mut prob = Array3::zeros((x.nrows(), self.means.nrows(), x.ncols()));
         Zip::from(prob.outer_iter_mut())
             .and(x.rows())
@@ -168,7 +878,14 @@ impl<F: Float> GaussianMixture<F> {
     /// Compute the density functions at x for the n multivariate normal distributions
     /// Returns the pdf values as a (n,) vector
     pub fn pdfs<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix1>) -> Array1<F> {
-        let xx = x.to_owned().insert_axis(Axis(0));
+        let x = x.view().insert_axis(Axis(0));
+        let xx = self.project(&x);
+        self.compute_log_gaussian_prob(&xx).row(0).mapv(|v| v.exp())
+    }
+
+    /// Compute the density functions at x already projected (see [GaussianMixture::projection])
+    fn projected_pdfs<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix1>) -> Array1<F> {
+        let xx = x.view().insert_axis(Axis(0));
         self.compute_log_gaussian_prob(&xx).row(0).mapv(|v| v.exp())
     }
 
@@ -199,6 +916,47 @@ impl<F: Float> GaussianMixture<F> {
         Ok(precisions_chol)
     }
 
+    /// Compute covariance matrices of the multivariate normal distributions
+    /// as (P.P^T)^-1 = P^-T.P^-1 from the upper triangular cholesky factors P of the precisions
+    fn compute_covariances<D: Data<Elem = F>>(
+        precisions_chol: &ArrayBase<D, Ix3>,
+    ) -> Result<Array3<F>> {
+        let mut covariances = Self::compute_precisions_chol_inv(precisions_chol)?;
+        for mut inv in covariances.outer_iter_mut() {
+            let cov = inv.t().dot(&inv);
+            inv.assign(&cov);
+        }
+        Ok(covariances)
+    }
+
+    /// Compute inverses P^-1 of the upper triangular cholesky factors P of the precisions
+    fn compute_precisions_chol_inv<D: Data<Elem = F>>(
+        precisions_chol: &ArrayBase<D, Ix3>,
+    ) -> Result<Array3<F>> {
+        let n_features = precisions_chol.shape()[1];
+        let mut invs = Array3::zeros(precisions_chol.dim());
+        for (k, prec_chol) in precisions_chol.outer_iter().enumerate() {
+            if let Some(d) = prec_chol
+                .diag()
+                .iter()
+                .find(|d| **d <= F::zero() || d.is_nan())
+            {
+                return Err(MoeError::InvalidValueError(format!(
+                    "precisions cholesky factors should have a positive diagonal, got {d}"
+                )));
+            }
+            #[cfg(feature = "blas")]
+            let inv = prec_chol
+                .with_lapack()
+                .solve_triangular(UPLO::Upper, Diag::NonUnit, &Array::eye(n_features))?
+                .without_lapack();
+            #[cfg(not(feature = "blas"))]
+            let inv = prec_chol.solve_triangular(&Array::eye(n_features), UPLO::Upper)?;
+            invs.slice_mut(s![k, .., ..]).assign(&inv);
+        }
+        Ok(invs)
+    }
+
     /// Compute precision matrices of the multivariate normal distributions
     fn compute_precisions<D: Data<Elem = F>>(precisions_chol: &ArrayBase<D, Ix3>) -> Array3<F> {
         let mut precisions = Array3::zeros(precisions_chol.dim());
@@ -228,26 +986,31 @@ impl<F: Float> GaussianMixture<F> {
         x: &ArrayBase<D, Ix2>,
     ) -> (Array1<F>, Array2<F>) {
         let weighted_log_prob = self.compute_log_gaussian_prob(x) + self.weights().mapv(|v| v.ln());
-        let log_prob_norm = weighted_log_prob
-            .mapv(|v| {
-                if v <= F::cast(f64::MIN_10_EXP) {
-                    F::zero()
-                } else {
-                    v.exp()
-                }
-            })
-            .sum_axis(Axis(1))
-            .mapv(|v| {
-                if v.abs() < F::epsilon() {
-                    F::zero()
-                } else {
-                    v.ln()
-                }
-            });
+        // Stable logsumexp: the row maximum is factored out before exponentiation
+        // to avoid overflow/underflow with large log probabilities
+        let log_prob_norm = weighted_log_prob.map_axis(Axis(1), |row| {
+            let max = row.fold(F::neg_infinity(), |m, &v| m.max(v));
+            if max.is_finite() {
+                max + row.mapv(|v| (v - max).exp()).sum().ln()
+            } else {
+                F::zero()
+            }
+        });
         let log_resp = weighted_log_prob - log_prob_norm.to_owned().insert_axis(Axis(1));
         (log_prob_norm, log_resp)
     }
 
+    // Lower cholesky precisions scaled by the heaviside factor
+    fn scaled_precisions_chol(&self) -> &Array3<F> {
+        self.scaled_precisions_chol.get_or_init(|| {
+            let factor = ndarray_rand::rand_distr::num_traits::Float::powf(
+                self.heaviside_factor,
+                F::cast(-0.5),
+            );
+            &self.precisions_chol * factor
+        })
+    }
+
     // Compute the log Likelihood
     // log(P(X|Mean, Precision)) = -0.5*(d*ln(2*PI)-ln(det(Precision)+(X-Mean)^t.Precision.(X-Mean))
     fn compute_log_gaussian_prob<D: Data<Elem = F>>(&self, x: &ArrayBase<D, Ix2>) -> Array2<F> {
@@ -255,9 +1018,7 @@ impl<F: Float> GaussianMixture<F> {
         let n_features = x.ncols();
         let means = self.means();
         let n_clusters = means.nrows();
-        let factor =
-            ndarray_rand::rand_distr::num_traits::Float::powf(self.heaviside_factor, F::cast(-0.5));
-        let precs = &self.precisions_chol * factor;
+        let precs = self.scaled_precisions_chol();
         // The determinant of the precision matrix from the Cholesky decomposition
         // corresponds to the negative half of the determinant of the full precision
         // matrix.
@@ -267,7 +1028,7 @@ impl<F: Float> GaussianMixture<F> {
         Zip::indexed(means.rows())
             .and(precs.outer_iter())
             .for_each(|k, mu, prec_chol| {
-                let diff = (&x.to_owned() - &mu).dot(&prec_chol);
+                let diff = (x - &mu).dot(&prec_chol);
                 log_prob
                     .slice_mut(s![.., k])
                     .assign(&diff.mapv(|v| v * v).sum_axis(Axis(1)))
@@ -295,6 +1056,56 @@ impl<F: Float> GaussianMixture<F> {
     }
 }
 
+/// Index of the first maximum value of the given vector, hence on ties the lowest index wins.
+/// Shrink the covariance matrix `cov` (nx, nx) estimated from `n` points toward the scaled identity
+/// according to the given regularizer (see [CovarianceRegularizer]).
+pub fn shrink_covariance<F: Float>(
+    cov: &ArrayBase<impl Data<Elem = F>, Ix2>,
+    n: F,
+    regularizer: CovarianceRegularizer,
+) -> Array2<F> {
+    let p = F::cast(cov.nrows());
+    let tr = cov.diag().sum();
+    let mu = tr / p;
+    let rho = match regularizer {
+        CovarianceRegularizer::None => return cov.to_owned(),
+        CovarianceRegularizer::Shrinkage(rho) => F::cast(rho).max(F::zero()).min(F::one()),
+        CovarianceRegularizer::Oas => {
+            let tr2 = cov.iter().fold(F::zero(), |acc, &v| acc + v * v);
+            let two_over_p = F::cast(2.) / p;
+            let num = (F::one() - two_over_p) * tr2 + tr * tr;
+            let den = (n + F::one() - two_over_p) * (tr2 - tr * tr / p);
+            if den > F::zero() {
+                (num / den).min(F::one())
+            } else {
+                F::one()
+            }
+        }
+    };
+    let mut shrunk = cov.mapv(|v| v * (F::one() - rho));
+    shrunk.diag_mut().mapv_inplace(|v| v + rho * mu);
+    shrunk
+}
+
+/// Returns None if the vector is empty or contains NaN.
+fn first_argmax<F: Float>(row: &ArrayBase<impl Data<Elem = F>, Ix1>) -> Option<usize> {
+    let mut best: Option<(usize, F)> = None;
+    for (i, &v) in row.iter().enumerate() {
+        if v.is_nan() {
+            return None;
+        }
+        match best {
+            Some((_, max)) if v <= max => {}
+            _ => best = Some((i, v)),
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// Cluster prediction: each point is assigned to the cluster with the highest responsibility,
+/// ties being broken in favor of the lowest cluster index. Points with undefined (NaN)
+/// responsibilities are assigned to cluster 0, see [GaussianMixture::try_predict]
+/// to get an error instead.
 impl<F: Float, D: Data<Elem = F>> PredictInplace<ArrayBase<D, Ix2>, Array1<usize>>
     for GaussianMixture<F>
 {
@@ -305,10 +1116,8 @@ impl<F: Float, D: Data<Elem = F>> PredictInplace<ArrayBase<D, Ix2>, Array1<usize
             "The number of data points must match the number of output targets."
         );
 
-        let (_, log_resp) = self.compute_log_prob_resp(x);
-        *targets = log_resp
-            .mapv(F::exp)
-            .map_axis(Axis(1), |row| row.argmax().unwrap_or(0));
+        let (_, log_resp) = self.compute_log_prob_resp(&self.project(x));
+        *targets = log_resp.map_axis(Axis(1), |row| first_argmax(&row).unwrap_or(0));
     }
 
     fn default_target(&self, x: &ArrayBase<D, Ix2>) -> Array1<usize> {
@@ -320,8 +1129,11 @@ impl<F: Float, D: Data<Elem = F>> PredictInplace<ArrayBase<D, Ix2>, Array1<usize
 mod tests {
     use super::*;
 
-    use approx::assert_abs_diff_eq;
-    use ndarray::{array, Array, Array2};
+    use approx::{assert_abs_diff_eq, assert_abs_diff_ne};
+    use ndarray::{array, concatenate, Array, Array2};
+    use ndarray_rand::{rand::SeedableRng, rand_distr::Normal, RandomExt};
+    use ndarray_stats::QuantileExt;
+    use rand_xoshiro::Xoshiro256Plus;
 
     #[test]
     fn test_gmx() {
@@ -341,6 +1153,106 @@ mod tests {
         println!("probas =  {probas:?}");
     }
 
+    #[test]
+    fn test_log_responsibilities() {
+        let weights = array![0.3, 0.7];
+        let means = array![[0., 0.], [3., 2.]];
+        let covs = array![[[1., 0.3], [0.3, 2.]], [[2., -0.5], [-0.5, 1.]]];
+        let gmix = GaussianMixture::new(weights, means, covs)
+            .expect("Gaussian mixture creation failed")
+            .heaviside_factor(0.8);
+        let x = array![[0.5, 0.5], [1.5, 1.], [-1., 2.], [3., 2.5], [20., -10.]];
+        let log_resp = gmix.log_responsibilities(&x);
+        assert_abs_diff_eq!(
+            log_resp.mapv(f64::exp),
+            gmix.predict_probas(&x),
+            epsilon = 1e-12
+        );
+        // far away point: log of the small responsibility is still finite
+        assert!(log_resp[[4, 0]].is_finite() && log_resp[[4, 0]] < -20.);
+    }
+
+    #[test]
+    fn test_gmx_from_precisions() {
+        let weights = array![0.3, 0.7];
+        let means = array![[0., 0.], [3., 2.]];
+        let covs = array![[[1., 0.3], [0.3, 2.]], [[2., -0.5], [-0.5, 1.]]];
+        let gmix = GaussianMixture::new(weights.clone(), means.clone(), covs.clone())
+            .expect("Gaussian mixture creation failed");
+        let gmix2 =
+            GaussianMixture::from_precisions(weights, means, gmix.precisions_chol().to_owned())
+                .expect("Gaussian mixture creation from precisions failed");
+        assert_abs_diff_eq!(*gmix2.covariances(), covs, epsilon = 1e-12);
+
+        let x = array![[0.5, 0.5], [1.5, 1.], [-1., 2.], [3., 2.5], [20., -10.]];
+        assert_abs_diff_eq!(
+            gmix2.predict_probas(&x),
+            gmix.predict_probas(&x),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            gmix2.score_samples(&x),
+            gmix.score_samples(&x),
+            epsilon = 1e-12
+        );
+        assert_eq!(gmix2.predict(&x), gmix.predict(&x));
+
+        let singular = Array3::zeros((2, 2, 2));
+        assert!(matches!(
+            GaussianMixture::from_precisions(
+                array![0.3, 0.7],
+                array![[0., 0.], [3., 2.]],
+                singular
+            ),
+            Err(MoeError::InvalidValueError(_))
+        ));
+    }
+
+    #[test]
+    fn test_gmx_extreme_log_probas() {
+        // well-separated narrow clusters: log probabilities of points between them are
+        // far below the smallest exponent representable as f64
+        let weights = array![0.5, 0.5];
+        let means = array![[-1e3], [1e3]];
+        let covs = array![[[1.]], [[1.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+        let x = array![[0.], [-1e3], [10.], [2e3]];
+        let probas = gmix.predict_probas(&x);
+        assert!(probas.iter().all(|v| f64::is_finite(*v)));
+        assert_abs_diff_eq!(probas.sum_axis(Axis(1)), Array1::ones(4), epsilon = 1e-8);
+        assert_abs_diff_eq!(probas.row(0), array![0.5, 0.5], epsilon = 1e-8);
+        assert_abs_diff_eq!(probas.row(1), array![1., 0.], epsilon = 1e-8);
+        assert_abs_diff_eq!(probas.row(2), array![0., 1.], epsilon = 1e-8);
+        assert_eq!(gmix.predict(&x.slice(s![1.., ..])), array![0, 1, 1]);
+        assert!(gmix.score_samples(&x).iter().all(|v| f64::is_finite(*v)));
+    }
+
+    #[test]
+    fn test_score_gradient() {
+        let weights = array![0.3, 0.7];
+        let means = array![[0., 0.], [3., 2.]];
+        let covs = array![[[1., 0.3], [0.3, 2.]], [[2., -0.5], [-0.5, 1.]]];
+        let gmix = GaussianMixture::new(weights, means, covs)
+            .expect("Gaussian mixture creation failed")
+            .heaviside_factor(0.8);
+        let x = array![[0.5, 0.5], [1.5, 1.], [-1., 2.], [3., 2.5]];
+        let grad = gmix.score_gradient(&x);
+        assert_eq!(grad.dim(), x.dim());
+
+        let h = 1e-6;
+        for i in 0..x.nrows() {
+            for j in 0..x.ncols() {
+                let mut xp = x.row(i).to_owned().insert_axis(Axis(0));
+                let mut xm = xp.clone();
+                xp[[0, j]] += h;
+                xm[[0, j]] -= h;
+                let fd = (gmix.score_samples(&xp)[0] - gmix.score_samples(&xm)[0]) / (2. * h);
+                assert_abs_diff_eq!(grad[[i, j]], fd, epsilon = 1e-6);
+            }
+        }
+    }
+
     #[test]
     fn test_gmx_one_cluster() {
         let weights = array![1.0];
@@ -359,6 +1271,525 @@ mod tests {
         assert_abs_diff_eq!(probas, Array::from_elem((11, 1), 1.0));
     }
 
+    #[test]
+    #[should_panic(expected = "GaussianMixture: x points should have 2 features, got 3")]
+    fn test_gmx_features_mismatch() {
+        let weights = array![0.5, 0.5];
+        let means = array![[0., 0.], [4., 4.]];
+        let covs = array![[[3., 0.], [0., 3.]], [[3., 0.], [0., 3.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+        let obs = Array2::from_elem((5, 3), 1.);
+        gmix.predict_probas(&obs);
+    }
+
+    #[cfg(feature = "persistent")]
+    #[test]
+    fn test_gmx_to_sklearn_json() {
+        let weights = array![0.3, 0.7];
+        let means = array![[0., 0.], [3., 2.]];
+        let covs = array![[[1., 0.3], [0.3, 2.]], [[2., -0.5], [-0.5, 1.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+        let json = gmix.to_sklearn_json().expect("Export failed");
+        let params: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(params["weights_"].as_array().unwrap().len(), 2);
+        assert_eq!(params["means_"].as_array().unwrap().len(), 2);
+        assert_eq!(params["means_"][0].as_array().unwrap().len(), 2);
+        for key in ["covariances_", "precisions_cholesky_"] {
+            assert_eq!(params[key].as_array().unwrap().len(), 2);
+            assert_eq!(params[key][1].as_array().unwrap().len(), 2);
+            assert_eq!(params[key][1][0].as_array().unwrap().len(), 2);
+        }
+        assert_abs_diff_eq!(params["covariances_"][1][0][1].as_f64().unwrap(), -0.5);
+        // precisions_cholesky_ is upper triangular such that precision = P.P^T
+        assert_abs_diff_eq!(
+            params["precisions_cholesky_"][0][1][0].as_f64().unwrap(),
+            0.
+        );
+    }
+
+    #[test]
+    fn test_gmx_strided_view() {
+        let weights = array![0.3, 0.7];
+        let means = array![[0., 0.], [3., 2.]];
+        let covs = array![[[1., 0.3], [0.3, 2.]], [[2., -0.5], [-0.5, 1.]]];
+        let gmix = GaussianMixture::new(weights, means, covs)
+            .expect("Gaussian mixture creation failed")
+            .heaviside_factor(0.8);
+        // x points as every other row and column of a larger buffer
+        let buffer = Array::linspace(-2., 5., 80).into_shape((20, 4)).unwrap();
+        let x = buffer.slice(s![..;2, ..;2]);
+        let x_owned = x.to_owned();
+        assert_abs_diff_eq!(gmix.predict_probas(&x), gmix.predict_probas(&x_owned));
+        assert_abs_diff_eq!(gmix.score_samples(&x), gmix.score_samples(&x_owned));
+        assert_abs_diff_eq!(gmix.score_gradient(&x), gmix.score_gradient(&x_owned));
+        assert_abs_diff_eq!(gmix.pdfs(&x.row(3)), gmix.pdfs(&x_owned.row(3).to_owned()));
+
+        let gmix = gmix.projection(array![0.5, 0.5], array![[1., 0.5], [0., 1.]]);
+        assert_abs_diff_eq!(gmix.predict_probas(&x), gmix.predict_probas(&x_owned));
+        assert_abs_diff_eq!(
+            gmix.predict_probas_derivatives(&x),
+            gmix.predict_probas_derivatives(&x_owned)
+        );
+    }
+
+    #[test]
+    fn test_gmx_invalid_parameters() {
+        let weights = array![0.5, 0.5];
+        let means = array![[0., 0.], [4., 4.]];
+        let covs = array![[[3., 0.], [0., 3.]], [[3., 0.], [0., 3.]]];
+        let check_err = |res: Result<GaussianMixture<f64>>, msg: &str| match res {
+            Err(MoeError::InvalidValueError(err)) => assert_eq!(err, msg),
+            _ => panic!("Expected invalid value error: {msg}"),
+        };
+
+        check_err(
+            GaussianMixture::new(array![0.2, 0.3, 0.5], means.clone(), covs.clone()),
+            "weights length 3 does not match 2 components",
+        );
+        check_err(
+            GaussianMixture::new(
+                weights.clone(),
+                means.clone(),
+                covs.slice(s![..1, .., ..]).to_owned(),
+            ),
+            "covariances count 1 does not match 2 components",
+        );
+        check_err(
+            GaussianMixture::new(weights.clone(), array![[0.], [4.]], covs.clone()),
+            "covariances matrices shape [2, 2] does not match means dimension 1",
+        );
+        check_err(
+            GaussianMixture::new(array![-0.5, 1.5], means, covs),
+            "weights should be nonnegative, got -0.5",
+        );
+    }
+
+    #[test]
+    fn test_gmx_minibatch_em() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let blob1 = Array2::random_using((60, 2), Normal::new(0., 1.).unwrap(), &mut rng);
+        let blob2 = Array2::random_using((40, 2), Normal::new(5., 0.5).unwrap(), &mut rng);
+        let x = concatenate![Axis(0), blob1, blob2];
+
+        let weights = array![0.5, 0.5];
+        let means = array![[1., 1.], [3., 3.]];
+        let covs = array![[[1., 0.], [0., 1.]], [[1., 0.], [0., 1.]]];
+        let gmix = GaussianMixture::new(weights, means, covs)
+            .expect("Gaussian mixture creation failed")
+            .heaviside_factor(0.5);
+
+        let full = gmix.em(&x, 100, 1e-10, None).expect("EM failed");
+        let mini = gmix.em(&x, 100, 1e-10, Some(7)).expect("EM failed");
+        assert_abs_diff_eq!(full.weights(), mini.weights(), epsilon = 1e-10);
+        assert_abs_diff_eq!(full.means(), mini.means(), epsilon = 1e-10);
+        assert_abs_diff_eq!(full.covariances(), mini.covariances(), epsilon = 1e-10);
+        assert_abs_diff_eq!(full.weights(), &array![0.6, 0.4], epsilon = 1e-3);
+        assert_abs_diff_eq!(full.means()[[1, 0]], 5., epsilon = 0.2);
+        // Heaviside factor is kept
+        assert_abs_diff_eq!(full.heaviside_factor, 0.5);
+
+        let loglikelihood = |g: &GaussianMixture<f64>| {
+            g.clone()
+                .heaviside_factor(1.)
+                .score_samples(&x)
+                .mean()
+                .unwrap()
+        };
+        assert!(loglikelihood(&full) > loglikelihood(&gmix));
+    }
+
+    #[test]
+    fn test_gmx_em_report() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let blob1 = Array2::random_using((60, 2), Normal::new(0., 1.).unwrap(), &mut rng);
+        let blob2 = Array2::random_using((40, 2), Normal::new(5., 0.5).unwrap(), &mut rng);
+        let x = concatenate![Axis(0), blob1, blob2];
+
+        let weights = array![0.5, 0.5];
+        let means = array![[1., 1.], [3., 3.]];
+        let covs = array![[[1., 0.], [0., 1.]], [[1., 0.], [0., 1.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+
+        let (_, report) = gmix.em_with_report(&x, 2, 1e-10, None).expect("EM failed");
+        assert!(!report.converged);
+        assert_eq!(report.n_iter, 2);
+
+        let (full, report) = gmix
+            .em_with_report(&x, 1000, 1e-10, None)
+            .expect("EM failed");
+        assert!(report.converged);
+        assert!(report.n_iter > 2 && report.n_iter < 1000);
+        assert_abs_diff_eq!(
+            report.log_likelihood,
+            full.score_samples(&x).mean().unwrap(),
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_gmx_em_empty_cluster_threshold() {
+        let x = Array::linspace(-1., 1., 11).insert_axis(Axis(1));
+        // second component far from data: its responsibilities sum to about 4e-16
+        let gmix = GaussianMixture::new(
+            array![0.5, 0.5],
+            array![[0.], [9.5]],
+            array![[[1.]], [[1.]]],
+        )
+        .expect("Gaussian mixture creation failed");
+
+        let res = gmix.em(&x, 1, 1e-10, None);
+        assert!(matches!(res, Err(MoeError::EmptyCluster(_))));
+
+        let gmx = gmix
+            .empty_cluster_threshold(1e-16)
+            .em(&x, 1, 1e-10, None)
+            .expect("EM with a looser threshold");
+        assert!(gmx.weights()[1] > 0. && gmx.weights()[1] < 1e-16);
+    }
+
+    #[test]
+    fn test_gmx_predict_smoothed() {
+        let weights = array![0.5, 0.5];
+        let means = array![[0.], [2.]];
+        let covs = array![[[1.]], [[1.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+
+        // noisy trajectory switching once from the first regime to the second one
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let regimes = Array1::from_shape_fn(100, |i| if i < 50 { 0. } else { 2. });
+        let noise = Array1::random_using(100, Normal::new(0., 0.8).unwrap(), &mut rng);
+        let x = (regimes + noise).insert_axis(Axis(1));
+
+        let n_switches = |labels: &Array1<usize>| {
+            labels
+                .windows(2)
+                .into_iter()
+                .filter(|w| w[0] != w[1])
+                .count()
+        };
+        let labels = gmix.predict(&x);
+        assert_eq!(gmix.predict_smoothed(&x, 0.), labels);
+        let smoothed = gmix.predict_smoothed(&x, 3.);
+        println!(
+            "switches: plain = {}, smoothed = {}",
+            n_switches(&labels),
+            n_switches(&smoothed)
+        );
+        assert!(n_switches(&smoothed) < n_switches(&labels));
+        assert_eq!(smoothed[0], 0);
+        assert_eq!(smoothed[99], 1);
+    }
+
+    #[test]
+    fn test_gmx_merge() {
+        let gmx1 = GaussianMixture::new(array![1.], array![[0., 0.]], array![[[1., 0.], [0., 1.]]])
+            .expect("Gaussian mixture creation failed");
+        let gmx2 = GaussianMixture::new(array![1.], array![[5., 5.]], array![[[1., 0.], [0., 1.]]])
+            .expect("Gaussian mixture creation failed");
+
+        let merged = gmx1.merge(&gmx2, 0.3).expect("Mixtures merged");
+        assert_eq!(merged.n_clusters(), 2);
+        assert_abs_diff_eq!(merged.weights(), &array![0.3, 0.7], epsilon = 1e-12);
+        assert_abs_diff_eq!(
+            merged.precisions_chol().slice(s![1, .., ..]),
+            gmx2.precisions_chol().slice(s![0, .., ..]),
+            epsilon = 1e-12
+        );
+        let labels = merged.predict(&array![[0., 0.], [0.5, -0.5], [5., 5.], [4.5, 5.5]]);
+        assert_eq!(labels, array![0, 0, 1, 1]);
+
+        let gmx3 = GaussianMixture::new(array![1.], array![[0.]], array![[[1.]]])
+            .expect("Gaussian mixture creation failed");
+        assert!(gmx1.merge(&gmx3, 0.5).is_err());
+        assert!(gmx1.merge(&gmx2, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_shrink_covariance() {
+        // fewer points than dimensions: empirical covariance is singular
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let x = Array2::random_using((5, 20), Normal::new(0., 1.).unwrap(), &mut rng);
+        let xc = &x - &x.mean_axis(Axis(0)).unwrap();
+        let cov = xc.t().dot(&xc) / 5.;
+        assert!(GaussianMixture::new(
+            array![1.],
+            x.slice(s![0..1, ..]).to_owned(),
+            cov.to_owned().insert_axis(Axis(0))
+        )
+        .is_err());
+
+        // ratio of extreme squared cholesky pivots as a conditioning estimate
+        let condition_number = |c: &Array2<f64>| {
+            let gmx = GaussianMixture::new(
+                array![1.],
+                x.slice(s![0..1, ..]).to_owned(),
+                c.to_owned().insert_axis(Axis(0)),
+            )
+            .expect("Cholesky");
+            let d = gmx
+                .precisions_chol()
+                .slice(s![0, .., ..])
+                .diag()
+                .mapv(|v| v * v);
+            d.max().unwrap() / d.min().unwrap()
+        };
+        let fixed = shrink_covariance(&cov, 5., CovarianceRegularizer::Shrinkage(0.1));
+        let oas = shrink_covariance(&cov, 5., CovarianceRegularizer::Oas);
+        assert!(condition_number(&oas) < condition_number(&fixed));
+        // trace is preserved
+        assert_abs_diff_eq!(oas.diag().sum(), cov.diag().sum(), epsilon = 1e-10);
+        assert_eq!(
+            shrink_covariance(&cov, 5., CovarianceRegularizer::None),
+            cov
+        );
+
+        // EM on wide clusters
+        let weights = array![0.5, 0.5];
+        let means = concatenate![Axis(0), x.slice(s![0..1, ..]), &x.slice(s![0..1, ..]) + 3.];
+        let covs = Array3::from_shape_fn((2, 20, 20), |(_, i, j)| if i == j { 1. } else { 0. });
+        let data = concatenate![Axis(0), x, &x + 3.];
+        let gmx = GaussianMixture::new(weights, means, covs)
+            .unwrap()
+            .covariance_regularizer(CovarianceRegularizer::Oas);
+        let fitted = gmx.em(&data, 10, 1e-6, None).expect("EM with shrinkage");
+        assert_eq!(fitted.predict(&data), array![0, 0, 0, 0, 0, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_gmx_label_grid() {
+        let weights = array![0.5, 0.5];
+        let means = array![[-1., -1.], [1., 1.]];
+        let covs = array![[[1., 0.], [0., 1.]], [[1., 0.], [0., 1.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+
+        let res = 20;
+        let (grid, labels) = gmix
+            .label_grid(&array![[-3., 3.], [-3., 3.]], res)
+            .expect("Label grid");
+        assert_eq!(grid.dim(), (res * res, 2));
+        assert_eq!(grid.row(1), array![-3., -3. + 6. / 19.]);
+        assert!(labels.iter().any(|&l| l == 0) && labels.iter().any(|&l| l == 1));
+
+        // each label forms a single connected region of the grid
+        for label in 0..2 {
+            let cells: Vec<usize> = (0..res * res).filter(|&k| labels[k] == label).collect();
+            let mut visited = vec![false; res * res];
+            let mut stack = vec![cells[0]];
+            visited[cells[0]] = true;
+            let mut n_reached = 0;
+            while let Some(k) = stack.pop() {
+                n_reached += 1;
+                let (i, j) = (k / res, k % res);
+                let neighbors = [
+                    (i > 0).then(|| k - res),
+                    (i + 1 < res).then(|| k + res),
+                    (j > 0).then(|| k - 1),
+                    (j + 1 < res).then(|| k + 1),
+                ];
+                for n in neighbors.into_iter().flatten() {
+                    if !visited[n] && labels[n] == label {
+                        visited[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+            assert_eq!(n_reached, cells.len());
+        }
+
+        let gmix1d = GaussianMixture::new(array![1.], array![[0.]], array![[[1.]]]).unwrap();
+        assert!(gmix1d.label_grid(&array![[0., 1.]], res).is_err());
+    }
+
+    #[test]
+    fn test_gmx_display() {
+        let weights = array![0.25, 0.75];
+        let means = array![[0., 0.], [5., 5.]];
+        let covs = array![[[1., 0.], [0., 1.]], [[1., 0.], [0., 1.]]];
+        let gmix = GaussianMixture::new(weights, means, covs)
+            .expect("Gaussian mixture creation failed")
+            .heaviside_factor(0.5);
+        assert_eq!(
+            gmix.to_string(),
+            "GaussianMixture(n_clusters=2, n_features=2, weights=[0.2500, 0.7500], heaviside_factor=0.5)"
+        );
+        assert!(format!("{gmix:?}").contains("covariances"));
+    }
+
+    #[test]
+    fn test_gmx_sample_with_labels() {
+        let weights = array![0.2, 0.5, 0.3];
+        let means = array![[0., 0.], [5., 5.], [-5., 5.]];
+        let covs = array![
+            [[1., 0.], [0., 1.]],
+            [[0.5, 0.3], [0.3, 0.5]],
+            [[0.8, -0.2], [-0.2, 0.4]]
+        ];
+        let gmix = GaussianMixture::new(weights.clone(), means.clone(), covs.clone())
+            .expect("Gaussian mixture creation failed");
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let (x, labels) = gmix.sample_with_labels(3000, &mut rng);
+        assert_eq!(x.dim(), (3000, 2));
+        assert_eq!(labels.len(), 3000);
+
+        // points labeled k are closest to means[k]
+        let closest = x.rows().into_iter().map(|xi| {
+            means
+                .rows()
+                .into_iter()
+                .map(|m| (&xi - &m).mapv(|v| v * v).sum())
+                .collect::<Array1<_>>()
+                .argmin()
+                .unwrap()
+        });
+        let matching = closest.zip(labels.iter()).filter(|(c, l)| c == *l).count();
+        assert!(matching as f64 / 3000. > 0.99);
+
+        for k in 0..3 {
+            let xk = x.select(
+                Axis(0),
+                &labels
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, l)| (*l == k).then_some(i))
+                    .collect::<Vec<_>>(),
+            );
+            assert_abs_diff_eq!(xk.nrows() as f64 / 3000., weights[k], epsilon = 0.03);
+            assert_abs_diff_eq!(xk.mean_axis(Axis(0)).unwrap(), means.row(k), epsilon = 0.1);
+            let centered = &xk - &means.row(k);
+            let cov = centered.t().dot(&centered) / xk.nrows() as f64;
+            assert_abs_diff_eq!(cov, covs.index_axis(Axis(0), k), epsilon = 0.15);
+        }
+
+        // same draws without labels
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        assert_abs_diff_eq!(gmix.sample(3000, &mut rng), x);
+    }
+
+    #[test]
+    fn test_gmx_predict_ties() {
+        let weights = array![0.5, 0.5];
+        let means = array![[-1., 0.], [1., 0.]];
+        let covs = array![[[1., 0.], [0., 1.]], [[1., 0.], [0., 1.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+        let x = array![[0., 0.], [0., 3.], [-0.1, 0.], [0.1, 0.]];
+        for _ in 0..10 {
+            assert_eq!(gmix.predict(&x), array![0, 0, 0, 1]);
+            assert_eq!(gmix.try_predict(&x).unwrap(), array![0, 0, 0, 1]);
+        }
+
+        let x = array![[0., 0.], [f64::NAN, 0.]];
+        assert_eq!(gmix.predict(&x), array![0, 0]);
+        assert!(matches!(
+            gmix.try_predict(&x),
+            Err(MoeError::InvalidValueError(msg)) if msg.contains("point 1")
+        ));
+        assert!(gmix.try_predict(&array![[0.]]).is_err());
+    }
+
+    #[test]
+    fn test_gmx_responsibility_entropy() {
+        let weights = array![0.5, 0.5];
+        let means = array![[-3., 0.], [3., 0.]];
+        let covs = array![[[1., 0.], [0., 1.]], [[1., 0.], [0., 1.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+        let x = array![[-3., 0.], [3., 0.], [0., 0.], [0., 2.], [100., 0.]];
+        let entropy = gmix.responsibility_entropy(&x);
+        assert_abs_diff_eq!(entropy[0], 0., epsilon = 1e-6);
+        assert_abs_diff_eq!(entropy[1], 0., epsilon = 1e-6);
+        assert_abs_diff_eq!(entropy[2], f64::ln(2.), epsilon = 1e-12);
+        assert_abs_diff_eq!(entropy[3], f64::ln(2.), epsilon = 1e-12);
+        // far away point with a vanishing probability for the first cluster
+        assert_abs_diff_eq!(entropy[4], 0., epsilon = 1e-12);
+        assert!(entropy
+            .iter()
+            .all(|e| (0. ..=f64::ln(2.) + 1e-12).contains(e)));
+    }
+
+    #[test]
+    fn test_gmx_tied_covariance() {
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        // Clusters with same correlated shape at different locations
+        let chol = array![[1., 0.], [0.8, 0.6]];
+        let blob1 =
+            Array2::random_using((150, 2), Normal::new(0., 1.).unwrap(), &mut rng).dot(&chol.t());
+        let blob2 = Array2::random_using((150, 2), Normal::new(0., 1.).unwrap(), &mut rng)
+            .dot(&chol.t())
+            + array![6., 0.];
+        let x = concatenate![Axis(0), blob1, blob2];
+
+        let weights = array![0.5, 0.5];
+        let means = array![[1., 1.], [5., -1.]];
+        let covs = array![[[1., 0.], [0., 1.]], [[2., 0.], [0., 2.]]];
+        let gmix =
+            GaussianMixture::new(weights, means, covs).expect("Gaussian mixture creation failed");
+
+        let tied = gmix
+            .covariance_type(CovarianceType::Tied)
+            .expect("Tied covariance");
+        assert_abs_diff_eq!(
+            tied.covariances().index_axis(Axis(0), 0),
+            array![[1.5, 0.], [0., 1.5]]
+        );
+        assert_abs_diff_eq!(
+            tied.covariances().index_axis(Axis(0), 1),
+            array![[1.5, 0.], [0., 1.5]]
+        );
+
+        let full = gmix.em(&x, 100, 1e-8, None).expect("EM failed");
+        let tied = tied.em(&x, 100, 1e-8, Some(50)).expect("EM failed");
+        let cov = tied.covariances();
+        assert_abs_diff_eq!(cov.index_axis(Axis(0), 0), cov.index_axis(Axis(0), 1));
+        assert_abs_diff_eq!(
+            cov.index_axis(Axis(0), 0),
+            array![[1., 0.8], [0.8, 1.]],
+            epsilon = 0.2
+        );
+        assert!(tied.bic(&x) < full.bic(&x));
+    }
+
+    #[test]
+    fn test_gmx_cached_precisions() {
+        let weights = array![0.5, 0.5];
+        let means = array![[0., 0.], [4., 4.]];
+        let covs = array![[[3., 0.], [0., 3.]], [[3., 0.], [0., 3.]]];
+        let gmix = GaussianMixture::new(weights, means, covs)
+            .expect("Gaussian mixture creation failed")
+            .heaviside_factor(0.99);
+        let x = Array::linspace(-1., 5., 10).into_shape((5, 2)).unwrap();
+
+        // uncached computation
+        let uncached = |g: &GaussianMixture<f64>| {
+            let precs = &g.precisions_chol * g.heaviside_factor.powf(-0.5);
+            let mut log_prob = Array2::zeros((x.nrows(), g.n_clusters()));
+            for (k, mu) in g.means.rows().into_iter().enumerate() {
+                let diff = (&x - &mu).dot(&precs.index_axis(Axis(0), k));
+                log_prob
+                    .column_mut(k)
+                    .assign(&diff.mapv(|v| v * v).sum_axis(Axis(1)));
+            }
+            let cst = 2. * f64::ln(2. * std::f64::consts::PI);
+            log_prob.mapv(|v| -0.5 * (v + cst)) + &g.log_det
+        };
+        // first call fills the cache, second call uses it
+        let first = gmix.compute_log_gaussian_prob(&x);
+        let second = gmix.compute_log_gaussian_prob(&x);
+        assert_eq!(first, uncached(&gmix));
+        assert_eq!(first, second);
+
+        // cache is invalidated when heaviside factor changes
+        let gmix = gmix.heaviside_factor(0.5);
+        assert_eq!(gmix.compute_log_gaussian_prob(&x), uncached(&gmix));
+        assert_abs_diff_ne!(gmix.compute_log_gaussian_prob(&x), first, epsilon = 1e-6);
+    }
+
     fn test_case(
         means: Array2<f64>,
         covariances: Array3<f64>,
@@ -366,7 +1797,7 @@ mod tests {
         x: Array1<f64>,
     ) {
         let part = 1. / means.nrows() as f64;
-        let weights = Array::from_elem((means.len(),), part);
+        let weights = Array::from_elem((means.nrows(),), part);
         let mvn = GaussianMixture::new(weights, means, covariances).unwrap();
         assert_abs_diff_eq!(expected, mvn.pdfs(&x));
     }
@@ -17,7 +17,7 @@ use rand_xoshiro::Xoshiro256Plus;
 #[cfg(feature = "serializable")]
 use serde::{Deserialize, Serialize};
 
-pub use egobox_gp::{Inducings, SparseMethod, ThetaTuning};
+pub use egobox_gp::{Inducings, Scaler, SparseMethod, ThetaTuning};
 
 #[derive(Clone)]
 #[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
@@ -31,6 +31,34 @@ pub enum GpType<F: Float> {
     },
 }
 
+/// Default maximum number of EM iterations of the gaussian mixture fit
+pub const GMM_MAX_ITER: usize = 100;
+/// Default log-likelihood change threshold of the gaussian mixture fit
+pub const GMM_TOL: f64 = 1e-3;
+/// Default number of initializations of the gaussian mixture fit
+pub const GMM_N_INIT: usize = 20;
+
+#[cfg(feature = "serializable")]
+fn default_gmm_max_iter() -> usize {
+    GMM_MAX_ITER
+}
+
+#[cfg(feature = "serializable")]
+fn default_gmm_tol<F: Float>() -> F {
+    F::cast(GMM_TOL)
+}
+
+#[cfg(feature = "serializable")]
+fn default_gmm_n_init() -> usize {
+    GMM_N_INIT
+}
+
+/// Default threshold on the sum of a cluster responsibilities under which
+/// the cluster is declared empty: `10 * F::epsilon()`
+pub fn default_gmm_empty_cluster_threshold<F: Float>() -> F {
+    F::cast(10.) * F::epsilon()
+}
+
 /// Mixture of experts checked parameters
 #[derive(Clone)]
 #[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
@@ -39,6 +67,35 @@ pub struct GpMixtureValidParams<F: Float> {
     gp_type: GpType<F>,
     /// Number of clusters (i.e. number of experts)
     n_clusters: usize,
+    /// Method used to select the number of clusters in automatic mode
+    #[cfg_attr(feature = "serializable", serde(default))]
+    cluster_selection: ClusterSelection,
+    /// Minimum number of training points per cluster, smaller clusters are removed
+    #[cfg_attr(feature = "serializable", serde(default))]
+    min_cluster_size: usize,
+    /// Number of PLS components of the space where clustering is done
+    cluster_in_pls_space: Option<usize>,
+    /// Initialization method of the gaussian mixture
+    #[cfg_attr(feature = "serializable", serde(default))]
+    gmm_init: GmmInit,
+    /// Space where the gaussian mixture is fitted
+    #[cfg_attr(feature = "serializable", serde(default))]
+    clustering_space: ClusteringSpace,
+    /// Maximum number of EM iterations of the gaussian mixture fit
+    #[cfg_attr(feature = "serializable", serde(default = "default_gmm_max_iter"))]
+    gmm_max_iter: usize,
+    /// Log-likelihood change under which EM iterations of the gaussian mixture fit are stopped
+    #[cfg_attr(feature = "serializable", serde(default = "default_gmm_tol"))]
+    gmm_tol: F,
+    /// Number of initializations of the gaussian mixture fit, the best one being kept
+    #[cfg_attr(feature = "serializable", serde(default = "default_gmm_n_init"))]
+    gmm_n_init: usize,
+    /// Sum of responsibilities under which a cluster of the gaussian mixture fit is declared empty
+    #[cfg_attr(
+        feature = "serializable",
+        serde(default = "default_gmm_empty_cluster_threshold")
+    )]
+    gmm_empty_cluster_threshold: F,
     /// [Recombination] mode
     recombination: Recombination<F>,
     /// Specification of GP regression models to be used
@@ -50,6 +107,9 @@ pub struct GpMixtureValidParams<F: Float> {
     /// Number of PLS components, should be used when problem size
     /// is over ten variables or so.
     kpls_dim: Option<usize>,
+    /// Categorical inputs given as (first column, number of levels) of one-hot encoded columns
+    #[cfg_attr(feature = "serializable", serde(default))]
+    categorical_inputs: Vec<(usize, usize)>,
     /// Number of GP hyperparameters optimization restarts
     n_start: usize,
     /// Gaussian Mixture model used to cluster
@@ -65,11 +125,21 @@ impl<F: Float> Default for GpMixtureValidParams<F> {
         GpMixtureValidParams {
             gp_type: GpType::FullGp,
             n_clusters: 1,
+            cluster_selection: ClusterSelection::default(),
+            min_cluster_size: 0,
+            cluster_in_pls_space: None,
+            gmm_init: GmmInit::default(),
+            clustering_space: ClusteringSpace::default(),
+            gmm_max_iter: GMM_MAX_ITER,
+            gmm_tol: F::cast(GMM_TOL),
+            gmm_n_init: GMM_N_INIT,
+            gmm_empty_cluster_threshold: default_gmm_empty_cluster_threshold(),
             recombination: Recombination::Hard,
             regression_spec: RegressionSpec::CONSTANT,
             correlation_spec: CorrelationSpec::SQUAREDEXPONENTIAL,
             theta_tunings: vec![ThetaTuning::default()],
             kpls_dim: None,
+            categorical_inputs: vec![],
             n_start: 10,
             gmm: None,
             gmx: None,
@@ -89,6 +159,51 @@ impl<F: Float> GpMixtureValidParams<F> {
         self.n_clusters
     }
 
+    /// The method used to select the number of clusters in automatic mode
+    pub fn cluster_selection(&self) -> ClusterSelection {
+        self.cluster_selection
+    }
+
+    /// The minimum number of training points per cluster
+    pub fn min_cluster_size(&self) -> usize {
+        self.min_cluster_size
+    }
+
+    /// The optional number of PLS components of the clustering space
+    pub fn cluster_in_pls_space(&self) -> Option<usize> {
+        self.cluster_in_pls_space
+    }
+
+    /// The initialization method of the gaussian mixture
+    pub fn gmm_init(&self) -> GmmInit {
+        self.gmm_init
+    }
+
+    /// The space where the gaussian mixture is fitted
+    pub fn clustering_space(&self) -> ClusteringSpace {
+        self.clustering_space
+    }
+
+    /// The maximum number of EM iterations of the gaussian mixture fit
+    pub fn gmm_max_iter(&self) -> usize {
+        self.gmm_max_iter
+    }
+
+    /// The log-likelihood change threshold stopping EM iterations of the gaussian mixture fit
+    pub fn gmm_tol(&self) -> F {
+        self.gmm_tol
+    }
+
+    /// The number of initializations of the gaussian mixture fit
+    pub fn gmm_n_init(&self) -> usize {
+        self.gmm_n_init
+    }
+
+    /// The sum of responsibilities under which a cluster of the gaussian mixture fit is declared empty
+    pub fn gmm_empty_cluster_threshold(&self) -> F {
+        self.gmm_empty_cluster_threshold
+    }
+
     /// The recombination mode
     pub fn recombination(&self) -> Recombination<F> {
         self.recombination
@@ -114,6 +229,11 @@ impl<F: Float> GpMixtureValidParams<F> {
         self.kpls_dim
     }
 
+    /// The categorical inputs as (first column, number of levels) of one-hot encoded columns
+    pub fn categorical_inputs(&self) -> &[(usize, usize)] {
+        &self.categorical_inputs
+    }
+
     /// The number of hypermarameters optimization restarts
     pub fn n_start(&self) -> usize {
         self.n_start
@@ -163,11 +283,21 @@ impl<F: Float> GpMixtureParams<F> {
         Self(GpMixtureValidParams {
             gp_type,
             n_clusters: 1,
+            cluster_selection: ClusterSelection::default(),
+            min_cluster_size: 0,
+            cluster_in_pls_space: None,
+            gmm_init: GmmInit::default(),
+            clustering_space: ClusteringSpace::default(),
+            gmm_max_iter: GMM_MAX_ITER,
+            gmm_tol: F::cast(GMM_TOL),
+            gmm_n_init: GMM_N_INIT,
+            gmm_empty_cluster_threshold: default_gmm_empty_cluster_threshold(),
             recombination: Recombination::Smooth(Some(F::one())),
             regression_spec: RegressionSpec::CONSTANT,
             correlation_spec: CorrelationSpec::SQUAREDEXPONENTIAL,
             theta_tunings: vec![ThetaTuning::default()],
             kpls_dim: None,
+            categorical_inputs: vec![],
             n_start: 10,
             gmm: None,
             gmx: None,
@@ -187,6 +317,98 @@ impl<F: Float> GpMixtureParams<F> {
         self
     }
 
+    /// Sets the method used to select the number of clusters in automatic mode
+    /// (i.e. when `n_clusters` is set to 0)
+    pub fn cluster_selection(mut self, cluster_selection: ClusterSelection) -> Self {
+        self.0.cluster_selection = cluster_selection;
+        self
+    }
+
+    /// Sets the minimum number of training points per cluster.
+    ///
+    /// Before training experts, clusters with fewer training points are removed
+    /// one at a time (smallest first) from the gaussian mixture, their points being reassigned
+    /// to the clusters with the highest responsabilities.
+    /// Default to 0 meaning no cluster is removed.
+    pub fn min_cluster_size(mut self, min_cluster_size: usize) -> Self {
+        self.0.min_cluster_size = min_cluster_size;
+        self
+    }
+
+    /// Sets the number of PLS components of the space where clustering is done.
+    ///
+    /// When set, training inputs are projected onto the first PLS components
+    /// (computed from the training data) and the gaussian mixture is fitted in
+    /// that reduced space while experts are still trained in the original input space.
+    /// Useful when the input dimension is high while the function structure lies in
+    /// a low dimensional subspace. Default to None meaning clustering in the original input space.
+    pub fn cluster_in_pls_space(mut self, n_components: Option<usize>) -> Self {
+        self.0.cluster_in_pls_space = n_components;
+        self
+    }
+
+    /// Sets the initialization method of the gaussian mixture used to cluster training data.
+    ///
+    /// The initialization is driven by the random generator set with
+    /// [`with_rng()`](GpMixtureParams::with_rng), hence seeded fits are reproducible.
+    /// Default to [GmmInit::KMeans].
+    pub fn gmm_init(mut self, gmm_init: GmmInit) -> Self {
+        self.0.gmm_init = gmm_init;
+        self
+    }
+
+    /// Sets the space where the gaussian mixture used to cluster training data is fitted.
+    ///
+    /// [ClusteringSpace::StandardizedJoint] makes experts align with regions of similar
+    /// output behavior whatever the scales of inputs and output.
+    /// Default to [ClusteringSpace::Joint].
+    pub fn clustering_space(mut self, clustering_space: ClusteringSpace) -> Self {
+        self.0.clustering_space = clustering_space;
+        self
+    }
+
+    /// Sets the maximum number of EM iterations of the gaussian mixture fit.
+    ///
+    /// Fitting fails if EM does not converge within `max_iter` iterations.
+    /// Default to [GMM_MAX_ITER].
+    pub fn gmm_max_iter(mut self, max_iter: usize) -> Self {
+        self.0.gmm_max_iter = max_iter;
+        self
+    }
+
+    /// Sets the threshold on the change of the log-likelihood lower bound
+    /// under which EM iterations of the gaussian mixture fit are stopped.
+    ///
+    /// A larger tolerance stops EM sooner at the price of a less accurate clustering.
+    /// Default to [GMM_TOL].
+    pub fn gmm_tol(mut self, tol: F) -> Self {
+        self.0.gmm_tol = tol;
+        self
+    }
+
+    /// Sets the number of initializations of the gaussian mixture fit.
+    ///
+    /// EM is run from `n_init` initializations (see [GpMixtureParams::gmm_init])
+    /// and the mixture with the highest log-likelihood lower bound is kept,
+    /// which makes the clustering less sensitive to bad EM local optima.
+    /// Default to [GMM_N_INIT].
+    pub fn gmm_n_init(mut self, n_init: usize) -> Self {
+        self.0.gmm_n_init = n_init;
+        self
+    }
+
+    /// Sets the threshold on the sum of a cluster responsibilities (i.e. its weight times
+    /// the number of clustered points) under which the gaussian mixture fit fails
+    /// with an [MoeError::EmptyCluster] error.
+    ///
+    /// Note: when a threshold below the default one is set and the gaussian mixture fit
+    /// finds an empty cluster, EM is run again from a k-means clustering with this threshold.
+    /// Default to [default_gmm_empty_cluster_threshold].
+    pub fn gmm_empty_cluster_threshold(mut self, threshold: F) -> Self {
+        self.0.gmm_empty_cluster_threshold = threshold;
+        self
+    }
+
     /// Sets the recombination mode
     pub fn recombination(mut self, recombination: Recombination<F>) -> Self {
         self.0.recombination = recombination;
@@ -196,7 +418,8 @@ impl<F: Float> GpMixtureParams<F> {
     /// Sets the regression models used in the mixture.
     ///
     /// Only GP models with regression models allowed by this specification
-    /// will be used in the mixture.  
+    /// will be used in the mixture. When several models are allowed, the best one
+    /// is selected independently for each expert using cross-validation on its cluster.
     pub fn regression_spec(mut self, regression_spec: RegressionSpec) -> Self {
         self.0.regression_spec = regression_spec;
         self
@@ -205,7 +428,8 @@ impl<F: Float> GpMixtureParams<F> {
     /// Sets the correlation models used in the mixture.
     ///
     /// Only GP models with correlation models allowed by this specification
-    /// will be used in the mixture.  
+    /// will be used in the mixture. When several models are allowed, the best one
+    /// is selected independently for each expert using cross-validation on its cluster.
     pub fn correlation_spec(mut self, correlation_spec: CorrelationSpec) -> Self {
         self.0.correlation_spec = correlation_spec;
         self
@@ -217,6 +441,13 @@ impl<F: Float> GpMixtureParams<F> {
         self
     }
 
+    /// Sets the categorical inputs of the experts given as (first column, number of levels)
+    /// of their one-hot encoded columns, see [`egobox_gp::GpParams::categorical_inputs`].
+    pub fn categorical_inputs(mut self, categorical_inputs: Vec<(usize, usize)>) -> Self {
+        self.0.categorical_inputs = categorical_inputs;
+        self
+    }
+
     /// Set theta hyper parameter tuning
     pub fn theta_tunings(mut self, theta_tunings: &[ThetaTuning<F>]) -> Self {
         self.0.theta_tunings = theta_tunings.to_vec();
@@ -264,6 +495,26 @@ impl<F: Float> ParamGuard for GpMixtureParams<F> {
                 ));
             }
         }
+        if self.0.gmm_max_iter == 0 {
+            return Err(MoeError::InvalidValueError(
+                "`gmm_max_iter` should be strictly positive".to_string(),
+            ));
+        }
+        if self.0.gmm_tol <= F::zero() {
+            return Err(MoeError::InvalidValueError(
+                "`gmm_tol` should be strictly positive".to_string(),
+            ));
+        }
+        if self.0.gmm_n_init == 0 {
+            return Err(MoeError::InvalidValueError(
+                "`gmm_n_init` should be strictly positive".to_string(),
+            ));
+        }
+        if self.0.gmm_empty_cluster_threshold < F::zero() {
+            return Err(MoeError::InvalidValueError(
+                "`gmm_empty_cluster_threshold` should be nonnegative".to_string(),
+            ));
+        }
         if self.0.n_clusters > 1 && self.0.theta_tunings.len() == 1 {
         } else if self.0.n_clusters > 0 && self.0.n_clusters != self.0.theta_tunings.len() {
             panic!("Number of clusters (={}) and theta init size (={}) not compatible, should be equal", 
@@ -1,17 +1,26 @@
 use crate::errors::Result;
+#[cfg(feature = "persistent")]
 use crate::types::GpFileFormat;
+use crate::types::{Dtype, ValidationMetrics};
+use egobox_doe::{Lhs, SamplingMethod};
+use egobox_gp::metrics::CrossValScore;
 use egobox_gp::{
-    correlation_models::*, mean_models::*, GaussianProcess, GpParams, SgpParams,
+    correlation_models::*, mean_models::*, GaussianProcess, GpParams, Inducings, Scaler, SgpParams,
     SparseGaussianProcess, SparseMethod, ThetaTuning,
 };
 use linfa::prelude::{Dataset, Fit};
-use ndarray::{Array1, Array2, ArrayView2, Axis};
+use linfa::ParamGuard;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, ArrayViewMut1, Axis, CowArray, Ix2, Zip};
+use ndarray_rand::rand::SeedableRng;
 use paste::paste;
+use rand_xoshiro::Xoshiro256Plus;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 #[cfg(feature = "serializable")]
 use serde::{Deserialize, Serialize};
 
-#[cfg(feature = "persistent")]
 use crate::MoeError;
 #[cfg(feature = "persistent")]
 use std::fs;
@@ -21,14 +30,98 @@ use std::io::Write;
 pub trait GpSurrogateParams {
     /// Set theta
     fn theta_tuning(&mut self, theta_tuning: ThetaTuning<f64>);
+    /// Set theta to given values without hyperparameters optimization,
+    /// the process variance is still estimated from training data.
+    /// Theta length has to match the input dimension (or the PLS dimension if any)
+    /// otherwise training fails.
+    fn fixed_theta(&mut self, theta: Vec<f64>) {
+        self.theta_tuning(ThetaTuning::Fixed(theta))
+    }
     /// Set the number of PLS components
     fn kpls_dim(&mut self, kpls_dim: Option<usize>);
     /// Set the nuber of internal optimization restarts
     fn n_start(&mut self, n_start: usize);
     /// Set the nugget parameter to improve numerical stability
     fn nugget(&mut self, nugget: f64);
+    /// Set the noise variances of training observations
+    fn observation_noise(&mut self, noise: Array1<f64>);
+    /// Set the importance weights of training observations scaling their nugget and noise variances
+    fn sample_weights(&mut self, weights: Array1<f64>);
+    /// Enable the initial theta guess from training data inter-point distances
+    fn theta_init_from_data(&mut self, enabled: bool);
+    /// Set the distance under which training points are merged as duplicates
+    fn merge_duplicates(&mut self, tol: f64);
+    /// Set the scaling of training inputs
+    fn scaler(&mut self, scaler: Scaler);
+    /// Set whether an infeasible PLS dimension reduction is an error instead of being adjusted
+    fn strict_kpls(&mut self, strict: bool);
+    /// Set the categorical inputs given as (first column, number of levels) of one-hot encoded columns
+    fn categorical_inputs(&mut self, categorical_inputs: Vec<(usize, usize)>);
     /// Train the surrogate
     fn train(&self, x: &ArrayView2<f64>, y: &ArrayView2<f64>) -> Result<Box<dyn FullGpSurrogate>>;
+    /// Train a surrogate for each output (column) of `y` given as a (n, n_outputs) matrix.
+    /// Default implementation trains independent surrogates.
+    fn train_outputs(
+        &self,
+        x: &ArrayView2<f64>,
+        y: &ArrayView2<f64>,
+    ) -> Result<MultiOutputSurrogate> {
+        let outputs = y
+            .columns()
+            .into_iter()
+            .map(|yk| self.train(x, &yk.insert_axis(Axis(1))))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(MultiOutputSurrogate { outputs })
+    }
+    /// Train a surrogate for each output (column) of `y` given as a (n, n_outputs) matrix
+    /// where `weights` (n_outputs,) gives the contribution of each output to shared
+    /// hyperparameters optimization if any (see [MultiOutputSurrogate::weighted_likelihood]).
+    /// Default implementation trains independent surrogates which are not affected by the weights.
+    ///
+    /// Returns an error if weights length is not n_outputs or if weights are negative or all zero.
+    fn train_weighted_outputs(
+        &self,
+        x: &ArrayView2<f64>,
+        y: &ArrayView2<f64>,
+        weights: &ArrayView1<f64>,
+    ) -> Result<MultiOutputSurrogate> {
+        check_output_weights(y, weights)?;
+        self.train_outputs(x, y)
+    }
+}
+
+/// Check test points `x` (n, xdim) and outputs `y` (n, 1) are consistent and not empty
+fn check_test_data(x: &ArrayView2<f64>, y: &ArrayView2<f64>) -> Result<()> {
+    if y.dim() != (x.nrows(), 1) {
+        return Err(MoeError::InvalidValueError(format!(
+            "Test outputs should be a ({}, 1) matrix, got {:?}",
+            x.nrows(),
+            y.dim()
+        )));
+    }
+    if x.nrows() == 0 {
+        return Err(MoeError::InvalidValueError(
+            "At least one test point is required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check output weights are consistent with outputs `y` (n, n_outputs)
+fn check_output_weights(y: &ArrayView2<f64>, weights: &ArrayView1<f64>) -> Result<()> {
+    if weights.len() != y.ncols() {
+        return Err(MoeError::InvalidValueError(format!(
+            "Output weights length should be {}, got {}",
+            y.ncols(),
+            weights.len()
+        )));
+    }
+    if weights.iter().any(|w| *w < 0. || w.is_nan()) || weights.sum() <= 0. {
+        return Err(MoeError::InvalidValueError(format!(
+            "Output weights should be non negative and not all zero, got {weights}"
+        )));
+    }
+    Ok(())
 }
 
 /// A trait for sparse GP surrogate parameters to build surrogate.
@@ -39,11 +132,28 @@ pub trait SgpSurrogateParams: GpSurrogateParams {
     fn seed(&mut self, seed: Option<u64>);
 }
 
+/// Number of rows of the chunks of points predicted in parallel (see [GpSurrogate::predict_par])
+pub const PREDICT_CHUNK_SIZE: usize = 1000;
+
+/// A closure predicting the output value at a single point (see [GpSurrogate::as_value_fn])
+pub type ValueFn<'a> = Box<dyn Fn(&ArrayView1<f64>) -> f64 + 'a>;
+
 /// A trait for a base GP surrogate
 #[cfg_attr(feature = "serializable", typetag::serde(tag = "type"))]
 pub trait GpSurrogate: std::fmt::Display + Sync + Send {
     /// Returns input/output dims
     fn dims(&self) -> (usize, usize);
+    /// Kind of the surrogate as `<Mean>_<Correlation>` (e.g. `"Constant_Matern52"`)
+    /// without PLS decoration, see [make_surrogate_params_from_kind].
+    /// Sparse GP surrogates kind is `Sgp_<Correlation>` (e.g. `"Sgp_Matern52"`),
+    /// see [make_sgp_surrogate_params_from_kind].
+    fn kind(&self) -> String;
+    /// PLS rotation matrix (xdim, kpls_dim) giving the linear combinations of inputs
+    /// used as reduced inputs, `None` when PLS dimension reduction is not used.
+    /// Default implementation returns `None`.
+    fn pls_rotation(&self) -> Option<Array2<f64>> {
+        None
+    }
     /// Predict output values at n points given as (n, xdim) matrix.
     #[deprecated(since = "0.17.0", note = "renamed predict")]
     fn predict_values(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
@@ -51,13 +161,373 @@ pub trait GpSurrogate: std::fmt::Display + Sync + Send {
     }
     /// Predict output values at n points given as a vector (n,)..
     fn predict(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>>;
+    /// Predict output values at n points given as (n, xdim) matrix.
+    /// When `parallel` feature is enabled, points are split in chunks of [PREDICT_CHUNK_SIZE] rows
+    /// predicted in parallel, otherwise it is equivalent to [GpSurrogate::predict].
+    fn predict_par(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
+        #[cfg(feature = "parallel")]
+        if x.nrows() > PREDICT_CHUNK_SIZE {
+            let preds = x
+                .axis_chunks_iter(Axis(0), PREDICT_CHUNK_SIZE)
+                .into_par_iter()
+                .map(|xc| self.predict(&xc))
+                .collect::<Result<Vec<_>>>()?;
+            let preds: Vec<_> = preds.iter().map(|p| p.view()).collect();
+            return Ok(ndarray::concatenate(Axis(0), &preds)?);
+        }
+        self.predict(x)
+    }
+    /// Predict output values at n points given as (n, xdim) matrix into the given (n,) `out` buffer
+    /// (e.g. reused between calls in a prediction loop).
+    /// Returns an [MoeError::InvalidValueError] if `out` length is not n.
+    /// Default implementation assigns the result of [GpSurrogate::predict], GP surrogates
+    /// override it to write predictions directly into `out`.
+    fn predict_into(&self, x: &ArrayView2<f64>, out: &mut ArrayViewMut1<f64>) -> Result<()> {
+        if out.len() != x.nrows() {
+            return Err(MoeError::InvalidValueError(format!(
+                "Output buffer length should be {}, got {}",
+                x.nrows(),
+                out.len()
+            )));
+        }
+        out.assign(&self.predict(x)?);
+        Ok(())
+    }
+    /// Predict output values (n,) at n points given as (n, xdim) matrix after clamping
+    /// points outside the box given as a (xdim, 2) matrix of `xlimits` [lower, upper] to the box boundary.
+    /// Returns as well a boolean vector (n,) flagging the clamped points.
+    fn predict_clamped(
+        &self,
+        x: &ArrayView2<f64>,
+        xlimits: &ArrayView2<f64>,
+    ) -> Result<(Array1<f64>, Array1<bool>)> {
+        if xlimits.dim() != (x.ncols(), 2) {
+            return Err(MoeError::InvalidValueError(format!(
+                "xlimits shape should be ({}, 2), got {:?}",
+                x.ncols(),
+                xlimits.dim()
+            )));
+        }
+        let mut xc = x.to_owned();
+        let clamped = Zip::from(xc.rows_mut()).map_collect(|mut xi| {
+            let mut clamped = false;
+            Zip::from(&mut xi)
+                .and(xlimits.rows())
+                .for_each(|v, limits| {
+                    let c = v.clamp(limits[0], limits[1]);
+                    clamped |= c != *v;
+                    *v = c;
+                });
+            clamped
+        });
+        Ok((self.predict(&xc.view())?, clamped))
+    }
     /// Predict variance values at n points given as (n, xdim) matrix.
     fn predict_var(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>>;
+    /// Training inputs (n, xdim) and outputs (n, 1) the surrogate was fitted on,
+    /// borrowed unless converted from another precision.
+    fn training_data(&self) -> (CowArray<'_, f64, Ix2>, CowArray<'_, f64, Ix2>);
+    /// Predict both output values (n,) and variance values (n, 1) at n points given as (n, xdim) matrix.
+    fn predict_valvar(&self, x: &ArrayView2<f64>) -> Result<(Array1<f64>, Array2<f64>)> {
+        Ok((self.predict(x)?, self.predict_var(x)?))
+    }
+    /// Predict the prior mean (n,) at n points given as (n, xdim) matrix, i.e. the trend the
+    /// prediction reverts to far from training points.
+    /// Default implementation returns an error as the prior is not available.
+    fn prior_mean(&self, _x: &ArrayView2<f64>) -> Result<Array1<f64>> {
+        Err(MoeError::InvalidValueError(format!(
+            "Prior mean not available for {self} surrogate"
+        )))
+    }
+    /// Returns the prior variance of the process, the predicted variance far from training points
+    /// being the prior variance plus the variance of the trend estimation if any.
+    /// Default implementation returns an error as the prior is not available.
+    fn prior_variance(&self) -> Result<f64> {
+        Err(MoeError::InvalidValueError(format!(
+            "Prior variance not available for {self} surrogate"
+        )))
+    }
+    /// Predict variance values at n points given as (n, xdim) matrix split in the epistemic (model)
+    /// part (n, 1) and the aleatoric (observation noise) part (n, 1) whose sum is the predicted variance
+    /// of a noisy observation.
+    /// Default implementation considers the predicted variance as entirely epistemic.
+    fn predict_variance_components(
+        &self,
+        x: &ArrayView2<f64>,
+    ) -> Result<(Array2<f64>, Array2<f64>)> {
+        let var = self.predict_var(x)?;
+        let noise = Array2::zeros(var.raw_dim());
+        Ok((var, noise))
+    }
+    /// Predict lower and upper bounds (n, 1) of the confidence interval at given `level`
+    /// (e.g. 0.95) at n points given as (n, xdim) matrix.
+    /// Returns an error if `level` is not in ]0, 1[.
+    fn predict_confidence_interval(
+        &self,
+        x: &ArrayView2<f64>,
+        level: f64,
+    ) -> Result<(Array2<f64>, Array2<f64>)> {
+        if !(level > 0. && level < 1.) {
+            return Err(MoeError::InvalidValueError(format!(
+                "Confidence level should be in ]0, 1[, got {level}"
+            )));
+        }
+        let (mean, var) = self.predict_valvar(x)?;
+        let z = norm_quantile(0.5 + level / 2.);
+        let half_width = var.mapv(|v| z * v.sqrt());
+        let mean = mean.insert_axis(Axis(1));
+        Ok((&mean - &half_width, &mean + &half_width))
+    }
+    /// Returns relevance of each input dimension derived from the fitted hyperparameters
+    /// (normalized to sum to one): a near-zero value indicates an input with little influence.
+    /// Default implementation returns an empty array as relevance is not available.
+    fn relevance(&self) -> Array1<f64> {
+        Array1::zeros(0)
+    }
+    /// Returns an estimate of the condition number of the training covariance matrix,
+    /// a huge value denoting a nearly singular matrix.
+    /// Default implementation returns NaN as the estimate is not available.
+    fn condition_number(&self) -> f64 {
+        f64::NAN
+    }
+    /// Returns a closure predicting the output value at a single point
+    /// (e.g. to be passed to a generic optimizer).
+    /// The closure borrows the surrogate and panics if the prediction fails
+    /// (e.g. wrong input dimension).
+    fn as_value_fn(&self) -> ValueFn<'_> {
+        Box::new(move |x| {
+            self.predict(&x.view().insert_axis(Axis(0)))
+                .expect("Surrogate prediction")[0]
+        })
+    }
+    /// Mean negative log predictive density of test points `x` (n, xdim) with outputs `y` (n, 1)
+    /// under the predictive gaussian distributions of the surrogate.
+    /// Contrary to mean errors, it penalizes both overconfident and underconfident variances.
+    ///
+    /// Returns an error if there is no test point.
+    fn nlpd(&self, x: &ArrayView2<f64>, y: &ArrayView2<f64>) -> Result<f64> {
+        check_test_data(x, y)?;
+        let (mean, var) = self.predict_valvar(x)?;
+        // Avoid division by zero at training points
+        let var = var.column(0).mapv(|v| v.max(f64::EPSILON));
+        let err2 = (&y.column(0) - &mean).mapv(|v| v * v);
+        let nlpd = var.mapv(|v| (2. * std::f64::consts::PI * v).ln()) + err2 / var;
+        Ok(0.5 * nlpd.sum() / x.nrows() as f64)
+    }
+    /// Validate the surrogate against test points `x` (n, xdim) with outputs `y` (n, 1)
+    /// returning RMSE, MAE, R² and the coverage of the 95% confidence interval
+    /// (close to 0.95 for a well-calibrated surrogate).
+    /// When test outputs are constant, R² is 1 for an exact prediction and 0 otherwise.
+    ///
+    /// Returns an error if there is no test point.
+    fn validate(&self, x: &ArrayView2<f64>, y: &ArrayView2<f64>) -> Result<ValidationMetrics> {
+        check_test_data(x, y)?;
+        let pred = self.predict(x)?;
+        let (lower, upper) = self.predict_confidence_interval(x, 0.95)?;
+        let y = y.column(0);
+        let err = &y - &pred;
+        let n = y.len() as f64;
+        let ss_res = err.mapv(|v| v * v).sum();
+        let rmse = (ss_res / n).sqrt();
+        let mae = err.mapv(f64::abs).sum() / n;
+        let y_mean = y.sum() / n;
+        let ss_tot = y.mapv(|v| (v - y_mean) * (v - y_mean)).sum();
+        let r2 = if ss_tot > 0. {
+            1. - ss_res / ss_tot
+        } else if ss_res == 0. {
+            1.
+        } else {
+            0.
+        };
+        let n_covered = Zip::from(&y)
+            .and(lower.column(0))
+            .and(upper.column(0))
+            .fold(0, |acc, v, lo, up| acc + usize::from(lo <= v && v <= up));
+        Ok(ValidationMetrics {
+            rmse,
+            mae,
+            r2,
+            coverage: n_covered as f64 / y.len() as f64,
+        })
+    }
+    /// Search the point of maximum predicted variance within the box `xlimits` given as
+    /// a (xdim, 2) matrix of lower and upper bounds (e.g. to pick the next point in active learning).
+    /// Pattern searches are started from `n_start` LHS points and the best point found is returned.
+    fn max_variance_point(&self, xlimits: &Array2<f64>, n_start: usize) -> Result<Array1<f64>> {
+        let nx = self.dims().0;
+        if xlimits.dim() != (nx, 2) {
+            return Err(MoeError::InvalidValueError(format!(
+                "Bounds should be a ({nx}, 2) matrix, got {:?}",
+                xlimits.dim()
+            )));
+        }
+        let range = &xlimits.column(1) - &xlimits.column(0);
+        let clip = |x: &mut Array1<f64>| {
+            Zip::from(x)
+                .and(xlimits.rows())
+                .for_each(|v, b| *v = v.max(b[0]).min(b[1]))
+        };
+        let starts = Lhs::new(xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(n_start.max(1));
+
+        let mut best = (starts.row(0).to_owned(), f64::NEG_INFINITY);
+        for start in starts.rows() {
+            let mut x = start.to_owned();
+            let mut var = self.predict_var(&x.view().insert_axis(Axis(0)))?[[0, 0]];
+            let mut step = 0.1;
+            while step > 1e-6 {
+                // Evaluate moves of +/- step along each direction
+                let mut moves = Array2::zeros((2 * nx, nx));
+                for (k, mut m) in moves.rows_mut().into_iter().enumerate() {
+                    let mut xm = x.clone();
+                    let sign = if k % 2 == 0 { 1. } else { -1. };
+                    xm[k / 2] += sign * step * range[k / 2];
+                    clip(&mut xm);
+                    m.assign(&xm);
+                }
+                let vars = self.predict_var(&moves.view())?;
+                let (k, vmax) = vars.column(0).iter().enumerate().fold(
+                    (0, f64::NEG_INFINITY),
+                    |acc, (k, &v)| if v > acc.1 { (k, v) } else { acc },
+                );
+                if vmax > var {
+                    x = moves.row(k).to_owned();
+                    var = vmax;
+                } else {
+                    step /= 2.;
+                }
+            }
+            if var > best.1 {
+                best = (x, var);
+            }
+        }
+        Ok(best.0)
+    }
+    /// Integrated predicted variance over the box `xlimits` given as a (xdim, 2) matrix
+    /// of lower and upper bounds, normalized by the box volume (i.e. the mean variance over the box).
+    /// The integral is approximated with `n_samples` LHS points drawn with the optional `seed`.
+    /// The total remaining uncertainty can be used as a stopping criterion in active learning.
+    ///
+    /// Returns an error if `n_samples` is 0.
+    fn integrated_variance(
+        &self,
+        xlimits: &Array2<f64>,
+        n_samples: usize,
+        seed: Option<u64>,
+    ) -> Result<f64> {
+        let nx = self.dims().0;
+        if xlimits.dim() != (nx, 2) {
+            return Err(MoeError::InvalidValueError(format!(
+                "Bounds should be a ({nx}, 2) matrix, got {:?}",
+                xlimits.dim()
+            )));
+        }
+        if n_samples == 0 {
+            return Err(MoeError::InvalidValueError(
+                "Number of samples should be greater than 0".to_string(),
+            ));
+        }
+        let rng = match seed {
+            Some(seed) => Xoshiro256Plus::seed_from_u64(seed),
+            None => Xoshiro256Plus::from_entropy(),
+        };
+        let x = Lhs::new(xlimits).with_rng(rng).sample(n_samples);
+        Ok(self.predict_var(&x.view())?.sum() / n_samples as f64)
+    }
+    /// Flags the points of `x` given as (n, xdim) matrix lying outside the bounding box of
+    /// the training inputs, where the prediction extrapolates and reverts to the prior.
+    /// Default implementation returns an error as training inputs are not available.
+    fn is_extrapolating(&self, _x: &ArrayView2<f64>) -> Result<Array1<bool>> {
+        Err(MoeError::InvalidValueError(format!(
+            "Extrapolation check not available for {self} surrogate"
+        )))
+    }
+    /// Reduced likelihood evaluated at each theta given as the rows of `theta_grid` (n, n_theta)
+    /// without changing the trained surrogate, useful to plot the likelihood wrt theta.
+    /// Default implementation returns an error as the likelihood profile is not available.
+    fn likelihood_profile(&self, _theta_grid: &ArrayView2<f64>) -> Result<Array1<f64>> {
+        Err(MoeError::InvalidValueError(format!(
+            "Likelihood profile not available for {self} surrogate"
+        )))
+    }
+    /// Add observation (x, y) to the trained surrogate keeping its hyperparameters unchanged.
+    /// Default implementation returns an error as incremental update is not supported.
+    fn add_observation(&mut self, _x: &ArrayView1<f64>, _y: f64) -> Result<()> {
+        Err(MoeError::InvalidValueError(format!(
+            "Incremental update not supported by {self} surrogate"
+        )))
+    }
+    /// Train a new surrogate with the same parameters as this one (regression and correlation
+    /// models, hyperparameters tuning and other fit options) on given training data (x, y)
+    /// where y is a (n, 1) matrix, theta being re-optimized.
+    /// Per-point options (e.g. observation noise or sample weights) are kept as well,
+    /// hence their length should match the new number of training points.
+    /// Default implementation returns an error as refitting is not supported.
+    fn refit(&self, _x: &Array2<f64>, _y: &Array2<f64>) -> Result<Box<dyn GpSurrogate>> {
+        Err(MoeError::InvalidValueError(format!(
+            "Refit not supported by {self} surrogate"
+        )))
+    }
+    /// Floating point precision of the surrogate computations
+    fn dtype(&self) -> Dtype {
+        Dtype::F64
+    }
     /// Save model in given file.
     #[cfg(feature = "persistent")]
     fn save(&self, path: &str, format: GpFileFormat) -> Result<()>;
 }
 
+/// Quantile function (inverse of the cumulative distribution function) of the standard normal
+/// distribution for p in ]0, 1[ using Acklam's rational approximation (relative error < 1.15e-9)
+fn norm_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+    if p < P_LOW {
+        tail((-2. * p.ln()).sqrt())
+    } else if p <= 1. - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    } else {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    }
+}
+
 /// A trait for a GP surrogate with derivatives predictions and sampling
 #[cfg_attr(feature = "serializable", typetag::serde(tag = "type"))]
 pub trait GpSurrogateExt {
@@ -78,6 +548,15 @@ pub trait GpParameterized {
     fn variance(&self) -> f64;
     fn noise_variance(&self) -> f64;
     fn likelihood(&self) -> f64;
+    /// Number of likelihood evaluations used to optimize hyperparameters at training
+    /// (zero when hyperparameters are fixed or when not tracked)
+    fn likelihood_evals(&self) -> usize {
+        0
+    }
+    /// Number of PLS components if any
+    fn kpls_dim(&self) -> Option<usize>;
+    /// Leave-one-out cross validation score (see [egobox_gp::metrics::CrossValScore])
+    fn loocv_score(&self) -> f64;
 }
 
 /// A trait for a GP surrogate.
@@ -88,10 +567,73 @@ pub trait FullGpSurrogate: GpParameterized + GpSurrogate + GpSurrogateExt {}
 #[cfg_attr(feature = "serializable", typetag::serde(tag = "type"))]
 pub trait SgpSurrogate: FullGpSurrogate {}
 
+/// A surrogate of a function with several outputs made of one GP surrogate per output.
+/// See [`GpSurrogateParams::train_outputs`].
+#[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+pub struct MultiOutputSurrogate {
+    outputs: Vec<Box<dyn FullGpSurrogate>>,
+}
+
+impl MultiOutputSurrogate {
+    /// Number of outputs
+    pub fn n_outputs(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Surrogates of each output
+    pub fn outputs(&self) -> &[Box<dyn FullGpSurrogate>] {
+        &self.outputs
+    }
+
+    /// Predict output values at n points given as (n, xdim) matrix.
+    /// Returns a (n, n_outputs) matrix.
+    pub fn predict(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
+        let mut y = Array2::zeros((x.nrows(), self.n_outputs()));
+        for (mut yk, output) in y.columns_mut().into_iter().zip(self.outputs.iter()) {
+            yk.assign(&output.predict(x)?);
+        }
+        Ok(y)
+    }
+
+    /// Predict variance values at n points given as (n, xdim) matrix.
+    /// Returns a (n, n_outputs) matrix.
+    pub fn predict_var(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
+        let mut var = Array2::zeros((x.nrows(), self.n_outputs()));
+        for (mut vk, output) in var.columns_mut().into_iter().zip(self.outputs.iter()) {
+            vk.assign(&output.predict_var(x)?.column(0));
+        }
+        Ok(var)
+    }
+
+    /// Combined likelihood of the surrogates of each output weighted by `weights` (n_outputs,)
+    /// that is the objective maximized by [GpSurrogateParams::train_weighted_outputs]
+    /// when hyperparameters are shared.
+    pub fn weighted_likelihood(&self, weights: &ArrayView1<f64>) -> Result<f64> {
+        if weights.len() != self.n_outputs() {
+            return Err(MoeError::InvalidValueError(format!(
+                "Output weights length should be {}, got {}",
+                self.n_outputs(),
+                weights.len()
+            )));
+        }
+        Ok(self
+            .outputs
+            .iter()
+            .zip(weights.iter())
+            .map(|(output, w)| w * output.likelihood())
+            .sum())
+    }
+}
+
 /// A macro to declare GP surrogate using regression model and correlation model names.
 ///
 /// Regression model is either `Constant`, `Linear` or `Quadratic`.
-/// Correlation model is either `SquaredExponential`, `AbsoluteExponential`, `Matern32` or `Matern52`.
+/// Correlation model is either `SquaredExponential`, `AbsoluteExponential`, `Matern32` or `Matern52`
+/// or a combination of those (e.g. `Matern52PlusSquaredExponential`, see [egobox_gp::correlation_models]).
+///
+/// Surrogates are registered by name for serialization, hence user-defined regression models
+/// are not supported here: use [egobox_gp::GaussianProcess] directly with a type implementing
+/// [egobox_gp::mean_models::RegressionModel] as `Mean` parameter.
 macro_rules! declare_surrogate {
     ($regr:ident, $corr:ident) => {
         paste! {
@@ -127,6 +669,34 @@ macro_rules! declare_surrogate {
                     self.0 = self.0.clone().nugget(nugget);
                 }
 
+                fn observation_noise(&mut self, noise: Array1<f64>) {
+                    self.0 = self.0.clone().observation_noise(noise);
+                }
+
+                fn sample_weights(&mut self, weights: Array1<f64>) {
+                    self.0 = self.0.clone().sample_weights(weights);
+                }
+
+                fn theta_init_from_data(&mut self, enabled: bool) {
+                    self.0 = self.0.clone().theta_init_from_data(enabled);
+                }
+
+                fn merge_duplicates(&mut self, tol: f64) {
+                    self.0 = self.0.clone().merge_duplicates(tol);
+                }
+
+                fn scaler(&mut self, scaler: Scaler) {
+                    self.0 = self.0.clone().scaler(scaler);
+                }
+
+                fn strict_kpls(&mut self, strict: bool) {
+                    self.0 = self.0.clone().strict_kpls(strict);
+                }
+
+                fn categorical_inputs(&mut self, categorical_inputs: Vec<(usize, usize)>) {
+                    self.0 = self.0.clone().categorical_inputs(categorical_inputs);
+                }
+
                 fn train(
                     &self,
                     x: &ArrayView2<f64>,
@@ -136,6 +706,35 @@ macro_rules! declare_surrogate {
                         self.0.clone().fit(&Dataset::new(x.to_owned(), y.to_owned().remove_axis(Axis(1))))?,
                     )))
                 }
+
+                /// Hyperparameters are optimized on the sum of the reduced likelihoods of the outputs
+                /// and the factorization of the correlation matrix is shared by all outputs.
+                fn train_outputs(
+                    &self,
+                    x: &ArrayView2<f64>,
+                    y: &ArrayView2<f64>,
+                ) -> Result<MultiOutputSurrogate> {
+                    self.train_weighted_outputs(x, y, &Array1::ones(y.ncols()).view())
+                }
+
+                /// Hyperparameters are optimized on the weighted sum of the reduced likelihoods
+                /// of the outputs, the factorization of the correlation matrix being shared by all outputs.
+                fn train_weighted_outputs(
+                    &self,
+                    x: &ArrayView2<f64>,
+                    y: &ArrayView2<f64>,
+                    weights: &ArrayView1<f64>,
+                ) -> Result<MultiOutputSurrogate> {
+                    check_output_weights(y, weights)?;
+                    let outputs = self
+                        .0
+                        .check_ref()?
+                        .fit_outputs(x, y, weights)?
+                        .into_iter()
+                        .map(|gp| Box::new([<Gp $regr $corr Surrogate>](gp)) as Box<dyn FullGpSurrogate>)
+                        .collect();
+                    Ok(MultiOutputSurrogate { outputs })
+                }
             }
 
             #[doc = "GP surrogate with `" $regr "` regression model and `" $corr "` correlation model. \n\nSee [`GaussianProcess`](egobox_gp::GaussianProcess)"]
@@ -150,19 +749,77 @@ macro_rules! declare_surrogate {
                 fn dims(&self) -> (usize, usize) {
                     self.0.dims()
                 }
+                fn kind(&self) -> String {
+                    format!("{}_{}", stringify!($regr), stringify!($corr))
+                }
+                fn pls_rotation(&self) -> Option<Array2<f64>> {
+                    self.0.pls_rotation().cloned()
+                }
+                fn relevance(&self) -> Array1<f64> {
+                    self.0.relevance()
+                }
+                fn condition_number(&self) -> f64 {
+                    self.0.condition_number()
+                }
+                fn prior_mean(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
+                    Ok(self.0.prior_mean(x))
+                }
+                fn prior_variance(&self) -> Result<f64> {
+                    Ok(self.0.variance())
+                }
                 fn predict(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
                     Ok(self.0.predict(x)?)
                 }
+                fn predict_into(
+                    &self,
+                    x: &ArrayView2<f64>,
+                    out: &mut ArrayViewMut1<f64>,
+                ) -> Result<()> {
+                    if out.len() != x.nrows() {
+                        return Err(MoeError::InvalidValueError(format!(
+                            "Output buffer length should be {}, got {}",
+                            x.nrows(),
+                            out.len()
+                        )));
+                    }
+                    Ok(self.0.predict_into(x, out)?)
+                }
                 fn predict_var(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
                     Ok(self.0.predict_var(x)?)
                 }
+                /// The epistemic part is the predicted (noise-free) variance, the aleatoric part being
+                /// the nugget variance plus the observation noise at training points.
+                fn predict_variance_components(&self, x: &ArrayView2<f64>) -> Result<(Array2<f64>, Array2<f64>)> {
+                    Ok((self.0.predict_var(x)?, self.0.predict_noise_var(x)))
+                }
+                fn training_data(&self) -> (CowArray<'_, f64, Ix2>, CowArray<'_, f64, Ix2>) {
+                    let (xt, yt) = self.0.training_data();
+                    (xt.view().into(), yt.view().insert_axis(Axis(1)).into())
+                }
+                fn predict_valvar(&self, x: &ArrayView2<f64>) -> Result<(Array1<f64>, Array2<f64>)> {
+                    Ok(self.0.predict_valvar(x)?)
+                }
+                fn is_extrapolating(&self, x: &ArrayView2<f64>) -> Result<Array1<bool>> {
+                    Ok(self.0.is_extrapolating(x))
+                }
+                fn likelihood_profile(&self, theta_grid: &ArrayView2<f64>) -> Result<Array1<f64>> {
+                    Ok(self.0.likelihood_profile(theta_grid)?)
+                }
+                fn add_observation(&mut self, x: &ArrayView1<f64>, y: f64) -> Result<()> {
+                    Ok(self.0.add_observation(x, y)?)
+                }
+                fn refit(&self, x: &Array2<f64>, y: &Array2<f64>) -> Result<Box<dyn GpSurrogate>> {
+                    let params = self.0.fit_params();
+                    Ok(Box::new([<Gp $regr $corr Surrogate>](
+                        params.fit(&Dataset::new(x.to_owned(), y.column(0).to_owned()))?,
+                    )))
+                }
 
                 #[cfg(feature = "persistent")]
                 fn save(&self, path: &str, format: GpFileFormat) -> Result<()> {
-                    let mut file = fs::File::create(path).unwrap();
+                    let mut file = fs::File::create(path)?;
                     let bytes = match format {
-                        GpFileFormat::Json => serde_json::to_vec(self as &dyn GpSurrogate)
-                            .map_err(MoeError::SaveJsonError)?,
+                        GpFileFormat::Json => to_json_with_dtype(self as &dyn GpSurrogate, self.dtype())?,
                         GpFileFormat::Binary => {
                             bincode::serialize(self as &dyn GpSurrogate).map_err(MoeError::SaveBinaryError)?
                         }
@@ -204,6 +861,18 @@ macro_rules! declare_surrogate {
                 fn likelihood(&self) -> f64 {
                     self.0.likelihood()
                 }
+
+                fn likelihood_evals(&self) -> usize {
+                    self.0.likelihood_evals()
+                }
+
+                fn kpls_dim(&self) -> Option<usize> {
+                    self.0.kpls_dim()
+                }
+
+                fn loocv_score(&self) -> f64 {
+                    self.0.loocv_score()
+                }
             }
 
             #[cfg_attr(feature = "serializable", typetag::serde)]
@@ -236,31 +905,52 @@ declare_surrogate!(Quadratic, SquaredExponential);
 declare_surrogate!(Quadratic, AbsoluteExponential);
 declare_surrogate!(Quadratic, Matern32);
 declare_surrogate!(Quadratic, Matern52);
+declare_surrogate!(Constant, Matern52PlusSquaredExponential);
+declare_surrogate!(Constant, SquaredExponentialPlusAbsoluteExponential);
+declare_surrogate!(Constant, SquaredExponentialTimesAbsoluteExponential);
+declare_surrogate!(Linear, Matern52PlusSquaredExponential);
+declare_surrogate!(Linear, SquaredExponentialPlusAbsoluteExponential);
+declare_surrogate!(Linear, SquaredExponentialTimesAbsoluteExponential);
+declare_surrogate!(Quadratic, Matern52PlusSquaredExponential);
+declare_surrogate!(Quadratic, SquaredExponentialPlusAbsoluteExponential);
+declare_surrogate!(Quadratic, SquaredExponentialTimesAbsoluteExponential);
 
-/// A macro to declare SGP surrogate using correlation model names.
+/// Convert theta tuning to single precision
+fn theta_tuning_f32(theta_tuning: ThetaTuning<f64>) -> ThetaTuning<f32> {
+    match theta_tuning {
+        ThetaTuning::Fixed(init) => ThetaTuning::Fixed(init.iter().map(|&v| v as f32).collect()),
+        ThetaTuning::Optimized { init, bounds } => ThetaTuning::Optimized {
+            init: init.iter().map(|&v| v as f32).collect(),
+            bounds: bounds.iter().map(|&(a, b)| (a as f32, b as f32)).collect(),
+        },
+    }
+}
+
+/// A macro to declare single precision GP surrogate using regression model and correlation model names.
 ///
-/// Correlation model is either `SquaredExponential`, `AbsoluteExponential`, `Matern32` or `Matern52`.
-macro_rules! declare_sgp_surrogate {
-    ($corr:ident) => {
+/// The surrogate is trained and stored in single precision (f32), which halves
+/// memory footprint, while surrogate traits interface remains in double precision (f64).
+macro_rules! declare_f32_surrogate {
+    ($regr:ident, $corr:ident) => {
         paste! {
 
             #[doc(hidden)]
-            #[doc = "SGP surrogate parameters with `" $corr "` correlation model. \n\nSee [SgpParams](egobox_gp::SgpParams)"]
+            #[doc = "Single precision GP surrogate parameters with `" $regr "` regression model and `" $corr "` correlation model. \n\nSee [GpParams](egobox_gp::GpParams)"]
             #[derive(Clone, Debug)]
-            pub struct [<Sgp $corr SurrogateParams>](
-                SgpParams<f64, [<$corr Corr>]>,
+            pub struct [<Gp $regr $corr F32SurrogateParams>](
+                GpParams<f32, [<$regr Mean>], [<$corr Corr>]>,
             );
 
-            impl [<Sgp $corr SurrogateParams>] {
+            impl [<Gp $regr $corr F32SurrogateParams>] {
                 /// Constructor
-                pub fn new(gp_params: SgpParams<f64, [<$corr Corr>]>) -> [<Sgp $corr SurrogateParams>] {
-                    [<Sgp $corr SurrogateParams>](gp_params)
+                pub fn new(gp_params: GpParams<f32, [<$regr Mean>], [<$corr Corr>]>) -> [<Gp $regr $corr F32SurrogateParams>] {
+                    [<Gp $regr $corr F32SurrogateParams>](gp_params)
                 }
             }
 
-            impl GpSurrogateParams for [<Sgp $corr SurrogateParams>] {
+            impl GpSurrogateParams for [<Gp $regr $corr F32SurrogateParams>] {
                 fn theta_tuning(&mut self, theta_tuning: ThetaTuning<f64>) {
-                    self.0 = self.0.clone().theta_tuning(theta_tuning);
+                    self.0 = self.0.clone().theta_tuning(theta_tuning_f32(theta_tuning));
                 }
 
                 fn kpls_dim(&mut self, kpls_dim: Option<usize>) {
@@ -272,7 +962,35 @@ macro_rules! declare_sgp_surrogate {
                 }
 
                 fn nugget(&mut self, nugget: f64) {
-                    self.0 = self.0.clone().nugget(nugget);
+                    self.0 = self.0.clone().nugget(nugget as f32);
+                }
+
+                fn observation_noise(&mut self, noise: Array1<f64>) {
+                    self.0 = self.0.clone().observation_noise(noise.mapv(|v| v as f32));
+                }
+
+                fn sample_weights(&mut self, weights: Array1<f64>) {
+                    self.0 = self.0.clone().sample_weights(weights.mapv(|v| v as f32));
+                }
+
+                fn theta_init_from_data(&mut self, enabled: bool) {
+                    self.0 = self.0.clone().theta_init_from_data(enabled);
+                }
+
+                fn merge_duplicates(&mut self, tol: f64) {
+                    self.0 = self.0.clone().merge_duplicates(tol as f32);
+                }
+
+                fn scaler(&mut self, scaler: Scaler) {
+                    self.0 = self.0.clone().scaler(scaler);
+                }
+
+                fn strict_kpls(&mut self, strict: bool) {
+                    self.0 = self.0.clone().strict_kpls(strict);
+                }
+
+                fn categorical_inputs(&mut self, categorical_inputs: Vec<(usize, usize)>) {
+                    self.0 = self.0.clone().categorical_inputs(categorical_inputs);
                 }
 
                 fn train(
@@ -280,102 +998,173 @@ macro_rules! declare_sgp_surrogate {
                     x: &ArrayView2<f64>,
                     y: &ArrayView2<f64>,
                 ) -> Result<Box<dyn FullGpSurrogate>> {
-                    Ok(Box::new([<Sgp $corr Surrogate>](
-                        self.0.clone().fit(&Dataset::new(x.to_owned(), y.to_owned().remove_axis(Axis(1))))?,
+                    let x = x.mapv(|v| v as f32);
+                    let y = y.column(0).mapv(|v| v as f32);
+                    Ok(Box::new([<Gp $regr $corr F32Surrogate>]::new(
+                        self.0.clone().fit(&Dataset::new(x, y))?,
                     )))
                 }
             }
 
-            impl SgpSurrogateParams for [<Sgp $corr SurrogateParams>] {
-                fn sparse_method(&mut self, method: SparseMethod) {
-                    self.0 = self.0.clone().sparse_method(method);
+            #[doc = "Single precision GP surrogate with `" $regr "` regression model and `" $corr "` correlation model. \n\nSee [`GaussianProcess`](egobox_gp::GaussianProcess)"]
+            #[derive(Clone, Debug)]
+            #[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+            pub struct [<Gp $regr $corr F32Surrogate>] {
+                gp: GaussianProcess<f32, [<$regr Mean>], [<$corr Corr>]>,
+                theta: Array1<f64>,
+            }
+
+            impl [<Gp $regr $corr F32Surrogate>] {
+                /// Constructor
+                pub fn new(gp: GaussianProcess<f32, [<$regr Mean>], [<$corr Corr>]>) -> Self {
+                    let theta = gp.theta().mapv(|v| v as f64);
+                    [<Gp $regr $corr F32Surrogate>] { gp, theta }
                 }
 
-                fn seed(&mut self, seed: Option<u64>) {
-                    self.0 = self.0.clone().seed(seed);
+                /// Underlying single precision gaussian process
+                pub fn gp(&self) -> &GaussianProcess<f32, [<$regr Mean>], [<$corr Corr>]> {
+                    &self.gp
                 }
             }
 
-            #[doc = "SGP surrogate with `" $corr "` correlation model. \n\nSee [`SparseGaussianProcess`](egobox_gp::SparseGaussianProcess)"]
-            #[derive(Clone, Debug)]
-            #[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
-            pub struct [<Sgp $corr Surrogate>](
-                pub SparseGaussianProcess<f64, [<$corr Corr>]>,
-            );
-
             #[cfg_attr(feature = "serializable", typetag::serde)]
-            impl GpSurrogate for [<Sgp $corr Surrogate>] {
+            impl GpSurrogate for [<Gp $regr $corr F32Surrogate>] {
                 fn dims(&self) -> (usize, usize) {
-                    self.0.dims()
+                    self.gp.dims()
+                }
+                fn kind(&self) -> String {
+                    format!("{}_{}", stringify!($regr), stringify!($corr))
+                }
+                fn pls_rotation(&self) -> Option<Array2<f64>> {
+                    self.gp.pls_rotation().map(|w| w.mapv(|v| v as f64))
+                }
+                fn relevance(&self) -> Array1<f64> {
+                    self.gp.relevance().mapv(|v| v as f64)
+                }
+                fn condition_number(&self) -> f64 {
+                    self.gp.condition_number() as f64
+                }
+                fn prior_mean(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
+                    Ok(self.gp.prior_mean(&x.mapv(|v| v as f32)).mapv(|v| v as f64))
+                }
+                fn prior_variance(&self) -> Result<f64> {
+                    Ok(self.gp.variance() as f64)
                 }
                 fn predict(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
-                    Ok(self.0.predict(x)?)
+                    Ok(self.gp.predict(&x.mapv(|v| v as f32))?.mapv(|v| v as f64))
                 }
                 fn predict_var(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
-                    Ok(self.0.predict_var(x)?)
+                    Ok(self.gp.predict_var(&x.mapv(|v| v as f32))?.mapv(|v| v as f64))
+                }
+                /// The epistemic part is the predicted (noise-free) variance, the aleatoric part being
+                /// the nugget variance plus the observation noise at training points.
+                fn predict_variance_components(&self, x: &ArrayView2<f64>) -> Result<(Array2<f64>, Array2<f64>)> {
+                    let noise = self.gp.predict_noise_var(&x.mapv(|v| v as f32));
+                    Ok((self.predict_var(x)?, noise.mapv(|v| v as f64)))
+                }
+                fn dtype(&self) -> Dtype {
+                    Dtype::F32
+                }
+
+                fn training_data(&self) -> (CowArray<'_, f64, Ix2>, CowArray<'_, f64, Ix2>) {
+                    let (xt, yt) = self.gp.training_data();
+                    (xt.mapv(|v| v as f64).into(), yt.mapv(|v| v as f64).insert_axis(Axis(1)).into())
+                }
+                fn predict_valvar(&self, x: &ArrayView2<f64>) -> Result<(Array1<f64>, Array2<f64>)> {
+                    let (y, var) = self.gp.predict_valvar(&x.mapv(|v| v as f32))?;
+                    Ok((y.mapv(|v| v as f64), var.mapv(|v| v as f64)))
+                }
+                fn is_extrapolating(&self, x: &ArrayView2<f64>) -> Result<Array1<bool>> {
+                    Ok(self.gp.is_extrapolating(&x.mapv(|v| v as f32)))
+                }
+                fn likelihood_profile(&self, theta_grid: &ArrayView2<f64>) -> Result<Array1<f64>> {
+                    Ok(self
+                        .gp
+                        .likelihood_profile(&theta_grid.mapv(|v| v as f32))?
+                        .mapv(|v| v as f64))
+                }
+                fn add_observation(&mut self, x: &ArrayView1<f64>, y: f64) -> Result<()> {
+                    Ok(self.gp.add_observation(&x.mapv(|v| v as f32), y as f32)?)
+                }
+                fn refit(&self, x: &Array2<f64>, y: &Array2<f64>) -> Result<Box<dyn GpSurrogate>> {
+                    let params = self.gp.fit_params();
+                    let x = x.mapv(|v| v as f32);
+                    let y = y.column(0).mapv(|v| v as f32);
+                    Ok(Box::new([<Gp $regr $corr F32Surrogate>]::new(
+                        params.fit(&Dataset::new(x, y))?,
+                    )))
                 }
 
                 #[cfg(feature = "persistent")]
                 fn save(&self, path: &str, format: GpFileFormat) -> Result<()> {
-                    let mut file = fs::File::create(path).unwrap();
+                    let mut file = fs::File::create(path)?;
                     let bytes = match format {
-                        GpFileFormat::Json => serde_json::to_vec(self as &dyn SgpSurrogate)
-                            .map_err(MoeError::SaveJsonError)?,
+                        GpFileFormat::Json => to_json_with_dtype(self as &dyn GpSurrogate, self.dtype())?,
                         GpFileFormat::Binary => {
-                            bincode::serialize(self as &dyn SgpSurrogate).map_err(MoeError::SaveBinaryError)?
+                            bincode::serialize(self as &dyn GpSurrogate).map_err(MoeError::SaveBinaryError)?
                         }
                     };
                     file.write_all(&bytes)?;
+
                     Ok(())
                 }
             }
 
             #[cfg_attr(feature = "serializable", typetag::serde)]
-            impl GpSurrogateExt for [<Sgp $corr Surrogate>] {
+            impl GpSurrogateExt for [<Gp $regr $corr F32Surrogate>] {
                 fn predict_gradients(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
-                    Ok(self.0.predict_gradients(x))
+                    Ok(self.gp.predict_gradients(&x.mapv(|v| v as f32)).mapv(|v| v as f64))
                 }
                 fn predict_var_gradients(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
-                    Ok(self.0.predict_var_gradients(x))
+                    Ok(self.gp.predict_var_gradients(&x.mapv(|v| v as f32)).mapv(|v| v as f64))
                 }
                 fn sample(&self, x: &ArrayView2<f64>, n_traj: usize) -> Result<Array2<f64>> {
-                    Ok(self.0.sample(x, n_traj))
+                    Ok(self.gp.sample(&x.mapv(|v| v as f32), n_traj).mapv(|v| v as f64))
                 }
             }
 
             #[cfg_attr(feature = "serializable", typetag::serde)]
-            impl GpParameterized for [<Sgp $corr Surrogate>] {
+            impl GpParameterized for [<Gp $regr $corr F32Surrogate>] {
                 fn theta(&self) -> &Array1<f64> {
-                    self.0.theta()
+                    &self.theta
                 }
 
                 fn variance(&self) -> f64 {
-                    self.0.variance()
+                    self.gp.variance() as f64
                 }
 
                 fn noise_variance(&self) -> f64 {
-                    self.0.noise_variance()
+                    0.0
                 }
 
                 fn likelihood(&self) -> f64 {
-                    self.0.likelihood()
+                    self.gp.likelihood() as f64
                 }
-            }
 
-            #[cfg_attr(feature = "serializable", typetag::serde)]
-            impl FullGpSurrogate for [<Sgp $corr Surrogate>] {}
+                fn likelihood_evals(&self) -> usize {
+                    self.gp.likelihood_evals()
+                }
+
+                fn kpls_dim(&self) -> Option<usize> {
+                    self.gp.kpls_dim()
+                }
+
+                fn loocv_score(&self) -> f64 {
+                    self.gp.loocv_score() as f64
+                }
+            }
 
             #[cfg_attr(feature = "serializable", typetag::serde)]
-            impl SgpSurrogate for [<Sgp $corr Surrogate>] {}
+            impl FullGpSurrogate for [<Gp $regr $corr F32Surrogate>] {}
 
-            impl std::fmt::Display for [<Sgp $corr Surrogate>] {
+            impl std::fmt::Display for [<Gp $regr $corr F32Surrogate>] {
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    write!(f, "{}{}{}", stringify!($corr),
-                        match self.0.kpls_dim() {
+                    write!(f, "{}_{}_F32{}{}", stringify!($regr), stringify!($corr),
+                        match self.gp.kpls_dim() {
                             None => String::from(""),
                             Some(dim) => format!("_PLS({})", dim),
                         },
-                        self.0.to_string()
+                        self.gp.to_string()
                     )
                 }
             }
@@ -383,41 +1172,505 @@ macro_rules! declare_sgp_surrogate {
     };
 }
 
-declare_sgp_surrogate!(SquaredExponential);
-declare_sgp_surrogate!(AbsoluteExponential);
-declare_sgp_surrogate!(Matern32);
-declare_sgp_surrogate!(Matern52);
-
-#[cfg(feature = "persistent")]
-/// Load GP surrogate from given json file.
-pub fn load(path: &str, format: GpFileFormat) -> Result<Box<dyn GpSurrogate>> {
-    let data = fs::read(path)?;
-    match format {
-        GpFileFormat::Json => {
-            serde_json::from_slice::<Box<dyn GpSurrogate>>(&data).map_err(|err| {
-                MoeError::LoadError(format!("Error while loading from {path}: ({err})"))
-            })
-        }
-        GpFileFormat::Binary => bincode::deserialize(&data)
-            .map_err(|err| MoeError::LoadError(format!("Error while loading from {path} ({err})"))),
-    }
-}
+declare_f32_surrogate!(Constant, SquaredExponential);
+declare_f32_surrogate!(Constant, AbsoluteExponential);
+declare_f32_surrogate!(Constant, Matern32);
+declare_f32_surrogate!(Constant, Matern52);
+declare_f32_surrogate!(Linear, SquaredExponential);
+declare_f32_surrogate!(Linear, AbsoluteExponential);
+declare_f32_surrogate!(Linear, Matern32);
+declare_f32_surrogate!(Linear, Matern52);
+declare_f32_surrogate!(Quadratic, SquaredExponential);
+declare_f32_surrogate!(Quadratic, AbsoluteExponential);
+declare_f32_surrogate!(Quadratic, Matern32);
+declare_f32_surrogate!(Quadratic, Matern52);
 
-#[doc(hidden)]
-// Create GP surrogate parameters with given regression and correlation models.
-macro_rules! make_surrogate_params {
-    ($regr:ident, $corr:ident) => {
+/// A macro to declare SGP surrogate using correlation model names.
+///
+/// Correlation model is either `SquaredExponential`, `AbsoluteExponential`, `Matern32` or `Matern52`.
+macro_rules! declare_sgp_surrogate {
+    ($corr:ident) => {
         paste! {
-            #[allow(unused_allocation)]
-            Box::new([<Gp $regr $corr SurrogateParams>]::new(
-                GaussianProcess::<f64, [<$regr Mean>], [<$corr Corr>] >::params(
-                    [<$regr Mean>]::default(),
-                    [<$corr Corr>]::default(),
-                )
-            ))
-        }
-    };
-}
+
+            #[doc(hidden)]
+            #[doc = "SGP surrogate parameters with `" $corr "` correlation model. \n\nSee [SgpParams](egobox_gp::SgpParams)"]
+            #[derive(Clone, Debug)]
+            pub struct [<Sgp $corr SurrogateParams>](
+                SgpParams<f64, [<$corr Corr>]>,
+            );
+
+            impl [<Sgp $corr SurrogateParams>] {
+                /// Constructor
+                pub fn new(gp_params: SgpParams<f64, [<$corr Corr>]>) -> [<Sgp $corr SurrogateParams>] {
+                    [<Sgp $corr SurrogateParams>](gp_params)
+                }
+            }
+
+            impl GpSurrogateParams for [<Sgp $corr SurrogateParams>] {
+                fn theta_tuning(&mut self, theta_tuning: ThetaTuning<f64>) {
+                    self.0 = self.0.clone().theta_tuning(theta_tuning);
+                }
+
+                fn kpls_dim(&mut self, kpls_dim: Option<usize>) {
+                    self.0 = self.0.clone().kpls_dim(kpls_dim);
+                }
+
+                fn n_start(&mut self, n_start: usize) {
+                    self.0 = self.0.clone().n_start(n_start);
+                }
+
+                fn nugget(&mut self, nugget: f64) {
+                    self.0 = self.0.clone().nugget(nugget);
+                }
+
+                fn observation_noise(&mut self, _noise: Array1<f64>) {
+                    log::warn!("Observation noise ignored by sparse GP, use noise variance estimation instead");
+                }
+
+                fn sample_weights(&mut self, _weights: Array1<f64>) {
+                    log::warn!("Sample weights ignored by sparse GP");
+                }
+
+                fn theta_init_from_data(&mut self, _enabled: bool) {
+                    log::warn!("Initial theta from data ignored by sparse GP");
+                }
+
+                fn merge_duplicates(&mut self, _tol: f64) {
+                    log::warn!("Duplicates merging ignored by sparse GP");
+                }
+
+                fn scaler(&mut self, _scaler: Scaler) {
+                    log::warn!("Input scaling ignored by sparse GP");
+                }
+
+                fn strict_kpls(&mut self, _strict: bool) {
+                    log::warn!("Strict PLS setting ignored by sparse GP");
+                }
+
+                fn categorical_inputs(&mut self, categorical_inputs: Vec<(usize, usize)>) {
+                    if !categorical_inputs.is_empty() {
+                        log::warn!("Categorical inputs ignored by sparse GP");
+                    }
+                }
+
+                fn train(
+                    &self,
+                    x: &ArrayView2<f64>,
+                    y: &ArrayView2<f64>,
+                ) -> Result<Box<dyn FullGpSurrogate>> {
+                    Ok(Box::new([<Sgp $corr Surrogate>](
+                        self.0.clone().fit(&Dataset::new(x.to_owned(), y.to_owned().remove_axis(Axis(1))))?,
+                    )))
+                }
+            }
+
+            impl SgpSurrogateParams for [<Sgp $corr SurrogateParams>] {
+                fn sparse_method(&mut self, method: SparseMethod) {
+                    self.0 = self.0.clone().sparse_method(method);
+                }
+
+                fn seed(&mut self, seed: Option<u64>) {
+                    self.0 = self.0.clone().seed(seed);
+                }
+            }
+
+            #[doc = "SGP surrogate with `" $corr "` correlation model. \n\nSee [`SparseGaussianProcess`](egobox_gp::SparseGaussianProcess)"]
+            #[derive(Clone, Debug)]
+            #[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+            pub struct [<Sgp $corr Surrogate>](
+                pub SparseGaussianProcess<f64, [<$corr Corr>]>,
+            );
+
+            #[cfg_attr(feature = "serializable", typetag::serde)]
+            impl GpSurrogate for [<Sgp $corr Surrogate>] {
+                fn dims(&self) -> (usize, usize) {
+                    self.0.dims()
+                }
+                fn kind(&self) -> String {
+                    format!("{}_{}", "Sgp", stringify!($corr))
+                }
+                fn pls_rotation(&self) -> Option<Array2<f64>> {
+                    self.0.pls_rotation().cloned()
+                }
+                fn relevance(&self) -> Array1<f64> {
+                    self.0.relevance()
+                }
+                fn predict(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
+                    Ok(self.0.predict(x)?)
+                }
+                fn predict_into(
+                    &self,
+                    x: &ArrayView2<f64>,
+                    out: &mut ArrayViewMut1<f64>,
+                ) -> Result<()> {
+                    if out.len() != x.nrows() {
+                        return Err(MoeError::InvalidValueError(format!(
+                            "Output buffer length should be {}, got {}",
+                            x.nrows(),
+                            out.len()
+                        )));
+                    }
+                    Ok(self.0.predict_into(x, out)?)
+                }
+                fn predict_var(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
+                    Ok(self.0.predict_var(x)?)
+                }
+                fn training_data(&self) -> (CowArray<'_, f64, Ix2>, CowArray<'_, f64, Ix2>) {
+                    let (xt, yt) = self.0.training_data();
+                    (xt.view().into(), yt.view().insert_axis(Axis(1)).into())
+                }
+                fn prior_mean(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
+                    Ok(Array1::zeros(x.nrows()))
+                }
+                fn is_extrapolating(&self, x: &ArrayView2<f64>) -> Result<Array1<bool>> {
+                    Ok(self.0.is_extrapolating(x))
+                }
+                fn prior_variance(&self) -> Result<f64> {
+                    Ok(self.0.variance() + self.0.noise_variance())
+                }
+                fn predict_variance_components(&self, x: &ArrayView2<f64>) -> Result<(Array2<f64>, Array2<f64>)> {
+                    let var = self.0.predict_var(x)?;
+                    let noise = self.0.noise_variance();
+                    Ok((var.mapv(|v| v - noise), Array2::from_elem(var.raw_dim(), noise)))
+                }
+                fn refit(&self, x: &Array2<f64>, y: &Array2<f64>) -> Result<Box<dyn GpSurrogate>> {
+                    let params = self.0.fit_params();
+                    Ok(Box::new([<Sgp $corr Surrogate>](
+                        params.fit(&Dataset::new(x.to_owned(), y.column(0).to_owned()))?,
+                    )))
+                }
+
+                #[cfg(feature = "persistent")]
+                fn save(&self, path: &str, format: GpFileFormat) -> Result<()> {
+                    let mut file = fs::File::create(path)?;
+                    let bytes = match format {
+                        GpFileFormat::Json => to_json_with_dtype(self as &dyn SgpSurrogate, self.dtype())?,
+                        GpFileFormat::Binary => {
+                            bincode::serialize(self as &dyn SgpSurrogate).map_err(MoeError::SaveBinaryError)?
+                        }
+                    };
+                    file.write_all(&bytes)?;
+                    Ok(())
+                }
+            }
+
+            #[cfg_attr(feature = "serializable", typetag::serde)]
+            impl GpSurrogateExt for [<Sgp $corr Surrogate>] {
+                fn predict_gradients(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
+                    Ok(self.0.predict_gradients(x))
+                }
+                fn predict_var_gradients(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
+                    Ok(self.0.predict_var_gradients(x))
+                }
+                fn sample(&self, x: &ArrayView2<f64>, n_traj: usize) -> Result<Array2<f64>> {
+                    Ok(self.0.sample(x, n_traj))
+                }
+            }
+
+            #[cfg_attr(feature = "serializable", typetag::serde)]
+            impl GpParameterized for [<Sgp $corr Surrogate>] {
+                fn theta(&self) -> &Array1<f64> {
+                    self.0.theta()
+                }
+
+                fn variance(&self) -> f64 {
+                    self.0.variance()
+                }
+
+                fn noise_variance(&self) -> f64 {
+                    self.0.noise_variance()
+                }
+
+                fn likelihood(&self) -> f64 {
+                    self.0.likelihood()
+                }
+
+                fn kpls_dim(&self) -> Option<usize> {
+                    self.0.kpls_dim()
+                }
+
+                fn loocv_score(&self) -> f64 {
+                    self.0.loocv_score()
+                }
+            }
+
+            #[cfg_attr(feature = "serializable", typetag::serde)]
+            impl FullGpSurrogate for [<Sgp $corr Surrogate>] {}
+
+            #[cfg_attr(feature = "serializable", typetag::serde)]
+            impl SgpSurrogate for [<Sgp $corr Surrogate>] {}
+
+            impl std::fmt::Display for [<Sgp $corr Surrogate>] {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "{}{}{}", stringify!($corr),
+                        match self.0.kpls_dim() {
+                            None => String::from(""),
+                            Some(dim) => format!("_PLS({})", dim),
+                        },
+                        self.0.to_string()
+                    )
+                }
+            }
+        }
+    };
+}
+
+declare_sgp_surrogate!(SquaredExponential);
+declare_sgp_surrogate!(AbsoluteExponential);
+declare_sgp_surrogate!(Matern32);
+declare_sgp_surrogate!(Matern52);
+
+/// Serialize the given surrogate in json adding the `dtype` field giving its precision
+#[cfg(feature = "persistent")]
+pub fn to_json_with_dtype<T: Serialize + ?Sized>(surrogate: &T, dtype: Dtype) -> Result<Vec<u8>> {
+    let mut value = serde_json::to_value(surrogate).map_err(MoeError::SaveJsonError)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert(
+            "dtype".to_string(),
+            serde_json::to_value(dtype).map_err(MoeError::SaveJsonError)?,
+        );
+    }
+    serde_json::to_vec(&value).map_err(MoeError::SaveJsonError)
+}
+
+#[cfg(feature = "persistent")]
+/// Load GP surrogate from given json file.
+pub fn load(path: &str, format: GpFileFormat) -> Result<Box<dyn GpSurrogate>> {
+    let data = fs::read(path)?;
+    match format {
+        GpFileFormat::Json => {
+            serde_json::from_slice::<Box<dyn GpSurrogate>>(&data).map_err(MoeError::LoadJsonError)
+        }
+        GpFileFormat::Binary => bincode::deserialize(&data).map_err(MoeError::LoadBinaryError),
+    }
+}
+
+/// Load GP surrogate from given file checking its precision is the `expected` one.
+/// In json format, the `dtype` field if any is checked before deserializing the surrogate.
+#[cfg(feature = "persistent")]
+fn load_with_dtype(
+    path: &str,
+    format: GpFileFormat,
+    expected: Dtype,
+) -> Result<Box<dyn GpSurrogate>> {
+    let gp = match format {
+        GpFileFormat::Json => {
+            let value: serde_json::Value =
+                serde_json::from_slice(&fs::read(path)?).map_err(MoeError::LoadJsonError)?;
+            if let Some(dtype) = value.get("dtype") {
+                let dtype: Dtype =
+                    serde_json::from_value(dtype.clone()).map_err(MoeError::LoadJsonError)?;
+                if dtype != expected {
+                    return Err(MoeError::LoadError(format!(
+                        "Expected {expected} surrogate, got {dtype} surrogate from {path}"
+                    )));
+                }
+            }
+            serde_json::from_value::<Box<dyn GpSurrogate>>(value)
+                .map_err(MoeError::LoadJsonError)?
+        }
+        GpFileFormat::Binary => load(path, format)?,
+    };
+    if gp.dtype() != expected {
+        return Err(MoeError::LoadError(format!(
+            "Expected {expected} surrogate, got {} surrogate {gp} from {path}",
+            gp.dtype()
+        )));
+    }
+    Ok(gp)
+}
+
+/// Load single precision GP surrogate from given file,
+/// returns an error if the file holds a surrogate of another precision.
+#[cfg(feature = "persistent")]
+pub fn load_f32(path: &str, format: GpFileFormat) -> Result<Box<dyn GpSurrogate>> {
+    load_with_dtype(path, format, Dtype::F32)
+}
+
+/// Load double precision GP surrogate from given file,
+/// returns an error if the file holds a surrogate of another precision.
+#[cfg(feature = "persistent")]
+pub fn load_f64(path: &str, format: GpFileFormat) -> Result<Box<dyn GpSurrogate>> {
+    load_with_dtype(path, format, Dtype::F64)
+}
+
+/// Kriging model data as exported from the SMT Python toolbox `KRG` surrogate
+/// (`options["poly"]`, `options["corr"]`, `optimal_theta`, training points and nugget)
+#[cfg(feature = "persistent")]
+#[derive(Deserialize)]
+struct SmtKrigingData {
+    poly: String,
+    corr: String,
+    optimal_theta: Vec<f64>,
+    xt: Vec<Vec<f64>>,
+    yt: Vec<Vec<f64>>,
+    #[serde(default)]
+    nugget: Option<f64>,
+}
+
+#[cfg(feature = "persistent")]
+/// Load a GP surrogate from a json file describing a Kriging model trained with
+/// the SMT Python toolbox, with the following layout:
+///
+/// ```json
+/// {
+///   "poly": "constant",          // or "linear", "quadratic"
+///   "corr": "squar_exp",         // or "abs_exp", "matern32", "matern52"
+///   "optimal_theta": [0.5, 2.0], // one value per input dimension
+///   "xt": [[0.0, 1.0], ...],     // training inputs (n, nx)
+///   "yt": [[3.2], ...],          // training outputs (n, 1)
+///   "nugget": 2.22e-14           // optional
+/// }
+/// ```
+///
+/// SMT and egobox share the same conventions: inputs and outputs are standardized
+/// (with unbiased standard deviations) and theta enters the correlation directly
+/// (e.g. `exp(-sum(theta_i * d_i^2))` for the squared exponential). Hence SMT theta
+/// values are reused as is and the surrogate is rebuilt from the training data
+/// without hyperparameters optimization.
+pub fn load_smt(path: &str) -> Result<Box<dyn FullGpSurrogate>> {
+    let data = fs::read(path)?;
+    let smt: SmtKrigingData = serde_json::from_slice(&data).map_err(MoeError::LoadJsonError)?;
+
+    let nx = smt.xt.first().map_or(0, |row| row.len());
+    let xt = Array2::from_shape_vec((smt.xt.len(), nx), smt.xt.into_iter().flatten().collect())
+        .map_err(|e| MoeError::LoadError(format!("Bad SMT training inputs: {e}")))?;
+    let yt = Array2::from_shape_vec((smt.yt.len(), 1), smt.yt.into_iter().flatten().collect())
+        .map_err(|e| MoeError::LoadError(format!("Bad SMT training outputs: {e}")))?;
+    if xt.nrows() != yt.nrows() {
+        return Err(MoeError::LoadError(format!(
+            "SMT training data mismatch: {} inputs vs {} outputs",
+            xt.nrows(),
+            yt.nrows()
+        )));
+    }
+    if smt.optimal_theta.len() != nx {
+        return Err(MoeError::LoadError(format!(
+            "SMT theta of length {} expected to match input dimension {nx} (KPLS models not supported)",
+            smt.optimal_theta.len()
+        )));
+    }
+
+    let params: Result<Box<dyn GpSurrogateParams>> = match (smt.poly.as_str(), smt.corr.as_str()) {
+        ("constant", "squar_exp") => Ok(make_surrogate_params!(Constant, SquaredExponential)),
+        ("constant", "abs_exp") => Ok(make_surrogate_params!(Constant, AbsoluteExponential)),
+        ("constant", "matern32") => Ok(make_surrogate_params!(Constant, Matern32)),
+        ("constant", "matern52") => Ok(make_surrogate_params!(Constant, Matern52)),
+        ("linear", "squar_exp") => Ok(make_surrogate_params!(Linear, SquaredExponential)),
+        ("linear", "abs_exp") => Ok(make_surrogate_params!(Linear, AbsoluteExponential)),
+        ("linear", "matern32") => Ok(make_surrogate_params!(Linear, Matern32)),
+        ("linear", "matern52") => Ok(make_surrogate_params!(Linear, Matern52)),
+        ("quadratic", "squar_exp") => Ok(make_surrogate_params!(Quadratic, SquaredExponential)),
+        ("quadratic", "abs_exp") => Ok(make_surrogate_params!(Quadratic, AbsoluteExponential)),
+        ("quadratic", "matern32") => Ok(make_surrogate_params!(Quadratic, Matern32)),
+        ("quadratic", "matern52") => Ok(make_surrogate_params!(Quadratic, Matern52)),
+        (poly, corr) => Err(MoeError::LoadError(format!(
+            "Unsupported SMT Kriging model (poly={poly}, corr={corr})"
+        ))),
+    };
+    let mut params = params?;
+    params.theta_tuning(ThetaTuning::Fixed(smt.optimal_theta));
+    if let Some(nugget) = smt.nugget {
+        params.nugget(nugget);
+    }
+    params.train(&xt.view(), &yt.view())
+}
+
+/// Create GP surrogate parameters from an expert kind given as a string
+/// `<Mean>_<Correlation>` (e.g. `"Linear_Matern52"`), as reported by the mixture
+/// when selecting the best experts.
+///
+/// Returns an error if the kind does not match any available expert.
+pub fn make_surrogate_params_from_kind(kind: &str) -> Result<Box<dyn GpSurrogateParams>> {
+    match kind {
+        "Constant_SquaredExponential" => Ok(make_surrogate_params!(Constant, SquaredExponential)),
+        "Constant_AbsoluteExponential" => Ok(make_surrogate_params!(Constant, AbsoluteExponential)),
+        "Constant_Matern32" => Ok(make_surrogate_params!(Constant, Matern32)),
+        "Constant_Matern52" => Ok(make_surrogate_params!(Constant, Matern52)),
+        "Linear_SquaredExponential" => Ok(make_surrogate_params!(Linear, SquaredExponential)),
+        "Linear_AbsoluteExponential" => Ok(make_surrogate_params!(Linear, AbsoluteExponential)),
+        "Linear_Matern32" => Ok(make_surrogate_params!(Linear, Matern32)),
+        "Linear_Matern52" => Ok(make_surrogate_params!(Linear, Matern52)),
+        "Quadratic_SquaredExponential" => Ok(make_surrogate_params!(Quadratic, SquaredExponential)),
+        "Quadratic_AbsoluteExponential" => {
+            Ok(make_surrogate_params!(Quadratic, AbsoluteExponential))
+        }
+        "Quadratic_Matern32" => Ok(make_surrogate_params!(Quadratic, Matern32)),
+        "Quadratic_Matern52" => Ok(make_surrogate_params!(Quadratic, Matern52)),
+        "Constant_Matern52PlusSquaredExponential" => Ok(make_surrogate_params!(
+            Constant,
+            Matern52PlusSquaredExponential
+        )),
+        "Constant_SquaredExponentialPlusAbsoluteExponential" => Ok(make_surrogate_params!(
+            Constant,
+            SquaredExponentialPlusAbsoluteExponential
+        )),
+        "Constant_SquaredExponentialTimesAbsoluteExponential" => Ok(make_surrogate_params!(
+            Constant,
+            SquaredExponentialTimesAbsoluteExponential
+        )),
+        "Linear_Matern52PlusSquaredExponential" => Ok(make_surrogate_params!(
+            Linear,
+            Matern52PlusSquaredExponential
+        )),
+        "Linear_SquaredExponentialPlusAbsoluteExponential" => Ok(make_surrogate_params!(
+            Linear,
+            SquaredExponentialPlusAbsoluteExponential
+        )),
+        "Linear_SquaredExponentialTimesAbsoluteExponential" => Ok(make_surrogate_params!(
+            Linear,
+            SquaredExponentialTimesAbsoluteExponential
+        )),
+        "Quadratic_Matern52PlusSquaredExponential" => Ok(make_surrogate_params!(
+            Quadratic,
+            Matern52PlusSquaredExponential
+        )),
+        "Quadratic_SquaredExponentialPlusAbsoluteExponential" => Ok(make_surrogate_params!(
+            Quadratic,
+            SquaredExponentialPlusAbsoluteExponential
+        )),
+        "Quadratic_SquaredExponentialTimesAbsoluteExponential" => Ok(make_surrogate_params!(
+            Quadratic,
+            SquaredExponentialTimesAbsoluteExponential
+        )),
+        _ => Err(MoeError::ExpertError(format!("Unknown expert {kind}"))),
+    }
+}
+
+/// Create sparse GP surrogate parameters from a sparse expert kind given as a string
+/// `Sgp_<Correlation>` (e.g. `"Sgp_Matern52"`) with given inducing points specification.
+///
+/// Returns an error if the kind does not match any available sparse expert.
+pub fn make_sgp_surrogate_params_from_kind(
+    kind: &str,
+    inducings: Inducings<f64>,
+) -> Result<Box<dyn SgpSurrogateParams>> {
+    match kind {
+        "Sgp_SquaredExponential" => Ok(make_sgp_surrogate_params!(SquaredExponential, inducings)),
+        "Sgp_AbsoluteExponential" => Ok(make_sgp_surrogate_params!(AbsoluteExponential, inducings)),
+        "Sgp_Matern32" => Ok(make_sgp_surrogate_params!(Matern32, inducings)),
+        "Sgp_Matern52" => Ok(make_sgp_surrogate_params!(Matern52, inducings)),
+        _ => Err(MoeError::ExpertError(format!(
+            "Unknown sparse expert {kind}"
+        ))),
+    }
+}
+
+#[doc(hidden)]
+// Create GP surrogate parameters with given regression and correlation models.
+macro_rules! make_surrogate_params {
+    ($regr:ident, $corr:ident) => {
+        paste! {
+            #[allow(unused_allocation)]
+            Box::new([<Gp $regr $corr SurrogateParams>]::new(
+                GaussianProcess::<f64, [<$regr Mean>], [<$corr Corr>] >::params(
+                    [<$regr Mean>]::default(),
+                    [<$corr Corr>]::default(),
+                )
+            ))
+        }
+    };
+}
 
 #[doc(hidden)]
 // Create GP surrogate parameters with given regression and correlation models.
@@ -449,7 +1702,11 @@ mod tests {
     use ndarray::array;
     #[cfg(feature = "blas")]
     use ndarray_linalg::Norm;
+    use ndarray_rand::rand::SeedableRng;
+    use ndarray_rand::rand_distr::Normal;
+    use ndarray_rand::RandomExt;
     use ndarray_stats::DeviationExt;
+    use rand_xoshiro::Xoshiro256Plus;
 
     fn xsinx(x: &Array2<f64>) -> Array1<f64> {
         ((x - 3.5) * ((x - 3.5) / std::f64::consts::PI).mapv(|v| v.sin())).remove_axis(Axis(1))
@@ -473,6 +1730,967 @@ mod tests {
         assert_abs_diff_eq!(err, 0., epsilon = 2e-1);
     }
 
+    #[test]
+    fn test_save_load_combined_corr() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits).sample(10);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, Matern52PlusSquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        assert!(gp
+            .to_string()
+            .starts_with("Constant_Matern52PlusSquaredExponential"));
+        // one theta per input dimension for each combined correlation model
+        assert_eq!(gp.theta().len(), 2);
+        gp.save("target/tests/save_gp_combined.json", GpFileFormat::Json)
+            .expect("GP not saved");
+        let loaded =
+            load("target/tests/save_gp_combined.json", GpFileFormat::Json).expect("GP not loaded");
+        let xv = Lhs::new(&xlimits).sample(20);
+        assert_abs_diff_eq!(
+            loaded.predict(&xv.view()).unwrap(),
+            gp.predict(&xv.view()).unwrap(),
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_save_load_categorical() {
+        // continuous input and a categorical input with 3 levels one-hot encoded
+        let n = 8;
+        let mut xt = Array2::zeros((3 * n, 4));
+        for (i, mut row) in xt.rows_mut().into_iter().enumerate() {
+            row[0] = (i % n) as f64 / (n - 1) as f64;
+            row[1 + i / n] = 1.;
+        }
+        let yt = Zip::from(xt.rows()).map_collect(|x| (6. * x[0]).sin() + x[2] - 2. * x[3]);
+        let mut params = make_surrogate_params_from_kind("Constant_SquaredExponential").unwrap();
+        params.categorical_inputs(vec![(1, 3)]);
+        let gp = params
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        // one theta per input plus k-1 latent coordinates
+        assert_eq!(gp.theta().len(), 4 + 2);
+        std::fs::create_dir_all("target/tests").ok();
+        gp.save("target/tests/save_gp_categorical.json", GpFileFormat::Json)
+            .expect("GP not saved");
+        let loaded = load("target/tests/save_gp_categorical.json", GpFileFormat::Json)
+            .expect("GP not loaded");
+        assert_abs_diff_eq!(
+            loaded.predict(&xt.view()).unwrap(),
+            gp.predict(&xt.view()).unwrap(),
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_save_load_kind() {
+        let xlimits = array![[0., 25.], [-5., 5.]];
+        let xt = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(15);
+        let yt = (xt.column(0).mapv(f64::sin) + xt.column(1)).insert_axis(Axis(1));
+        let mut params = GpLinearMatern52SurrogateParams::new(GpParams::new(
+            LinearMean::default(),
+            Matern52Corr::default(),
+        ));
+        params.kpls_dim(Some(1));
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        assert_eq!(gp.kind(), "Linear_Matern52");
+        assert!(gp.to_string().contains("PLS"));
+        assert!(make_surrogate_params_from_kind(&gp.kind()).is_ok());
+
+        gp.save("target/tests/save_gp_kind.bin", GpFileFormat::Binary)
+            .expect("GP not saved");
+        let loaded =
+            load("target/tests/save_gp_kind.bin", GpFileFormat::Binary).expect("GP not loaded");
+        assert_eq!(loaded.kind(), gp.kind());
+
+        let gp32 = GpConstantMatern32F32SurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            Matern32Corr::default(),
+        ))
+        .train(&xt.view(), &yt.view())
+        .expect("GP fit error");
+        gp32.save("target/tests/save_gp32_kind.json", GpFileFormat::Json)
+            .expect("GP not saved");
+        let loaded =
+            load("target/tests/save_gp32_kind.json", GpFileFormat::Json).expect("GP not loaded");
+        assert_eq!(loaded.kind(), "Constant_Matern32");
+
+        let inducings = egobox_gp::Inducings::Randomized(5);
+        let sgp = make_sgp_surrogate_params_from_kind("Sgp_Matern52", inducings.clone())
+            .expect("Valid sparse kind")
+            .train(&xt.view(), &yt.view())
+            .expect("SGP fit error");
+        assert_eq!(sgp.kind(), "Sgp_Matern52");
+        assert!(make_sgp_surrogate_params_from_kind(&sgp.kind(), inducings).is_ok());
+        sgp.save("target/tests/save_sgp_kind.json", GpFileFormat::Json)
+            .expect("SGP not saved");
+        let loaded =
+            load("target/tests/save_sgp_kind.json", GpFileFormat::Json).expect("SGP not loaded");
+        assert_eq!(loaded.kind(), sgp.kind());
+    }
+
+    #[test]
+    fn test_is_extrapolating() {
+        let xt = array![[0.], [5.], [10.], [15.], [20.], [25.]];
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let x = array![[0.], [12.3], [25.], [-1.], [100.]];
+        assert_eq!(
+            gp.is_extrapolating(&x.view()).unwrap(),
+            array![false, false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_training_data() {
+        let xlimits = array![[0., 25.], [-5., 5.]];
+        let xt = Lhs::new(&xlimits).sample(12);
+        let yt = xt.column(0).mapv(f64::sin) + xt.column(1);
+        let yt = yt.insert_axis(Axis(1));
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.view())
+            .expect("GP fit error");
+        let (xdata, ydata) = gp.training_data();
+        assert_eq!(xdata.dim(), (12, 2));
+        assert_eq!(ydata.dim(), (12, 1));
+        assert_abs_diff_eq!(xdata, xt.view());
+        assert_abs_diff_eq!(ydata, yt.view());
+
+        let gp = GpConstantSquaredExponentialF32SurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ))
+        .train(&xt.view(), &yt.view())
+        .expect("GP fit error");
+        let (xdata, ydata) = gp.training_data();
+        assert_eq!(xdata.dim(), (12, 2));
+        assert_eq!(ydata.dim(), (12, 1));
+        assert_abs_diff_eq!(xdata, xt.view(), epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_save_load_minmax_scaler() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits).sample(10);
+        let yt = xsinx(&xt);
+        let mut params = GpConstantSquaredExponentialSurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ));
+        params.scaler(Scaler::MinMax);
+        let gp = params
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        std::fs::create_dir_all("target/tests").ok();
+        gp.save("target/tests/save_gp_minmax.json", GpFileFormat::Json)
+            .expect("GP not saved");
+        let gp2 =
+            load("target/tests/save_gp_minmax.json", GpFileFormat::Json).expect("GP not loaded");
+        let xv = Lhs::new(&xlimits).sample(20);
+        assert_abs_diff_eq!(
+            gp2.predict(&xv.view()).unwrap(),
+            gp.predict(&xv.view()).unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_save_load_dtype() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits).sample(10);
+        let yt = xsinx(&xt).insert_axis(Axis(1));
+        let xv = Lhs::new(&xlimits).sample(20);
+        std::fs::create_dir_all("target/tests").ok();
+
+        let gp64 = GpConstantSquaredExponentialSurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ))
+        .train(&xt.view(), &yt.view())
+        .expect("GP fit error");
+        let gp32 = GpConstantSquaredExponentialF32SurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ))
+        .train(&xt.view(), &yt.view())
+        .expect("GP fit error");
+
+        let format = |ext| match ext {
+            "json" => GpFileFormat::Json,
+            _ => GpFileFormat::Binary,
+        };
+        for (gp, dtype, other, filename) in [
+            (gp64, Dtype::F64, Dtype::F32, "save_gp_f64_dtype"),
+            (gp32, Dtype::F32, Dtype::F64, "save_gp_f32_dtype"),
+        ] {
+            assert_eq!(gp.dtype(), dtype);
+            for ext in ["json", "bin"] {
+                let path = format!("target/tests/{filename}.{ext}");
+                gp.save(&path, format(ext)).expect("GP not saved");
+                let load_as = |dtype| match dtype {
+                    Dtype::F32 => load_f32(&path, format(ext)),
+                    Dtype::F64 => load_f64(&path, format(ext)),
+                };
+                let loaded = load_as(dtype).expect("GP not loaded");
+                assert_eq!(loaded.dtype(), dtype);
+                assert_abs_diff_eq!(
+                    loaded.predict(&xv.view()).unwrap(),
+                    gp.predict(&xv.view()).unwrap(),
+                    epsilon = 1e-9
+                );
+                match load_as(other) {
+                    Err(MoeError::LoadError(msg)) => {
+                        assert!(msg.starts_with(&format!("Expected {other} surrogate")))
+                    }
+                    _ => panic!("Precision mismatch should be an error"),
+                }
+            }
+        }
+        let json = std::fs::read_to_string("target/tests/save_gp_f32_dtype.json").unwrap();
+        assert!(json.contains(r#""dtype":"f32""#));
+
+        let sgp =
+            make_sgp_surrogate_params_from_kind("Sgp_SquaredExponential", Inducings::Randomized(5))
+                .unwrap()
+                .train(&xt.view(), &yt.view())
+                .expect("SGP fit error");
+        sgp.save("target/tests/save_sgp_dtype.json", GpFileFormat::Json)
+            .expect("SGP not saved");
+        let json = std::fs::read_to_string("target/tests/save_sgp_dtype.json").unwrap();
+        assert!(json.contains(r#""dtype":"f64""#));
+        assert!(load_f64("target/tests/save_sgp_dtype.json", GpFileFormat::Json).is_ok());
+
+        // precision is checked before deserializing the surrogate
+        std::fs::write(
+            "target/tests/save_unknown_dtype.json",
+            r#"{"type":"Unknown","dtype":"f32"}"#,
+        )
+        .unwrap();
+        match load_f64("target/tests/save_unknown_dtype.json", GpFileFormat::Json) {
+            Err(MoeError::LoadError(msg)) => assert!(msg.starts_with("Expected f64 surrogate")),
+            _ => panic!("Precision mismatch should be an error"),
+        }
+    }
+
+    #[test]
+    fn test_predict_clamped() {
+        let xt = array![[0.], [5.], [10.], [15.], [20.], [25.]];
+        let yt = xsinx(&xt);
+        let gp = GpConstantSquaredExponentialSurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ))
+        .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+        .expect("GP fit error");
+        let xlimits = array![[0., 25.]];
+        let x = array![[-3.], [12.3], [25.], [40.]];
+        let (y, clamped) = gp.predict_clamped(&x.view(), &xlimits.view()).unwrap();
+        assert_eq!(clamped, array![true, false, false, true]);
+        let expected = gp
+            .predict(&array![[0.], [12.3], [25.], [25.]].view())
+            .unwrap();
+        assert_abs_diff_eq!(y, expected, epsilon = 1e-12);
+        assert!(gp
+            .predict_clamped(&x.view(), &array![[0., 25.], [0., 1.]].view())
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_refit() {
+        let xlimits = array![[0., 25.]];
+        let rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Lhs::new(&xlimits).with_rng(rng).sample(5);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        gp.save("target/tests/save_gp_refit.json", GpFileFormat::Json)
+            .expect("GP not saved");
+        let gp =
+            load("target/tests/save_gp_refit.json", GpFileFormat::Json).expect("GP not loaded");
+
+        let rng = Xoshiro256Plus::seed_from_u64(0);
+        let xt = Lhs::new(&xlimits).with_rng(rng).sample(15);
+        let yt = xsinx(&xt).insert_axis(Axis(1));
+        let refitted = gp.refit(&xt, &yt).expect("GP refit error");
+        assert_eq!(refitted.dims(), gp.dims());
+
+        let xv = Array1::linspace(0., 25., 50).insert_axis(Axis(1));
+        let yv = xsinx(&xv);
+        let err = |gp: &dyn GpSurrogate| {
+            gp.predict(&xv.view()).unwrap().l2_dist(&yv).unwrap() / yv.norm_l2()
+        };
+        let (err_loaded, err_refitted) = (err(gp.as_ref()), err(refitted.as_ref()));
+        assert!(
+            err_refitted < err_loaded,
+            "refit error {err_refitted} not better than {err_loaded}"
+        );
+        assert_abs_diff_eq!(err_refitted, 0., epsilon = 1e-1);
+
+        // Parameters of the loaded surrogate are kept
+        let mut params = make_surrogate_params_from_kind("Constant_SquaredExponential").unwrap();
+        params.fixed_theta(vec![0.3]);
+        params.nugget(1e-3);
+        let yv = yv.insert_axis(Axis(1));
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        let refitted = gp.refit(&xv, &yv).expect("GP refit error");
+        let expected = params.train(&xv.view(), &yv.view()).expect("GP fit error");
+        let x = array![[3.3], [12.1], [30.]];
+        assert_abs_diff_eq!(
+            refitted.predict(&x.view()).unwrap(),
+            expected.predict(&x.view()).unwrap(),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            refitted.predict_variance_components(&x.view()).unwrap().1,
+            expected.predict_variance_components(&x.view()).unwrap().1,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_load_smt() {
+        let test_dir = "target/tests";
+        std::fs::create_dir_all(test_dir).ok();
+        let filename = format!("{test_dir}/smt_krg.json");
+        std::fs::write(
+            &filename,
+            r#"{
+                "poly": "constant",
+                "corr": "squar_exp",
+                "optimal_theta": [0.5],
+                "xt": [[0.0], [1.0], [2.0], [3.0], [4.0]],
+                "yt": [[0.0], [1.0], [4.0], [9.0], [16.0]]
+            }"#,
+        )
+        .unwrap();
+        let gp = load_smt(&filename).expect("SMT model not loaded");
+        assert_eq!(gp.dims(), (1, 1));
+        assert_abs_diff_eq!(*gp.theta(), array![0.5], epsilon = 1e-12);
+        let xv = array![[0.0], [2.0], [2.5], [4.0]];
+        assert_abs_diff_eq!(
+            gp.predict(&xv.view()).unwrap(),
+            array![0.0, 4.0, 6.25, 16.0],
+            epsilon = 0.5
+        );
+
+        std::fs::write(
+            &filename,
+            r#"{"poly": "constant", "corr": "act_exp", "optimal_theta": [0.5],
+                "xt": [[0.0], [1.0]], "yt": [[0.0], [1.0]]}"#,
+        )
+        .unwrap();
+        assert!(matches!(load_smt(&filename), Err(MoeError::LoadError(_))));
+    }
+
+    #[test]
+    fn test_load_error_source() {
+        use std::error::Error;
+
+        let test_dir = "target/tests";
+        std::fs::create_dir_all(test_dir).ok();
+        let filename = format!("{test_dir}/invalid_gp.json");
+        std::fs::write(&filename, "{ not a gp }").unwrap();
+
+        let err = load(&filename, GpFileFormat::Json)
+            .err()
+            .expect("GP loading should fail");
+        assert!(matches!(err, MoeError::LoadJsonError(_)));
+        assert!(err
+            .source()
+            .and_then(|e| e.downcast_ref::<serde_json::Error>())
+            .is_some());
+
+        let err = load(&format!("{test_dir}/missing_gp.json"), GpFileFormat::Json)
+            .err()
+            .expect("GP loading should fail");
+        assert!(err
+            .source()
+            .and_then(|e| e.downcast_ref::<std::io::Error>())
+            .is_some());
+    }
+
+    #[test]
+    fn test_f32_surrogate() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(10);
+        let yt = xsinx(&xt).insert_axis(Axis(1));
+        let params = GpConstantSquaredExponentialF32SurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ));
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        let xv = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(0))
+            .sample(20);
+        let yv = xsinx(&xv);
+        let ytest = gp.predict(&xv.view()).unwrap();
+        let err = ytest.l2_dist(&yv).unwrap() / yv.norm_l2();
+        println!("f32 surrogate relative error = {err}");
+        assert_abs_diff_eq!(err, 0., epsilon = 1e-2);
+        // close to the double precision surrogate
+        let gp64 = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.view())
+            .expect("GP fit error");
+        let ytest64 = gp64.predict(&xv.view()).unwrap();
+        let diff = ytest.l2_dist(&ytest64).unwrap() / ytest64.norm_l2();
+        assert_abs_diff_eq!(diff, 0., epsilon = 1e-2);
+        assert!(gp.predict_var(&xv.view()).unwrap().iter().all(|&v| v >= 0.));
+
+        std::fs::create_dir_all("target/tests").ok();
+        gp.save("target/tests/save_gp_f32.json", GpFileFormat::Json)
+            .expect("GP not saved");
+        let gp2 = load("target/tests/save_gp_f32.json", GpFileFormat::Json).expect("GP not loaded");
+        assert_abs_diff_eq!(gp2.predict(&xv.view()).unwrap(), ytest, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_gp_error_propagation() {
+        let xt = array![[0.], [1.], [2.], [3.], [4.]];
+        let yt = xsinx(&xt);
+        let params = GpConstantSquaredExponentialSurrogateParams::new(
+            GpParams::new(ConstantMean::default(), SquaredExponentialCorr::default())
+                .kpls_dim(Some(3))
+                .strict_kpls(true),
+        );
+        let err = params
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .err()
+            .expect("GP fit should fail");
+        assert!(matches!(
+            err,
+            MoeError::GpError(egobox_gp::GpError::InvalidValueError(_))
+        ));
+
+        // DoE errors are converted as well
+        let sample =
+            |xlimits: &Array2<f64>| -> Result<Array2<f64>> { Ok(Lhs::try_new(xlimits)?.sample(5)) };
+        assert!(matches!(
+            sample(&array![[1., 0.]]),
+            Err(MoeError::DoeError(egobox_doe::DoeError::InvalidXlimits(_)))
+        ));
+    }
+
+    #[test]
+    fn test_add_observation() {
+        let xt = array![[0.], [1.], [2.], [3.], [4.]];
+        let yt = xsinx(&xt);
+        let mut gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let xnew = array![[2.5]];
+        let ynew = xsinx(&xnew);
+        gp.add_observation(&xnew.row(0), ynew[0])
+            .expect("GP update error");
+        let ytest = gp.predict(&xnew.view()).unwrap();
+        assert_abs_diff_eq!(ytest, ynew, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_condition_number() {
+        let xt = array![[0.], [1.], [2.], [3.], [4.]];
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let well_separated = gp.condition_number();
+
+        let xt = array![[0.], [1.], [2.], [2. + 1e-6], [3.], [4.]];
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let near_duplicates = gp.condition_number();
+
+        assert!(well_separated < 1e6, "{well_separated}");
+        assert!(near_duplicates > 1e10, "{near_duplicates}");
+    }
+
+    #[test]
+    fn test_max_variance_point() {
+        let xt = array![[0.], [0.1], [0.2], [0.8], [0.9], [1.]];
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let xlimits = array![[0., 1.]];
+        let xmax = gp
+            .max_variance_point(&xlimits, 5)
+            .expect("Max variance search");
+        assert!(xmax[0] > 0.3 && xmax[0] < 0.7, "{xmax}");
+
+        let xgrid = Array1::linspace(0., 1., 101).insert_axis(Axis(1));
+        let var_grid = gp.predict_var(&xgrid.view()).unwrap();
+        let var_max = gp.predict_var(&xmax.view().insert_axis(Axis(0))).unwrap();
+        assert!(var_grid.iter().all(|v| *v <= var_max[[0, 0]] + 1e-12));
+
+        assert!(gp
+            .max_variance_point(&array![[0., 1.], [0., 1.]], 5)
+            .is_err());
+    }
+
+    #[test]
+    fn test_integrated_variance() {
+        let xlimits = array![[0., 25.]];
+        let ivar = |n: usize| {
+            let xt = Array1::linspace(0., 25., n).insert_axis(Axis(1));
+            let yt = xsinx(&xt);
+            make_surrogate_params!(Constant, SquaredExponential)
+                .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+                .expect("GP fit error")
+                .integrated_variance(&xlimits, 200, Some(42))
+                .expect("Integrated variance")
+        };
+        let ivars = [5, 8, 12, 16].map(ivar);
+        println!("integrated variances = {ivars:?}");
+        assert!(ivars.windows(2).all(|w| w[1] < w[0]));
+
+        let xt = Array1::linspace(0., 25., 5).insert_axis(Axis(1));
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &xsinx(&xt).insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        assert!(gp.integrated_variance(&xlimits, 0, Some(42)).is_err());
+    }
+
+    #[test]
+    fn test_prior() {
+        let xt = Lhs::new(&array![[0., 10.]])
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(20);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let prior_mean = gp.prior_mean(&array![[5.], [1000.]].view()).unwrap();
+        // constant trend
+        assert_abs_diff_eq!(prior_mean[0], prior_mean[1]);
+        let prior_variance = gp.prior_variance().unwrap();
+
+        let xfar = array![[-1000.], [1000.]];
+        let (y, var) = gp.predict_valvar(&xfar.view()).unwrap();
+        assert_abs_diff_eq!(y, Array1::from_elem(2, prior_mean[0]), epsilon = 1e-8);
+        // far variance is the prior variance plus the variance of the trend estimation
+        assert_abs_diff_eq!(var[[0, 0]], var[[1, 0]], epsilon = 1e-8);
+        assert!(var[[0, 0]] > prior_variance && var[[0, 0]] < 1.5 * prior_variance);
+    }
+
+    #[test]
+    fn test_predict_variance_components() {
+        let xt = array![[0.], [1.], [2.], [3.], [4.]];
+        let yt = xsinx(&xt).insert_axis(Axis(1));
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.view())
+            .expect("GP fit error");
+        let x = array![[0.], [0.5], [2.], [3.7], [4.]];
+        let (epistemic, aleatoric) = gp.predict_variance_components(&x.view()).unwrap();
+        let var = gp.predict_var(&x.view()).unwrap();
+        assert_abs_diff_eq!(&epistemic + &aleatoric, var, epsilon = 1e-12);
+        for i in [0, 2, 4] {
+            assert_abs_diff_eq!(epistemic[[i, 0]], 0., epsilon = 1e-6);
+        }
+
+        // Nugget variance and observation noise as aleatoric part of a full GP
+        let mut params = make_surrogate_params_from_kind("Constant_SquaredExponential").unwrap();
+        params.nugget(1e-2);
+        params.observation_noise(array![0., 0., 0.5, 0., 0.]);
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        let (epistemic, aleatoric) = gp.predict_variance_components(&x.view()).unwrap();
+        assert_abs_diff_eq!(epistemic, gp.predict_var(&x.view()).unwrap());
+        let nugget_var = 1e-2 * gp.variance();
+        for i in [0, 1, 3, 4] {
+            assert_abs_diff_eq!(aleatoric[[i, 0]], nugget_var, epsilon = 1e-12);
+        }
+        assert_abs_diff_eq!(aleatoric[[2, 0]], 0.51 * gp.variance(), epsilon = 1e-12);
+        // epistemic uncertainty is reduced near data whereas aleatoric one is not
+        let xfar = array![[10.]];
+        let (epistemic_far, aleatoric_far) = gp.predict_variance_components(&xfar.view()).unwrap();
+        assert!(epistemic[[0, 0]] < epistemic_far[[0, 0]]);
+        assert!(epistemic[[0, 0]] < aleatoric[[0, 0]]);
+        assert_abs_diff_eq!(aleatoric_far[[0, 0]], nugget_var, epsilon = 1e-12);
+
+        // Noisy observations with sparse GP
+        let mut rng = Xoshiro256Plus::seed_from_u64(42);
+        let xt = Lhs::new(&array![[0., 4.]])
+            .with_rng(rng.clone())
+            .sample(100);
+        let noise = Array2::random_using((100, 1), Normal::new(0., 0.1).unwrap(), &mut rng);
+        let yt = xsinx(&xt).insert_axis(Axis(1)) + noise;
+        let inducings = egobox_gp::Inducings::Located(array![[0.], [1.], [2.], [3.], [4.]]);
+        let sgp = make_sgp_surrogate_params!(SquaredExponential, inducings)
+            .train(&xt.view(), &yt.view())
+            .expect("SGP fit error");
+        let x = array![[0.], [2.], [4.], [10.]];
+        let (epistemic, aleatoric) = sgp.predict_variance_components(&x.view()).unwrap();
+        assert_abs_diff_eq!(
+            &epistemic + &aleatoric,
+            sgp.predict_var(&x.view()).unwrap(),
+            epsilon = 1e-12
+        );
+        assert!(aleatoric.iter().all(|v| *v > 0.));
+        // epistemic uncertainty is reduced near data
+        assert!(epistemic[[1, 0]] < epistemic[[3, 0]]);
+    }
+
+    #[test]
+    fn test_train_outputs() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits).sample(20);
+        let y1 = xsinx(&xt);
+        let y2 = xt.column(0).mapv(|v| (v / 5.).cos());
+        let yt = ndarray::stack![Axis(1), y1, y2];
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train_outputs(&xt.view(), &yt.view())
+            .expect("GP fit error");
+        assert_eq!(gp.n_outputs(), 2);
+
+        let xv = Lhs::new(&xlimits).sample(20);
+        let yv = ndarray::stack![Axis(1), xsinx(&xv), xv.column(0).mapv(|v| (v / 5.).cos())];
+        let ytest = gp.predict(&xv.view()).unwrap();
+        assert_eq!(ytest.dim(), (20, 2));
+        for k in 0..2 {
+            let err = ytest.column(k).l2_dist(&yv.column(k)).unwrap() / yv.column(k).norm_l2();
+            assert_abs_diff_eq!(err, 0., epsilon = 2e-1);
+        }
+        assert_eq!(gp.predict_var(&xv.view()).unwrap().dim(), (20, 2));
+    }
+
+    #[test]
+    fn test_train_weighted_outputs() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(30);
+        // outputs of disparate scales: a large smooth one and a small wiggly one
+        let outputs = |x: &Array2<f64>| {
+            ndarray::stack![
+                Axis(1),
+                x.column(0).mapv(|v| 100. * (v / 10.).sin()),
+                x.column(0).mapv(|v| 1e-3 * (v * 1.5).sin())
+            ]
+        };
+        let yt = outputs(&xt);
+        let xv = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(0))
+            .sample(50);
+        let yv = outputs(&xv);
+        let rel_errors = |gp: &MultiOutputSurrogate| {
+            let ytest = gp.predict(&xv.view()).unwrap();
+            (0..2)
+                .map(|k| ytest.column(k).l2_dist(&yv.column(k)).unwrap() / yv.column(k).norm_l2())
+                .collect::<Vec<_>>()
+        };
+
+        let params = GpConstantSquaredExponentialSurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ));
+        let first = params
+            .train_weighted_outputs(&xt.view(), &yt.view(), &array![1., 0.].view())
+            .expect("GP fit error");
+        let balanced = params
+            .train_weighted_outputs(&xt.view(), &yt.view(), &array![1., 1.].view())
+            .expect("GP fit error");
+        let (first_err, balanced_err) = (rel_errors(&first), rel_errors(&balanced));
+        // outputs are balanced by default
+        let default = params
+            .train_outputs(&xt.view(), &yt.view())
+            .expect("GP fit error");
+        assert_abs_diff_eq!(
+            default.predict(&xv.view()).unwrap(),
+            balanced.predict(&xv.view()).unwrap(),
+            epsilon = 1e-12
+        );
+        println!("relative errors: first output only {first_err:?}, balanced {balanced_err:?}");
+        // hyperparameters fitted to the smooth output poorly fit the wiggly one
+        assert!(balanced_err[1] < first_err[1]);
+        assert!(balanced_err.iter().all(|e| *e < 0.1));
+        // shared hyperparameters maximize the weighted sum of the outputs likelihoods
+        let balanced_likelihood = balanced
+            .weighted_likelihood(&array![1., 1.].view())
+            .unwrap();
+        assert!(balanced_likelihood.is_finite());
+        assert!(balanced_likelihood >= first.weighted_likelihood(&array![1., 1.].view()).unwrap());
+        assert!(
+            first.weighted_likelihood(&array![1., 0.].view()).unwrap()
+                >= balanced
+                    .weighted_likelihood(&array![1., 0.].view())
+                    .unwrap()
+        );
+
+        assert!(params
+            .train_weighted_outputs(&xt.view(), &yt.view(), &array![1.].view())
+            .is_err());
+        assert!(params
+            .train_weighted_outputs(&xt.view(), &yt.view(), &array![0., 0.].view())
+            .is_err());
+    }
+
+    #[test]
+    fn test_predict_confidence_interval() {
+        assert_abs_diff_eq!(norm_quantile(0.975), 1.959964, epsilon = 1e-6);
+        assert_abs_diff_eq!(norm_quantile(0.5), 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(norm_quantile(0.001), -3.090232, epsilon = 1e-6);
+
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits).sample(10);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let xv = Lhs::new(&xlimits).sample(20);
+        let (lower, upper) = gp
+            .predict_confidence_interval(&xv.view(), 0.95)
+            .expect("Confidence interval");
+        let sigma = gp.predict_var(&xv.view()).unwrap().mapv(f64::sqrt);
+        assert_abs_diff_eq!(upper - lower, 3.92 * sigma, epsilon = 1e-2);
+
+        assert!(gp.predict_confidence_interval(&xv.view(), 0.).is_err());
+        assert!(gp.predict_confidence_interval(&xv.view(), 1.).is_err());
+        assert!(gp
+            .predict_confidence_interval(&xv.view(), f64::NAN)
+            .is_err());
+    }
+
+    // A surrogate with variances artificially shrunk
+    #[derive(Serialize, Deserialize)]
+    struct OverconfidentSurrogate(Box<dyn FullGpSurrogate>);
+
+    impl std::fmt::Display for OverconfidentSurrogate {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Overconfident{}", self.0)
+        }
+    }
+
+    #[typetag::serde]
+    impl GpSurrogate for OverconfidentSurrogate {
+        fn dims(&self) -> (usize, usize) {
+            self.0.dims()
+        }
+        fn kind(&self) -> String {
+            self.0.kind()
+        }
+        fn predict(&self, x: &ArrayView2<f64>) -> Result<Array1<f64>> {
+            self.0.predict(x)
+        }
+        fn predict_var(&self, x: &ArrayView2<f64>) -> Result<Array2<f64>> {
+            Ok(self.0.predict_var(x)? * 1e-4)
+        }
+        fn training_data(&self) -> (CowArray<'_, f64, Ix2>, CowArray<'_, f64, Ix2>) {
+            self.0.training_data()
+        }
+        fn save(&self, path: &str, format: GpFileFormat) -> Result<()> {
+            let bytes = match format {
+                GpFileFormat::Json => to_json_with_dtype(self as &dyn GpSurrogate, self.dtype())?,
+                GpFileFormat::Binary => bincode::serialize(self as &dyn GpSurrogate)?,
+            };
+            Ok(fs::write(path, bytes)?)
+        }
+    }
+
+    #[test]
+    fn test_nlpd() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(10);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let xv = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(0))
+            .sample(50);
+        let yv = xsinx(&xv).insert_axis(Axis(1));
+
+        let nlpd = gp.nlpd(&xv.view(), &yv.view()).expect("NLPD");
+        let overconfident = OverconfidentSurrogate(gp);
+        let overconfident_nlpd = overconfident.nlpd(&xv.view(), &yv.view()).expect("NLPD");
+        println!("NLPD = {nlpd}, overconfident NLPD = {overconfident_nlpd}");
+        assert!(nlpd.is_finite());
+        assert!(overconfident_nlpd > nlpd);
+        assert_eq!(overconfident.relevance().len(), 0);
+
+        assert!(overconfident.nlpd(&xv.view(), &yv.t()).is_err());
+        let empty = Array2::<f64>::zeros((0, 1));
+        assert!(overconfident.nlpd(&empty.view(), &empty.view()).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(12);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let xv = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(0))
+            .sample(100);
+        let yv = xsinx(&xv).insert_axis(Axis(1));
+
+        let metrics = gp.validate(&xv.view(), &yv.view()).expect("Validation");
+        println!("{metrics:?}");
+        assert!(metrics.rmse < 1.);
+        assert!(metrics.mae <= metrics.rmse);
+        assert!(metrics.r2 > 0.95 && metrics.r2 <= 1.);
+        assert!(metrics.coverage > 0.85);
+
+        assert!(gp.validate(&xv.view(), &yv.t()).is_err());
+        let empty = Array2::<f64>::zeros((0, 1));
+        assert!(gp.validate(&empty.view(), &empty.view()).is_err());
+
+        // constant test outputs
+        let metrics = gp
+            .validate(&xv.view(), &Array2::from_elem((100, 1), 1.).view())
+            .expect("Validation");
+        assert_eq!(metrics.r2, 0.);
+    }
+
+    #[test]
+    fn test_make_surrogate_params_from_kind() {
+        let xt = array![[0.], [5.], [10.], [15.], [18.], [20.], [25.]];
+        let yt = xsinx(&xt).insert_axis(Axis(1));
+        let gp = make_surrogate_params_from_kind("Linear_Matern52")
+            .expect("Valid kind")
+            .train(&xt.view(), &yt.view())
+            .expect("GP fit error");
+        assert!(gp.to_string().starts_with("Linear_Matern52"));
+
+        assert!(make_surrogate_params_from_kind("Cubic_Matern52").is_err());
+        assert!(make_surrogate_params_from_kind("Linear").is_err());
+    }
+
+    #[test]
+    fn test_fixed_theta() {
+        let xlimits = array![[0., 25.], [-5., 5.]];
+        let xt = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(15);
+        let yt = (xt.column(0).mapv(f64::sin) + xt.column(1)).insert_axis(Axis(1));
+
+        let mut params = GpConstantSquaredExponentialSurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ));
+        params.fixed_theta(vec![0.1, 2.]);
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        assert_eq!(gp.theta(), array![0.1, 2.]);
+        assert!(gp.variance() > 0.);
+
+        params.fixed_theta(vec![0.1]);
+        assert!(params.train(&xt.view(), &yt.view()).is_err());
+
+        params.kpls_dim(Some(1));
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        assert_eq!(gp.theta(), array![0.1]);
+    }
+
+    #[test]
+    fn test_likelihood_profile() {
+        let xt = array![[0.], [5.], [10.], [15.], [18.], [20.], [25.]];
+        let yt = xsinx(&xt).insert_axis(Axis(1));
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.view())
+            .expect("GP fit error");
+        let theta_grid = ndarray::stack![Axis(0), gp.theta().mapv(|v| v / 10.), *gp.theta()];
+        let profile = gp
+            .likelihood_profile(&theta_grid.view())
+            .expect("Likelihood profile");
+        assert_abs_diff_eq!(profile[1], gp.likelihood(), epsilon = 1e-10);
+        assert!(profile[0] < profile[1]);
+    }
+
+    #[test]
+    fn test_pls_rotation() {
+        let xlimits = Array2::from_shape_fn((5, 2), |(_, j)| if j == 0 { -1. } else { 1. });
+        let xt = Lhs::new(&xlimits)
+            .with_rng(Xoshiro256Plus::seed_from_u64(42))
+            .sample(20);
+        let yt = (xt.column(0).mapv(f64::sin) + xt.column(3)).insert_axis(Axis(1));
+
+        let mut params = GpConstantSquaredExponentialSurrogateParams::new(GpParams::new(
+            ConstantMean::default(),
+            SquaredExponentialCorr::default(),
+        ));
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        assert!(gp.pls_rotation().is_none());
+
+        params.kpls_dim(Some(2));
+        let gp = params.train(&xt.view(), &yt.view()).expect("GP fit error");
+        let w_star = gp.pls_rotation().expect("PLS rotation");
+        assert_eq!(w_star.dim(), (5, 2));
+    }
+
+    #[test]
+    fn test_predict_into() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits).sample(10);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let xv = Lhs::new(&xlimits).sample(20);
+        let expected = gp.predict(&xv.view()).unwrap();
+
+        let mut out = Array1::zeros(20);
+        for _ in 0..2 {
+            gp.predict_into(&xv.view(), &mut out.view_mut())
+                .expect("Prediction");
+            assert_abs_diff_eq!(out, expected, epsilon = 1e-9);
+        }
+        let mut out = Array1::zeros(19);
+        assert!(gp.predict_into(&xv.view(), &mut out.view_mut()).is_err());
+
+        let inducings = egobox_gp::Inducings::Located(array![[0.], [10.], [20.]]);
+        let sgp = make_sgp_surrogate_params!(SquaredExponential, inducings)
+            .train(&xt.view(), &xsinx(&xt).insert_axis(Axis(1)).view())
+            .expect("SGP fit error");
+        let mut out = Array1::zeros(20);
+        sgp.predict_into(&xv.view(), &mut out.view_mut())
+            .expect("Prediction");
+        assert_abs_diff_eq!(out, sgp.predict(&xv.view()).unwrap(), epsilon = 1e-9);
+        let mut out = Array1::zeros(19);
+        assert!(sgp.predict_into(&xv.view(), &mut out.view_mut()).is_err());
+    }
+
+    #[test]
+    fn test_train_non_finite_output() {
+        let xt = array![[0.], [5.], [10.], [15.], [20.]];
+        let mut yt = xsinx(&xt).insert_axis(Axis(1));
+        yt[[3, 0]] = f64::NAN;
+        let err = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.view())
+            .err()
+            .expect("Training with NaN should fail");
+        assert!(err.to_string().contains("y[3] should be finite"), "{err}");
+    }
+
+    #[test]
+    fn test_as_value_fn() {
+        let xlimits = array![[0., 25.]];
+        let xt = Lhs::new(&xlimits).sample(10);
+        let yt = xsinx(&xt);
+        let gp = make_surrogate_params!(Constant, SquaredExponential)
+            .train(&xt.view(), &yt.insert_axis(Axis(1)).view())
+            .expect("GP fit error");
+        let f = gp.as_value_fn();
+        let xv = Lhs::new(&xlimits).sample(20);
+        let expected = gp.predict(&xv.view()).unwrap();
+        for (x, y) in xv.rows().into_iter().zip(expected.iter()) {
+            assert_abs_diff_eq!(f(&x), *y, epsilon = 1e-12);
+        }
+    }
+
     #[test]
     fn test_load_fail() {
         let gp = load("notfound.json", GpFileFormat::Json);
@@ -8,6 +8,7 @@ use egobox_gp::correlation_models::{
 #[allow(unused_imports)]
 use egobox_gp::mean_models::{ConstantMean, LinearMean, QuadraticMean};
 use linfa::Float;
+use ndarray::Array1;
 use std::fmt::Display;
 
 #[cfg(feature = "serializable")]
@@ -37,6 +38,78 @@ impl<F: Float> Display for Recombination<F> {
     }
 }
 
+/// Enumeration of methods used to select the number of clusters in automatic mode
+/// (i.e. when number of clusters is set to 0)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+pub enum ClusterSelection {
+    /// Number of clusters giving the smallest cross-validation error of the mixture of experts
+    #[default]
+    CrossValidation,
+    /// Number of clusters giving the smallest Bayesian Information Criterion (BIC)
+    /// of the gaussian mixture fitted on training data, experts are not involved.
+    Bic,
+}
+
+/// Enumeration of initialization methods of the gaussian mixture used to cluster training data.
+/// Given a random generator seed (see [`with_rng()`](egobox_moe::GpMixtureParams::with_rng)),
+/// both methods give reproducible clusterings.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+pub enum GmmInit {
+    /// Responsibilities initialized from a k-means clustering
+    #[default]
+    KMeans,
+    /// Responsibilities initialized randomly
+    Random,
+}
+
+/// Enumeration of spaces where the gaussian mixture used to cluster training data is fitted.
+/// Whatever the space, experts are trained on inputs only and training points are assigned
+/// to clusters from their inputs (i.e. using the mixture marginal in the input space).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+pub enum ClusteringSpace {
+    /// Inputs and output concatenated as is
+    #[default]
+    Joint,
+    /// Inputs and output concatenated, each component being standardized so that output
+    /// variations weigh as much as input ones: clusters align with regions of similar output behavior
+    StandardizedJoint,
+    /// Inputs only: clusters only depend on the inputs geometry
+    Input,
+}
+
+/// Enumeration of covariance structures of the gaussian mixture components
+/// (see [`GaussianMixture::covariance_type()`](crate::GaussianMixture::covariance_type)).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+pub enum CovarianceType {
+    /// Each component has its own full covariance matrix
+    #[default]
+    Full,
+    /// All components share the same full covariance matrix
+    Tied,
+}
+
+/// Regularization of the covariance matrices estimated by EM iterations of the gaussian mixture
+/// (see [`GaussianMixture::covariance_regularizer()`](crate::GaussianMixture::covariance_regularizer)),
+/// the covariance S of a cluster is shrunk toward the scaled identity `mu.I` with `mu = tr(S) / nx`
+/// as `(1 - rho).S + rho.mu.I` which improves its conditioning when the cluster has few points
+/// with regards to its dimension.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serializable", derive(Serialize, Deserialize))]
+pub enum CovarianceRegularizer {
+    /// No shrinkage, only a small constant is added to the covariance diagonal
+    #[default]
+    None,
+    /// Shrinkage with the given intensity rho in [0, 1]
+    Shrinkage(f64),
+    /// Shrinkage intensity estimated per cluster with the Oracle Approximating Shrinkage
+    /// formula (Chen et al., 2010), an improvement of the Ledoit-Wolf estimate under gaussian assumption
+    Oas,
+}
+
 bitflags! {
     /// Flags to specify tested regression models during experts selection (see [`regression_spec()`](egobox_moe::GpMixtureParams::regression_spec)).
     ///
@@ -82,7 +155,13 @@ bitflags! {
         const MATERN32 = 0x04;
         /// Matern 5/2 correlation model
         const MATERN52 = 0x08;
-        /// All correlation models available
+        /// Sum of matern 5/2 and squared exponential correlation models (not part of `ALL`)
+        const MATERN52PLUSSQUAREDEXPONENTIAL = 0x10;
+        /// Sum of squared exponential and absolute exponential correlation models (not part of `ALL`)
+        const SQUAREDEXPONENTIALPLUSABSOLUTEEXPONENTIAL = 0x20;
+        /// Product of squared exponential and absolute exponential correlation models (not part of `ALL`)
+        const SQUAREDEXPONENTIALTIMESABSOLUTEEXPONENTIAL = 0x40;
+        /// All base correlation models available
         const ALL = CorrelationSpec::SQUAREDEXPONENTIAL.bits()
                     | CorrelationSpec::ABSOLUTEEXPONENTIAL.bits()
                     | CorrelationSpec::MATERN32.bits()
@@ -121,11 +200,64 @@ impl Clustering {
     }
 }
 
+/// Training diagnostics of an expert of a mixture (see [`GpMixture::expert_report()`](crate::GpMixture::expert_report))
+#[derive(Clone, Debug)]
+pub struct ExpertInfo {
+    /// Number of training points of the expert cluster
+    pub cluster_size: usize,
+    /// Expert kind (regression and correlation models) as displayed, e.g. `Constant_SquaredExponential`
+    pub kind: String,
+    /// Fitted hyperparameters
+    pub theta: Array1<f64>,
+    /// Number of PLS components if any
+    pub kpls_dim: Option<usize>,
+    /// Leave-one-out cross validation score of the expert on its cluster
+    pub loo_error: f64,
+}
+
+/// Metrics of a surrogate validated against a test set (see [`GpSurrogate::validate()`](crate::GpSurrogate::validate))
+#[derive(Clone, Copy, Debug)]
+pub struct ValidationMetrics {
+    /// Root mean squared error
+    pub rmse: f64,
+    /// Mean absolute error
+    pub mae: f64,
+    /// Coefficient of determination
+    pub r2: f64,
+    /// Fraction of test outputs lying within the 95% confidence interval of the predictions
+    pub coverage: f64,
+}
+
 /// A trait for Mixture of GP surrogates with derivatives using clustering
 pub trait MixtureGpSurrogate: Clustered + GpSurrogate + GpSurrogateExt {
     fn experts(&self) -> &Vec<Box<dyn FullGpSurrogate>>;
 }
 
+/// Floating point precision of surrogate computations,
+/// written as the `dtype` field of surrogates saved in json format
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(
+    feature = "serializable",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Dtype {
+    /// Single precision
+    F32,
+    /// Double precision
+    #[default]
+    F64,
+}
+
+impl Display for Dtype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dtype::F32 => write!(f, "f32"),
+            Dtype::F64 => write!(f, "f64"),
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 /// An enumeration of Gpx available file format
 pub enum GpFileFormat {
@@ -10,6 +10,9 @@
 //! See the [tutorial notebook](https://github.com/relf/egobox/doc/Egor_Tutorial.ipynb) for usage.
 //!
 
+// pyo3 0.22 macro expansion of early-returned errors triggers this lint
+#![allow(clippy::useless_conversion)]
+
 use crate::types::*;
 use egobox_ego::find_best_result_index;
 use ndarray::{concatenate, Array1, Array2, ArrayView2, Axis};
@@ -107,7 +110,9 @@ pub(crate) fn to_specs(py: Python, xlimits: Vec<Vec<f64>>) -> PyResult<PyObject>
 ///
 ///     infill_optimizer (InfillOptimizer enum)
 ///         Internal optimizer used to optimize infill criteria.
-///         Can be either InfillOptimizer.COBYLA or InfillOptimizer.SLSQP
+///         Can be either InfillOptimizer.COBYLA, InfillOptimizer.SLSQP or InfillOptimizer.LBFGS
+///         (the latter uses the analytic infill criterion gradient, SLSQP is used instead
+///         when there are constraint functions)
 ///
 ///     kpls_dim (0 < int < nx)
 ///         Number of components to be used when PLS projection is used (a.k.a KPLS method).
@@ -411,6 +416,7 @@ impl Egor {
         match self.infill_optimizer {
             InfillOptimizer::Cobyla => egobox_ego::InfillOptimizer::Cobyla,
             InfillOptimizer::Slsqp => egobox_ego::InfillOptimizer::Slsqp,
+            InfillOptimizer::Lbfgs => egobox_ego::InfillOptimizer::Lbfgs,
         }
     }
 
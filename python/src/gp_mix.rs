@@ -425,7 +425,7 @@ impl Gpx {
         &self,
         py: Python<'py>,
     ) -> (Bound<'py, PyArray2<f64>>, Bound<'py, PyArray1<f64>>) {
-        let (xdata, ydata) = self.0.training_data();
+        let (xdata, ydata) = CrossValScore::training_data(self.0.as_ref());
         (
             xdata.to_owned().into_pyarray_bound(py),
             ydata.to_owned().into_pyarray_bound(py),
@@ -92,7 +92,7 @@ pub fn sampling(
 #[pyfunction]
 #[pyo3(signature = (xspecs, n_samples, seed=None))]
 pub(crate) fn lhs(
-    py: Python,
+    py: Python<'_>,
     xspecs: PyObject,
     n_samples: usize,
     seed: Option<u64>,
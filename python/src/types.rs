@@ -70,6 +70,7 @@ pub(crate) enum ParInfillStrategy {
 pub(crate) enum InfillOptimizer {
     Cobyla = 1,
     Slsqp = 2,
+    Lbfgs = 3,
 }
 
 #[pyclass]